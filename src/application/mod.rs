@@ -1,5 +1,7 @@
 pub mod ports;
+pub mod scheduler;
 pub mod usecases;
 
 pub use ports::*;
+pub use scheduler::*;
 pub use usecases::*;
\ No newline at end of file
@@ -12,6 +12,8 @@ pub enum AppError {
     Notifier(String),
     #[error("invalid config: {0}")]
     Config(String),
+    #[error("rate limited: {0}")]
+    RateLimited(String),
 }
 
 pub type AppResult<T> = Result<T, AppError>;
@@ -32,6 +34,8 @@ pub struct EventRecordQuery {
     pub label: Option<String>,
     pub event_type: Option<crate::domain::EventType>,
     pub subject: Option<String>,
+    /// `Event::meta["bump"]`, only set on versioned targets
+    pub bump: Option<crate::domain::VersionBump>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -41,12 +45,34 @@ pub struct EventQuery {
     pub label: Option<String>,
     pub event_type: Option<crate::domain::EventType>,
     pub subject: Option<String>,
+    /// `Event::meta["bump"]`, only set on versioned targets
+    pub bump: Option<crate::domain::VersionBump>,
 }
 
 /// Produce an Event if a change is detected for a target.
 #[async_trait]
 pub trait WatchProvider: Send + Sync {
     async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>>;
+
+    /// Called once a change is confirmed (`old_value` filled in) to attach details
+    /// that depend on the previous value, e.g. the commit range of a branch move.
+    async fn enrich(&self, _target: &WatchTarget, _event: &mut Event) -> AppResult<()> {
+        Ok(())
+    }
+
+    /// For targets that `WatchKind::reports_items()`: every item currently listed
+    /// upstream, oldest first, one event each (deduplicated by `event_id`).
+    async fn check_items(&self, _target: &WatchTarget) -> AppResult<Vec<Event>> {
+        Ok(vec![])
+    }
+
+    /// Called after the result of the last check was handled (event notified,
+    /// or recorded as baseline / unchanged). Providers keeping their own state
+    /// next to the last value (page snapshots, ...) save it here, so a failed
+    /// notification is retried against the previous state.
+    async fn commit(&self, _target: &WatchTarget) -> AppResult<()> {
+        Ok(())
+    }
 }
 
 /// Persist events + idempotency + query.
@@ -78,6 +104,55 @@ pub trait EventStore: Send + Sync {
 
     async fn get_last_notified(&self, scope_key: &str) -> AppResult<Option<i64>>;
     async fn set_last_notified(&self, scope_key: &str, epoch_seconds: i64) -> AppResult<()>;
+
+    // target 最近一次观察到的值: 用于填充 old_value, 以及首次观察时记录 baseline
+    async fn get_last_value(&self, target_id: &str) -> AppResult<Option<String>>;
+    async fn set_last_value(&self, target_id: &str, value: &str) -> AppResult<()>;
+    // last value + 修订号 (每次 set_last_value 加 1): 同一个值回来时 (A -> B -> A) 也能区分两次变化
+    async fn get_last_value_revision(&self, target_id: &str) -> AppResult<Option<(String, u64)>>;
+}
+
+/// Validators + body of the last successful response for a conditional GET.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpCacheEntry {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: String,
+}
+
+/// Persist ETag / Last-Modified per cache key (usually the target id).
+#[async_trait]
+pub trait HttpCacheStore: Send + Sync {
+    async fn get_http_cache(&self, key: &str) -> AppResult<Option<HttpCacheEntry>>;
+    async fn set_http_cache(&self, key: &str, entry: &HttpCacheEntry) -> AppResult<()>;
+}
+
+/// State a provider keeps between checks, per target id (e.g. the full tag list
+/// behind a hashed value). Opaque to the store; providers write it from
+/// `WatchProvider::commit`, once the event was handled.
+#[async_trait]
+pub trait ProviderStateStore: Send + Sync {
+    async fn get_provider_state(&self, target_id: &str) -> AppResult<Option<String>>;
+    async fn set_provider_state(&self, target_id: &str, state: &str) -> AppResult<()>;
+}
+
+/// Upstream API quota as last reported by the upstream.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RateLimitSnapshot {
+    pub source: String, // e.g. "github" or "github@ghe.example.com"
+    /// quota bucket within the source, e.g. GitHub's "core" / "search"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    pub reset_epoch: Option<i64>,
+    /// set while requests are held back until this time
+    pub paused_until_epoch: Option<i64>,
+}
+
+/// Expose rate-limit state of upstream clients (for logs / HTTP API).
+pub trait RateLimitReporter: Send + Sync {
+    fn rate_limits(&self) -> Vec<RateLimitSnapshot>;
 }
 
 /// Provide list of targets (from config/DB)
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use crate::domain::{Schedule, WatchTarget};
+
+/// Tracks when each target is next due.
+///
+/// Targets never seen before are due immediately; after a check the next due
+/// time comes from the target's own `Schedule` (or the default interval) plus jitter.
+pub struct Scheduler {
+    default_interval_seconds: u64,
+    next_due: HashMap<String, i64>,
+}
+
+impl Scheduler {
+    pub fn new(default_interval_seconds: u64) -> Self {
+        Self {
+            default_interval_seconds,
+            next_due: HashMap::new(),
+        }
+    }
+
+    /// Targets due at `now_epoch`, in the given order.
+    pub fn due(&self, targets: &[WatchTarget], now_epoch: i64) -> Vec<WatchTarget> {
+        targets
+            .iter()
+            .filter(|t| self.next_due.get(&t.id).is_none_or(|due| *due <= now_epoch))
+            .cloned()
+            .collect()
+    }
+
+    /// Record that `targets` were checked at `now_epoch` and compute their next due time.
+    pub fn mark_checked(&mut self, targets: &[WatchTarget], now_epoch: i64) {
+        for t in targets {
+            let schedule = self.schedule_of(t);
+            let jitter = match schedule.jitter_seconds {
+                0 => 0,
+                j => rand::random_range(0..=j) as i64,
+            };
+            let due = schedule.next_due(now_epoch).saturating_add(jitter);
+            self.next_due.insert(t.id.clone(), due);
+        }
+    }
+
+    /// Earliest due time among `targets` (now if any has never been checked).
+    pub fn next_wake(&self, targets: &[WatchTarget], now_epoch: i64) -> i64 {
+        targets
+            .iter()
+            .map(|t| self.next_due.get(&t.id).copied().unwrap_or(now_epoch))
+            .min()
+            .unwrap_or(now_epoch.saturating_add(self.default_interval_seconds as i64))
+    }
+
+    pub fn next_due_of(&self, target_id: &str) -> Option<i64> {
+        self.next_due.get(target_id).copied()
+    }
+
+    fn schedule_of(&self, target: &WatchTarget) -> Schedule {
+        target
+            .schedule
+            .clone()
+            .unwrap_or_else(|| Schedule::interval(self.default_interval_seconds))
+    }
+}
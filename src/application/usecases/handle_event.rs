@@ -1,5 +1,5 @@
 use crate::application::{AppResult, EventStore, Notifier};
-use crate::domain::Event;
+use crate::domain::{Event, WatchTarget};
 
 pub struct HandleEventUseCase<'a> {
     pub store: &'a dyn EventStore,
@@ -9,12 +9,8 @@ pub struct HandleEventUseCase<'a> {
 }
 
 impl<'a> HandleEventUseCase<'a> {
-    pub async fn execute(
-        &self,
-        event: &Event,
-        target_id: &str,
-        labels: &[String],
-    ) -> AppResult<()> {
+    pub async fn execute(&self, event: &Event, target: &WatchTarget) -> AppResult<()> {
+        let target_id = target.id.as_str();
         // 1) dedup by event_id
         if self.store.has_seen(&event.event_id).await? {
             return Ok(());
@@ -25,7 +21,7 @@ impl<'a> HandleEventUseCase<'a> {
         let record = crate::application::EventRecord {
             event: event.clone(),
             target_id: target_id.to_string(),
-            labels: labels.to_vec(),
+            labels: target.labels.clone(),
             detected_at_epoch: now_epoch,
         };
         let rowid = self.store.upsert_event_record_return_rowid(&record).await?;
@@ -35,8 +31,15 @@ impl<'a> HandleEventUseCase<'a> {
             let _ = p.publish(rowid, &record).await;
         }
 
-        // 3) cooldown policy (ByTargetAndType)
-        if self.cooldown_seconds > 0 {
+        // 3) version policy: 低于 notify_on 的变化只记录不通知
+        if let Some(policy) = &target.version_policy
+            && !policy.should_notify(event)
+        {
+            return Ok(());
+        }
+
+        // 4) cooldown policy (ByTargetAndType); 高优先级事件 (安全公告) 不受限
+        if self.cooldown_seconds > 0 && !event.is_high_priority() {
             let scope_key = format!("{}|{:?}", target_id, event.event_type);
             let now = epoch_seconds();
 
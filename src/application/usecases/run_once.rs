@@ -1,35 +1,149 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use futures_util::future::join_all;
+use tokio::sync::Semaphore;
 use tracing::{info, warn};
 
 use crate::application::usecases::HandleEventUseCase;
-use crate::application::{AppResult, TargetRepository, WatchProvider};
+use crate::application::{AppError, AppResult, TargetRepository, WatchProvider};
+use crate::domain::{Event, VersionBump, WatchTarget};
+
+/// Bounds how many checks run at once within a cycle.
+#[derive(Clone, Debug)]
+pub struct ConcurrencyLimits {
+    /// max checks in flight across all sources
+    pub global: usize,
+    /// max checks in flight per source, keyed by `Source` display name ("github", "npm", ...)
+    pub per_source: HashMap<String, usize>,
+    /// a single check is abandoned after this long (0 means no timeout)
+    pub check_timeout_seconds: u64,
+}
+
+impl Default for ConcurrencyLimits {
+    fn default() -> Self {
+        Self {
+            global: 8,
+            per_source: HashMap::new(),
+            check_timeout_seconds: 30,
+        }
+    }
+}
+
+/// What one target check produced.
+enum Checked {
+    /// single-value target: an event if the value is known (changed or not)
+    Value(Option<Event>),
+    /// item-list target (`WatchKind::reports_items`): all items, oldest first
+    Items(Vec<Event>),
+}
 
 pub struct RunOnceUseCase<'a> {
     pub targets: &'a dyn TargetRepository,
     pub provider: &'a dyn WatchProvider,
     pub handle_event: HandleEventUseCase<'a>,
+    pub concurrency: ConcurrencyLimits,
 }
 
 impl<'a> RunOnceUseCase<'a> {
     pub async fn execute(&self) -> AppResult<()> {
         let targets = self.targets.list_enabled_targets().await?;
-        for t in targets {
-            if !t.enabled {
-                continue;
+        self.execute_targets(&targets).await
+    }
+
+    /// Check only the given targets (e.g. the ones the scheduler says are due).
+    pub async fn execute_targets(&self, targets: &[WatchTarget]) -> AppResult<()> {
+        let targets: Vec<&WatchTarget> = targets.iter().filter(|t| t.enabled).collect();
+
+        // 1) 并发检查 (受全局 + 按 source 的并发上限约束)
+        let global = Semaphore::new(self.concurrency.global.max(1));
+        let per_source: HashMap<String, Semaphore> = self
+            .concurrency
+            .per_source
+            .iter()
+            .map(|(source, n)| (source.clone(), Semaphore::new((*n).max(1))))
+            .collect();
+
+        let checks = targets.iter().map(|&t| {
+            let source_sem = per_source.get(&t.kind.source().to_string());
+            let global = &global;
+            async move {
+                // 先拿 source 的 permit, 避免占着全局 permit 排队
+                let _source_permit = match source_sem {
+                    Some(s) => Some(s.acquire().await.expect("semaphore closed")),
+                    None => None,
+                };
+                let _global_permit = global.acquire().await.expect("semaphore closed");
+                self.check_with_timeout(t).await
             }
+        });
+        // join_all 保持输入顺序, 结果按 target 顺序处理
+        let results = join_all(checks).await;
 
+        // 2) 按 target 顺序依次处理结果
+        for (t, result) in targets.into_iter().zip(results) {
             let target_id = t.id.clone();
 
-            match self.provider.check(&t).await {
-                Ok(Some(event)) => {
+            match result {
+                Ok(Checked::Items(items)) => self.handle_items(t, items).await,
+                Ok(Checked::Value(Some(event))) => {
+                    // 不符合 version policy 的值当作没看见: 不产生事件, 也不记为 last value
+                    if let Some(policy) = &t.version_policy
+                        && !policy.accepts(&event.new_value)
+                    {
+                        continue;
+                    }
+                    let mut event = match self.compare_with_last(&target_id, event).await {
+                        Ok(Some(e)) => e,
+                        Ok(None) => {
+                            self.commit_provider_state(t).await;
+                            continue;
+                        }
+                        Err(e) => {
+                            warn!(target_id = %target_id, error = %e, "load last value failed");
+                            continue;
+                        }
+                    };
+
+                    if t.kind.is_versioned()
+                        && let Some(bump) = event
+                            .old_value
+                            .as_deref()
+                            .and_then(|old| VersionBump::classify(old, &event.new_value))
+                    {
+                        event
+                            .meta
+                            .insert(VersionBump::META_KEY.to_string(), bump.as_str().to_string());
+                    }
+
+                    // 补充细节失败不影响事件本身
+                    if let Err(e) = self.enrich_with_timeout(t, &mut event).await {
+                        warn!(target_id = %target_id, error = %e, "enrich event failed");
+                    }
+
                     info!(target_id = %target_id, event_id = %event.event_id, "event detected");
-                    let labels = t.labels.clone();
-                    if let Err(e) = self.handle_event.execute(&event, &target_id, &labels).await {
+                    if let Err(e) = self.handle_event.execute(&event, t).await {
                         warn!(target_id = %target_id, error = %e, "handle event failed");
+                        continue;
+                    }
+                    // 只有处理成功才推进 last value, 失败时下一轮会重试
+                    if let Err(e) = self
+                        .handle_event
+                        .store
+                        .set_last_value(&target_id, &event.new_value)
+                        .await
+                    {
+                        warn!(target_id = %target_id, error = %e, "save last value failed");
+                        continue;
                     }
+                    self.commit_provider_state(t).await;
                 }
-                Ok(None) => {
+                Ok(Checked::Value(None)) => {
                     // 正常：无变化
                 }
+                Err(AppError::RateLimited(msg)) => {
+                    info!(target_id = %target_id, reason = %msg, "check skipped: rate limited");
+                }
                 Err(e) => {
                     warn!(target_id = %target_id, error = %e, "provider check failed");
                 }
@@ -37,4 +151,139 @@ impl<'a> RunOnceUseCase<'a> {
         }
         Ok(())
     }
+
+    async fn check_with_timeout(&self, target: &WatchTarget) -> AppResult<Checked> {
+        let check = async {
+            if target.kind.reports_items() {
+                self.provider.check_items(target).await.map(Checked::Items)
+            } else {
+                self.provider.check(target).await.map(Checked::Value)
+            }
+        };
+        let secs = self.concurrency.check_timeout_seconds;
+        if secs == 0 {
+            return check.await;
+        }
+        match tokio::time::timeout(Duration::from_secs(secs), check).await {
+            Ok(r) => r,
+            Err(_) => Err(AppError::Provider(format!("check timed out after {secs}s"))),
+        }
+    }
+
+    /// 列表型 target (feed): 每个没见过的条目一个事件, last value 记最新条目
+    /// - 首次观察: 当前条目全部记为已见 (baseline), 不产生事件
+    /// - 处理失败: 停在该条目, 下一轮从它开始重试
+    async fn handle_items(&self, t: &WatchTarget, items: Vec<Event>) {
+        let target_id = t.id.as_str();
+        let store = self.handle_event.store;
+        let newest = items
+            .last()
+            .map(|e| e.new_value.clone())
+            .unwrap_or_default();
+
+        match store.get_last_value(target_id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                for e in &items {
+                    if let Err(err) = store.mark_seen(&e.event_id).await {
+                        warn!(target_id = %target_id, error = %err, "record baseline failed");
+                        return;
+                    }
+                }
+                if let Err(e) = store.set_last_value(target_id, &newest).await {
+                    warn!(target_id = %target_id, error = %e, "save last value failed");
+                    return;
+                }
+                info!(target_id = %target_id, items = items.len(), "baseline recorded");
+                self.commit_provider_state(t).await;
+                return;
+            }
+            Err(e) => {
+                warn!(target_id = %target_id, error = %e, "load last value failed");
+                return;
+            }
+        }
+
+        for event in &items {
+            match store.has_seen(&event.event_id).await {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(e) => {
+                    warn!(target_id = %target_id, error = %e, "load seen state failed");
+                    return;
+                }
+            }
+            info!(target_id = %target_id, event_id = %event.event_id, "event detected");
+            if let Err(e) = self.handle_event.execute(event, t).await {
+                warn!(target_id = %target_id, error = %e, "handle event failed");
+                return;
+            }
+        }
+        if !items.is_empty()
+            && let Err(e) = store.set_last_value(target_id, &newest).await
+        {
+            warn!(target_id = %target_id, error = %e, "save last value failed");
+            return;
+        }
+        self.commit_provider_state(t).await;
+    }
+
+    /// 结果处理完 (通知成功 / baseline / 无变化) 后才让 provider 保存自己的状态,
+    /// 失败时下一轮仍和旧状态比较
+    async fn commit_provider_state(&self, t: &WatchTarget) {
+        if let Err(e) = self.provider.commit(t).await {
+            warn!(target_id = %t.id, error = %e, "save provider state failed");
+        }
+    }
+
+    async fn enrich_with_timeout(&self, target: &WatchTarget, event: &mut Event) -> AppResult<()> {
+        let secs = self.concurrency.check_timeout_seconds;
+        if secs == 0 {
+            return self.provider.enrich(target, event).await;
+        }
+        match tokio::time::timeout(
+            Duration::from_secs(secs),
+            self.provider.enrich(target, event),
+        )
+        .await
+        {
+            Ok(r) => r,
+            Err(_) => Err(AppError::Provider(format!(
+                "enrich timed out after {secs}s"
+            ))),
+        }
+    }
+
+    /// 与 target 上次观察到的值比较:
+    /// - 首次观察: 记录为 baseline, 不产生事件
+    /// - 值未变化: 不产生事件
+    /// - 值变化: 用上次的值填充 old_value, event id 按这次变化 (old -> new + 修订号) 生成;
+    ///   provider 只看新值的 id 在值回到旧状态时 (A -> B -> A) 会被当成已见过
+    async fn compare_with_last(
+        &self,
+        target_id: &str,
+        mut event: Event,
+    ) -> AppResult<Option<Event>> {
+        let store = self.handle_event.store;
+        match store.get_last_value_revision(target_id).await? {
+            None => {
+                store.set_last_value(target_id, &event.new_value).await?;
+                info!(target_id = %target_id, value = %event.new_value, "baseline recorded");
+                Ok(None)
+            }
+            Some((last, _)) if last == event.new_value => Ok(None),
+            Some((last, revision)) => {
+                event.event_id = Event::make_change_event_id(
+                    &event.event_type,
+                    &event.subject,
+                    target_id,
+                    revision,
+                    &last,
+                    &event.new_value,
+                );
+                event.old_value = Some(last);
+                Ok(Some(event))
+            }
+        }
+    }
 }
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 use super::Source;
@@ -6,8 +8,32 @@ use super::Source;
 pub enum EventType {
     GitHubRelease,
     GitHubBranch,
+    GitHubTag,
+    GitHubWorkflow,
+    GitHubSearchItem,
+    GitLabRelease,
+    GitLabBranch,
+    GiteaRelease,
+    GiteaBranch,
     NpmLatest,
+    NpmDistTag,
+    NpmDeprecated,
+    NpmUnpublished,
+    NpmModified,
+    CratesIoLatest,
+    CratesIoYanked,
+    PyPiLatest,
+    PyPiYanked,
+    MavenLatest,
+    GoModule,
+    OciDigest,
+    OciTags,
     WhatsAppWebVersion,
+    HttpJson,
+    HttpPage,
+    FeedEntry,
+    Advisory,
+    Custom,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -22,11 +48,37 @@ pub struct Event {
     pub occurred_at: Option<String>, // upstream time if known (RFC3339 string for now)
     pub detected_at: String, // local time (RFC3339 string for now)
     pub url: Option<String>,
+    /// provider-specific details, e.g. the commit range of a branch move
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub meta: BTreeMap<String, String>,
 }
 
+/// `Event::meta` key for urgency; "high" skips the notification cooldown
+pub const META_PRIORITY: &str = "priority";
+
 impl Event {
+    pub fn is_high_priority(&self) -> bool {
+        self.meta.get(META_PRIORITY).is_some_and(|p| p == "high")
+    }
+
     /// A simple deterministic id key. v1 uses a naive schema; can be upgraded to hashing later.
     pub fn make_event_id(event_type: &EventType, subject: &str, new_value: &str) -> String {
         format!("{:?}|{}|{}", event_type, subject, new_value)
     }
+
+    /// Id of a confirmed change of a single-value target: keyed by the
+    /// transition and the revision of the last value it replaces, so a value
+    /// coming back (A -> B -> A) is a new event, while re-checking the same
+    /// unhandled change (retry) keeps its id.
+    pub fn make_change_event_id(
+        event_type: &EventType,
+        subject: &str,
+        target_id: &str,
+        revision: u64,
+        old_value: &str,
+        new_value: &str,
+    ) -> String {
+        let change = format!("{target_id}#{revision}:{old_value}->{new_value}");
+        Self::make_event_id(event_type, subject, &change)
+    }
 }
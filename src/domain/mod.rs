@@ -2,8 +2,12 @@ pub mod types;
 pub mod event;
 pub mod watch_target;
 pub mod policy;
+pub mod schedule;
+pub mod version;
 
 pub use types::*;
 pub use event::*;
 pub use watch_target::*;
-pub use policy::*;
\ No newline at end of file
+pub use policy::*;
+pub use schedule::*;
+pub use version::*;
\ No newline at end of file
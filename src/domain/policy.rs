@@ -1,3 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Event, VersionBump, VersionRange, is_prerelease_version};
+
 #[derive(Clone, Debug)]
 pub struct CooldownPolicy {
     pub cooldown_seconds: u64,
@@ -9,3 +13,42 @@ pub enum CooldownScope {
     ByTarget,        // same watch target
     ByTargetAndType, // same watch target + event type
 }
+
+/// Per-target rules for version-valued targets (`WatchKind::is_versioned`).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionPolicy {
+    /// pre-release values are ignored: never reported, never recorded as last value
+    pub ignore_prereleases: bool,
+    /// npm-style range (">=2 <3"); values outside it are ignored like pre-releases
+    pub range: Option<String>,
+    /// notify only for bumps at least this big; smaller ones are still recorded
+    pub notify_on: Option<VersionBump>,
+}
+
+impl VersionPolicy {
+    /// Whether a value observed upstream counts at all.
+    pub fn accepts(&self, value: &str) -> bool {
+        if self.ignore_prereleases && is_prerelease_version(value) {
+            return false;
+        }
+        match &self.range {
+            Some(range) => VersionRange::parse(range).is_ok_and(|r| r.matches(value)),
+            None => true,
+        }
+    }
+
+    /// Events whose bump couldn't be classified are always notified.
+    pub fn should_notify(&self, event: &Event) -> bool {
+        let Some(min) = self.notify_on else {
+            return true;
+        };
+        match event
+            .meta
+            .get(VersionBump::META_KEY)
+            .and_then(|b| VersionBump::parse(b))
+        {
+            Some(bump) => bump >= min,
+            None => true,
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// When a target should be checked. Targets without a schedule use the
+/// global `poll_interval_seconds`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Schedule {
+    pub trigger: Trigger,
+    /// random delay (0..=jitter_seconds) added to every next due time
+    pub jitter_seconds: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Trigger {
+    Interval {
+        seconds: u64,
+    },
+    /// 5-field (min hour dom month dow) or 6/7-field (with seconds/year) cron, in UTC
+    Cron {
+        expr: String,
+    },
+}
+
+impl Schedule {
+    pub fn interval(seconds: u64) -> Self {
+        Self {
+            trigger: Trigger::Interval { seconds },
+            jitter_seconds: 0,
+        }
+    }
+
+    pub fn cron(expr: &str) -> Result<Self, ScheduleError> {
+        parse_cron(expr)?;
+        Ok(Self {
+            trigger: Trigger::Cron {
+                expr: expr.trim().to_string(),
+            },
+            jitter_seconds: 0,
+        })
+    }
+
+    pub fn with_jitter(mut self, jitter_seconds: u64) -> Self {
+        self.jitter_seconds = jitter_seconds;
+        self
+    }
+
+    /// Next due time (epoch seconds) after a check at `after_epoch`, without jitter.
+    pub fn next_due(&self, after_epoch: i64) -> i64 {
+        match &self.trigger {
+            Trigger::Interval { seconds } => after_epoch.saturating_add((*seconds).max(1) as i64),
+            Trigger::Cron { expr } => {
+                let after = DateTime::<Utc>::from_timestamp(after_epoch, 0).unwrap_or_default();
+                parse_cron(expr)
+                    .ok()
+                    .and_then(|s| s.after(&after).next())
+                    .map(|t| t.timestamp())
+                    // 不会再触发的表达式(例如指定了过去的年份): 一天后再看
+                    .unwrap_or(after_epoch.saturating_add(86400))
+            }
+        }
+    }
+}
+
+fn parse_cron(expr: &str) -> Result<cron::Schedule, ScheduleError> {
+    let expr = expr.trim();
+    // cron crate 需要秒字段; 常见的 5 字段写法补上 "0"
+    let normalized = if expr.split_whitespace().count() == 5 {
+        format!("0 {}", expr)
+    } else {
+        expr.to_string()
+    };
+    cron::Schedule::from_str(&normalized)
+        .map_err(|e| ScheduleError::InvalidCron(format!("{expr}: {e}")))
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum ScheduleError {
+    #[error("invalid cron expression: {0}")]
+    InvalidCron(String),
+}
@@ -20,6 +20,20 @@ impl RepoId {
         })
     }
 
+    /// GitLab-style path where the namespace may be nested: "group/subgroup/project".
+    pub fn parse_nested(s: &str) -> Result<Self, RepoIdError> {
+        let (owner, name) = s
+            .rsplit_once('/')
+            .ok_or_else(|| RepoIdError::InvalidFormat(s.to_string()))?;
+        if name.is_empty() || owner.split('/').any(|p| p.is_empty()) {
+            return Err(RepoIdError::InvalidFormat(s.to_string()));
+        }
+        Ok(Self {
+            owner: owner.to_string(),
+            name: name.to_string(),
+        })
+    }
+
     pub fn as_str(&self) -> String {
         format!("{}/{}", self.owner, self.name)
     }
@@ -31,19 +45,153 @@ pub enum RepoIdError {
     InvalidFormat(String),
 }
 
+/// Code hosting platform a repository lives on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Forge {
+    #[default]
+    GitHub,
+    /// gitlab.com or self-managed GitLab
+    GitLab,
+    /// Gitea and its fork Forgejo (same API)
+    Gitea,
+}
+
+impl Forge {
+    /// "github" | "gitlab" | "gitea" (also "forgejo")
+    pub fn parse(s: &str) -> Result<Self, ForgeError> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "github" => Ok(Forge::GitHub),
+            "gitlab" => Ok(Forge::GitLab),
+            "gitea" | "forgejo" => Ok(Forge::Gitea),
+            _ => Err(ForgeError::Unknown(s.to_string())),
+        }
+    }
+
+    /// GitLab namespaces nest, the others are always owner/repo.
+    pub fn parse_repo(&self, s: &str) -> Result<RepoId, RepoIdError> {
+        match self {
+            Forge::GitLab => RepoId::parse_nested(s),
+            Forge::GitHub | Forge::Gitea => RepoId::parse(s),
+        }
+    }
+
+    pub fn source(&self) -> Source {
+        match self {
+            Forge::GitHub => Source::GitHub,
+            Forge::GitLab => Source::GitLab,
+            Forge::Gitea => Source::Gitea,
+        }
+    }
+}
+
+impl fmt::Display for Forge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.source().fmt(f)
+    }
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum ForgeError {
+    #[error("unknown forge: {0} (github/gitlab/gitea)")]
+    Unknown(String),
+}
+
+/// A container image repository: registry host + repository path,
+/// with Docker Hub's implicit defaults ("node" -> docker.io/library/node).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ImageRef {
+    pub registry: String,
+    pub repository: String,
+}
+
+impl ImageRef {
+    pub const DOCKER_HUB: &'static str = "docker.io";
+
+    /// Parse "[registry/]repository[:tag]"; returns the tag if one was given.
+    pub fn parse(s: &str) -> Result<(Self, Option<String>), ImageRefError> {
+        let s = s.trim();
+        if s.is_empty() || s.contains('@') {
+            return Err(ImageRefError::InvalidFormat(s.to_string()));
+        }
+        // 第一段含 "." / ":" 或是 localhost 才是 registry 主机
+        let (registry, rest) = match s.split_once('/') {
+            Some((first, rest))
+                if first.contains('.') || first.contains(':') || first == "localhost" =>
+            {
+                (first.to_string(), rest)
+            }
+            _ => (Self::DOCKER_HUB.to_string(), s),
+        };
+        let (repository, tag) = match rest.rsplit_once(':') {
+            Some((repo, tag)) if !tag.contains('/') => (repo, Some(tag.to_string())),
+            _ => (rest, None),
+        };
+        if repository.is_empty() || tag.as_deref() == Some("") {
+            return Err(ImageRefError::InvalidFormat(s.to_string()));
+        }
+        let repository = if registry == Self::DOCKER_HUB && !repository.contains('/') {
+            format!("library/{}", repository)
+        } else {
+            repository.to_string()
+        };
+        Ok((
+            Self {
+                registry,
+                repository,
+            },
+            tag,
+        ))
+    }
+
+    pub fn as_str(&self) -> String {
+        format!("{}/{}", self.registry, self.repository)
+    }
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum ImageRefError {
+    #[error("invalid image reference: {0} (expected [registry/]repository[:tag])")]
+    InvalidFormat(String),
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Source {
     GitHub,
+    GitLab,
+    Gitea,
     Npm,
+    CratesIo,
+    PyPi,
+    Maven,
+    Go,
+    Oci,
     WhatsAppWeb,
+    /// generic HTTP endpoints (http_json, http_page)
+    Http,
+    /// RSS / Atom feeds
+    Feed,
+    /// OSV-compatible vulnerability database
+    Osv,
+    Custom,
 }
 
 impl fmt::Display for Source {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Source::GitHub => write!(f, "github"),
+            Source::GitLab => write!(f, "gitlab"),
+            Source::Gitea => write!(f, "gitea"),
             Source::Npm => write!(f, "npm"),
+            Source::CratesIo => write!(f, "crates-io"),
+            Source::PyPi => write!(f, "pypi"),
+            Source::Maven => write!(f, "maven"),
+            Source::Go => write!(f, "go"),
+            Source::Oci => write!(f, "oci"),
             Source::WhatsAppWeb => write!(f, "whatsapp-web"),
+            Source::Http => write!(f, "http"),
+            Source::Feed => write!(f, "feed"),
+            Source::Osv => write!(f, "osv"),
+            Source::Custom => write!(f, "custom"),
         }
     }
 }
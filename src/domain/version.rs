@@ -0,0 +1,366 @@
+use std::cmp::Ordering;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Parse a version the way tags and registries usually spell it:
+/// optional leading "v", and missing minor/patch filled with 0 ("v2" -> 2.0.0).
+pub fn parse_version_lenient(s: &str) -> Option<semver::Version> {
+    let s = s.trim();
+    let s = s.strip_prefix(['v', 'V']).unwrap_or(s);
+    if let Ok(v) = semver::Version::parse(s) {
+        return Some(v);
+    }
+    // "1.2" / "1" (+ 可选的 -pre / +build)
+    let split_at = s.find(['-', '+']).unwrap_or(s.len());
+    let (core, rest) = s.split_at(split_at);
+    let parts: Vec<&str> = core.split('.').collect();
+    if parts.is_empty() || parts.len() > 2 || parts.iter().any(|p| p.parse::<u64>().is_err()) {
+        return None;
+    }
+    let mut padded = parts.join(".");
+    for _ in parts.len()..3 {
+        padded.push_str(".0");
+    }
+    semver::Version::parse(&format!("{padded}{rest}")).ok()
+}
+
+/// Compare two version strings leniently; unparsable ones sort first.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (parse_version_lenient(a), parse_version_lenient(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Greater,
+        (None, Some(_)) => Ordering::Less,
+        (None, None) => a.cmp(b),
+    }
+}
+
+/// Which names a watch accepts (tags, image tags, ...).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NameFilter {
+    /// `*` and `?` wildcards, e.g. "v1.*"
+    Glob {
+        pattern: String,
+    },
+    Regex {
+        pattern: String,
+    },
+}
+
+impl NameFilter {
+    pub fn validate(&self) -> Result<(), NameFilterError> {
+        self.compile().map(|_| ())
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.compile().map(|re| re.is_match(name)).unwrap_or(false)
+    }
+
+    pub fn compile(&self) -> Result<Regex, NameFilterError> {
+        let pattern = match self {
+            NameFilter::Glob { pattern } => glob_to_regex(pattern),
+            NameFilter::Regex { pattern } => pattern.clone(),
+        };
+        Regex::new(&pattern).map_err(|e| NameFilterError::Invalid(e.to_string()))
+    }
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut out = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => out.push_str(".*"),
+            '?' => out.push('.'),
+            c => out.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    out.push('$');
+    out
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum NameFilterError {
+    #[error("invalid name filter: {0}")]
+    Invalid(String),
+}
+
+/// A PEP 440 version (`[N!]N(.N)*[{a|b|rc}N][.postN][.devN][+local]`),
+/// ordered the way pip orders them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pep440Version {
+    pub epoch: u64,
+    pub release: Vec<u64>,
+    /// (0 = a, 1 = b, 2 = rc, n)
+    pub pre: Option<(u8, u64)>,
+    pub post: Option<u64>,
+    pub dev: Option<u64>,
+    pub local: Option<String>,
+}
+
+impl Pep440Version {
+    /// Parse leniently: case-insensitive, optional leading "v", and the
+    /// alternate spellings PEP 440 normalizes (alpha, beta, c, pre, -1 post, ...).
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        let s = s.strip_prefix('v').unwrap_or(&s);
+        let (s, local) = match s.split_once('+') {
+            Some((v, l)) if !l.is_empty() => (v, Some(l.to_string())),
+            Some(_) => return None,
+            None => (s, None),
+        };
+        let (epoch, rest) = match s.split_once('!') {
+            Some((e, r)) => (e.parse().ok()?, r),
+            None => (0, s),
+        };
+
+        // release: 数字和点
+        let end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let release_s = rest[..end].trim_end_matches('.');
+        let mut tail = &rest[release_s.len()..];
+        if release_s.is_empty() {
+            return None;
+        }
+        let release = release_s
+            .split('.')
+            .map(|p| p.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+
+        let mut pre = None;
+        let mut post = None;
+        let mut dev = None;
+
+        const SEPS: [char; 3] = ['.', '-', '_'];
+        let t = tail.trim_start_matches(SEPS);
+        if let Some((phase, r)) = [
+            ("alpha", 0),
+            ("beta", 1),
+            ("preview", 2),
+            ("pre", 2),
+            ("rc", 2),
+            ("a", 0),
+            ("b", 1),
+            ("c", 2),
+        ]
+        .iter()
+        .find_map(|(label, phase)| t.strip_prefix(label).map(|r| (*phase, r)))
+        {
+            let (n, r) = take_number(r.trim_start_matches(SEPS));
+            pre = Some((phase, n.unwrap_or(0)));
+            tail = r;
+        }
+
+        let t = tail.trim_start_matches(SEPS);
+        if let Some(r) = ["post", "rev", "r"].iter().find_map(|l| t.strip_prefix(l)) {
+            let (n, r) = take_number(r.trim_start_matches(SEPS));
+            post = Some(n.unwrap_or(0));
+            tail = r;
+        } else if let Some(r) = tail.strip_prefix('-') {
+            // "1.0-1" 是 post release 的隐式写法
+            if let (Some(n), r) = take_number(r) {
+                post = Some(n);
+                tail = r;
+            }
+        }
+
+        let t = tail.trim_start_matches(SEPS);
+        if let Some(r) = t.strip_prefix("dev") {
+            let (n, r) = take_number(r.trim_start_matches(SEPS));
+            dev = Some(n.unwrap_or(0));
+            tail = r;
+        }
+
+        if !tail.is_empty() {
+            return None;
+        }
+        Some(Self {
+            epoch,
+            release,
+            pre,
+            post,
+            dev,
+            local,
+        })
+    }
+
+    /// pre-releases and dev releases
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some() || self.dev.is_some()
+    }
+
+    fn sort_key(&self) -> Pep440Key {
+        let mut release = self.release.clone();
+        while release.len() > 1 && release.last() == Some(&0) {
+            release.pop();
+        }
+        // 1.0.dev1 < 1.0a1 < 1.0 < 1.0.post1
+        let pre = match (self.pre, self.post, self.dev) {
+            (None, None, Some(_)) => (-1, 0, 0),
+            (None, _, _) => (1, 0, 0),
+            (Some((phase, n)), _, _) => (0, phase, n),
+        };
+        let post = match self.post {
+            None => (-1, 0),
+            Some(n) => (0, n),
+        };
+        let dev = match self.dev {
+            None => (1, 0),
+            Some(n) => (0, n),
+        };
+        (self.epoch, release, pre, post, dev)
+    }
+}
+
+/// (epoch, release without trailing zeros, pre, post, dev)
+type Pep440Key = (u64, Vec<u64>, (i8, u8, u64), (i8, u64), (i8, u64));
+
+impl Ord for Pep440Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key()
+            .cmp(&other.sort_key())
+            .then_with(|| self.local.cmp(&other.local))
+    }
+}
+
+impl PartialOrd for Pep440Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn take_number(s: &str) -> (Option<u64>, &str) {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    (s[..end].parse().ok(), &s[end..])
+}
+
+/// Versions added to / removed from a ", "-joined version list (e.g. yanked
+/// versions), each side sorted with `cmp`.
+pub fn version_list_diff(
+    old: &str,
+    new: &str,
+    cmp: impl Fn(&str, &str) -> Ordering,
+) -> (Vec<String>, Vec<String>) {
+    let split = |s: &str| -> std::collections::BTreeSet<String> {
+        s.split(", ")
+            .filter(|v| !v.is_empty())
+            .map(|v| v.to_string())
+            .collect()
+    };
+    let (old, new) = (split(old), split(new));
+    let mut added: Vec<String> = new.difference(&old).cloned().collect();
+    let mut removed: Vec<String> = old.difference(&new).cloned().collect();
+    added.sort_by(|a, b| cmp(a, b));
+    removed.sort_by(|a, b| cmp(a, b));
+    (added, removed)
+}
+
+/// How far a version moved, by the first component that differs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum VersionBump {
+    /// only the pre-release / build part changed (1.0.0-rc.1 -> 1.0.0)
+    Prerelease,
+    Patch,
+    Minor,
+    Major,
+}
+
+impl VersionBump {
+    /// `Event::meta` key the classification is stored under
+    pub const META_KEY: &'static str = "bump";
+
+    /// None when either side isn't a version.
+    pub fn classify(old: &str, new: &str) -> Option<Self> {
+        let (old, new) = (parse_version_lenient(old)?, parse_version_lenient(new)?);
+        Some(if old.major != new.major {
+            VersionBump::Major
+        } else if old.minor != new.minor {
+            VersionBump::Minor
+        } else if old.patch != new.patch {
+            VersionBump::Patch
+        } else {
+            VersionBump::Prerelease
+        })
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "major" => Some(VersionBump::Major),
+            "minor" => Some(VersionBump::Minor),
+            "patch" => Some(VersionBump::Patch),
+            "prerelease" => Some(VersionBump::Prerelease),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VersionBump::Major => "major",
+            VersionBump::Minor => "minor",
+            VersionBump::Patch => "patch",
+            VersionBump::Prerelease => "prerelease",
+        }
+    }
+}
+
+/// Semver pre-release ("2.0.0-rc.1"), or PEP 440 pre / dev release ("2.0rc1")
+/// for values semver can't read.
+pub fn is_prerelease_version(s: &str) -> bool {
+    match parse_version_lenient(s) {
+        Some(v) => !v.pre.is_empty(),
+        None => Pep440Version::parse(s).is_some_and(|v| v.is_prerelease()),
+    }
+}
+
+/// npm-style range: comparators separated by spaces or commas, alternatives by
+/// "||" (">=2 <3", "^1.4 || ^2"). As with npm, a pre-release only matches a
+/// comparator on the same major.minor.patch.
+#[derive(Clone, Debug)]
+pub struct VersionRange {
+    alternatives: Vec<semver::VersionReq>,
+}
+
+impl VersionRange {
+    pub fn parse(s: &str) -> Result<Self, VersionRangeError> {
+        let alternatives = s
+            .split("||")
+            .map(|alt| {
+                // ">= 2 < 3" -> ">=2, <3": 单独的运算符并到下一个 token 上
+                let mut comparators: Vec<String> = vec![];
+                let mut pending = String::new();
+                for token in alt.split([' ', ',']).filter(|t| !t.is_empty()) {
+                    pending.push_str(token);
+                    if !token
+                        .chars()
+                        .all(|c| matches!(c, '<' | '>' | '=' | '~' | '^'))
+                    {
+                        comparators.push(std::mem::take(&mut pending));
+                    }
+                }
+                if !pending.is_empty() {
+                    return Err(VersionRangeError::Invalid(s.to_string()));
+                }
+                if comparators.is_empty() {
+                    return Ok(semver::VersionReq::STAR);
+                }
+                semver::VersionReq::parse(&comparators.join(", "))
+                    .map_err(|e| VersionRangeError::Invalid(format!("{s}: {e}")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { alternatives })
+    }
+
+    /// Versions are read leniently ("v2.1" works); anything else never matches.
+    pub fn matches(&self, version: &str) -> bool {
+        match parse_version_lenient(version) {
+            Some(v) => self.alternatives.iter().any(|r| r.matches(&v)),
+            None => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum VersionRangeError {
+    #[error("invalid version range: {0}")]
+    Invalid(String),
+}
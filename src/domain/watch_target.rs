@@ -1,6 +1,8 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
-use super::{RepoId, Source};
+use super::{Forge, ImageRef, NameFilter, RepoId, Schedule, Source, VersionPolicy};
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WatchTarget {
@@ -8,23 +10,296 @@ pub struct WatchTarget {
     pub enabled: bool,
     pub labels: Vec<String>,
     pub kind: WatchKind,
+    /// None: checked every global poll interval
+    #[serde(default)]
+    pub schedule: Option<Schedule>,
+    /// None: use the provider's configured API root / credentials
+    #[serde(default)]
+    pub endpoint: Option<Endpoint>,
+    /// only for `WatchKind::is_versioned` kinds
+    #[serde(default)]
+    pub version_policy: Option<VersionPolicy>,
+}
+
+/// How "newest tag" is decided.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TagOrder {
+    /// highest semantic version (tags that don't parse are ignored)
+    #[default]
+    Semver,
+    /// most recent tagged commit
+    Created,
+}
+
+/// Per-target override of where and how a provider talks to its upstream
+/// (e.g. GitHub Enterprise, a private npm registry).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Endpoint {
+    pub base_url: Option<String>,
+    /// sent as Bearer, or as the Basic password when `username` is set
+    #[serde(skip_serializing, default)]
+    pub token: Option<String>,
+    pub username: Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum WatchKind {
-    GitHubRelease { repo: RepoId },
-    GitHubBranch { repo: RepoId, branch: String },
-    NpmLatest { package: String },
-    WhatsAppWebVersion {}, // v1 reserved
+    GitHubRelease {
+        repo: RepoId,
+    },
+    GitHubBranch {
+        repo: RepoId,
+        branch: String,
+    },
+    GitHubTag {
+        repo: RepoId,
+        filter: Option<NameFilter>,
+        order: TagOrder,
+    },
+    /// issues / PRs matching a GitHub search query; each new match, close / merge
+    /// and new label is its own event (see `reports_items`)
+    GitHubSearch {
+        /// e.g. "repo:owner/repo label:bug is:open"
+        query: String,
+    },
+    /// conclusion of the latest finished run of a GitHub Actions workflow on a branch
+    GitHubWorkflow {
+        repo: RepoId,
+        /// workflow file name ("ci.yml") or numeric id
+        workflow: String,
+        branch: String,
+    },
+    /// latest release on a GitLab / Gitea forge (GitHub uses `GitHubRelease`)
+    ForgeRelease {
+        forge: Forge,
+        repo: RepoId,
+    },
+    /// head commit of a branch on a GitLab / Gitea forge (GitHub uses `GitHubBranch`)
+    ForgeBranch {
+        forge: Forge,
+        repo: RepoId,
+        branch: String,
+    },
+    NpmLatest {
+        package: String,
+    },
+    /// version an arbitrary dist-tag ("next", "beta", ...) points at
+    NpmDistTag {
+        package: String,
+        tag: String,
+    },
+    /// the set of deprecated versions of a package
+    NpmDeprecated {
+        package: String,
+    },
+    /// the set of unpublished versions of a package
+    NpmUnpublished {
+        package: String,
+    },
+    /// the packument's `time.modified` (any publish, deprecation, tag move, ...)
+    NpmModified {
+        package: String,
+    },
+    /// highest non-yanked version on crates.io (pre-releases only when `include_prereleases`)
+    CratesIoLatest {
+        name: String,
+        include_prereleases: bool,
+    },
+    /// the set of yanked versions of a crate
+    CratesIoYanked {
+        name: String,
+    },
+    /// highest non-yanked release on PyPI by PEP 440 ordering
+    PyPiLatest {
+        project: String,
+        include_prereleases: bool,
+    },
+    /// the set of yanked releases of a PyPI project
+    PyPiYanked {
+        project: String,
+    },
+    /// newest release listed in a Maven repository's maven-metadata.xml
+    MavenLatest {
+        group_id: String,
+        artifact_id: String,
+        include_snapshots: bool,
+    },
+    /// latest version of a Go module within its major version (import path)
+    GoModule {
+        module: String,
+        include_prereleases: bool,
+    },
+    /// manifest digest a tag points at (changes when the tag is re-pushed)
+    OciDigest {
+        image: ImageRef,
+        tag: String,
+    },
+    /// the set of tags of an image repository, optionally filtered
+    OciTags {
+        image: ImageRef,
+        filter: Option<NameFilter>,
+    },
+    WhatsAppWebVersion {
+        url: Option<String>,
+    },
+    /// value selected by a JSONPath expression from a JSON document fetched over HTTP
+    HttpJson {
+        url: String,
+        /// extra request headers; not serialized since they may carry credentials
+        #[serde(skip_serializing, default)]
+        headers: BTreeMap<String, String>,
+        /// RFC 9535 JSONPath, e.g. "$.data.version"
+        selector: String,
+        /// any other status is an error
+        expected_status: u16,
+        /// event link; `{value}` is replaced by the selected value (defaults to `url`)
+        link_template: Option<String>,
+    },
+    /// hash of a web page's (normalized) text, optionally narrowed to a CSS selector
+    /// and/or regex captures
+    HttpPage {
+        url: String,
+        /// extra request headers; not serialized since they may carry credentials
+        #[serde(skip_serializing, default)]
+        headers: BTreeMap<String, String>,
+        /// CSS selector; matched elements' text is kept
+        selector: Option<String>,
+        /// regex applied to the (selected) HTML; keeps group 1 if present, else the match
+        regex: Option<String>,
+    },
+    /// RSS 2.0 / Atom feed; each new entry is its own event (see `reports_items`)
+    Feed {
+        url: String,
+    },
+    /// security advisories affecting a package, from an OSV-compatible API; each
+    /// new advisory is its own event (see `reports_items`)
+    Advisories {
+        /// OSV ecosystem name: "npm", "crates.io", "PyPI", "Maven", "Go", ...
+        ecosystem: String,
+        package: String,
+    },
+    /// Watched by a provider registered outside this crate under `provider`.
+    Custom {
+        provider: String,
+        subject: String,
+        params: BTreeMap<String, String>,
+    },
 }
 
 impl WatchKind {
+    pub const GITHUB_RELEASE: &'static str = "github_release";
+    pub const GITHUB_BRANCH: &'static str = "github_branch";
+    pub const GITHUB_TAG: &'static str = "github_tag";
+    pub const GITHUB_WORKFLOW: &'static str = "github_workflow";
+    pub const GITHUB_SEARCH: &'static str = "github_search";
+    pub const GITLAB_RELEASE: &'static str = "gitlab_release";
+    pub const GITLAB_BRANCH: &'static str = "gitlab_branch";
+    pub const GITEA_RELEASE: &'static str = "gitea_release";
+    pub const GITEA_BRANCH: &'static str = "gitea_branch";
+    pub const NPM_LATEST: &'static str = "npm_latest";
+    pub const NPM_DIST_TAG: &'static str = "npm_dist_tag";
+    pub const NPM_DEPRECATED: &'static str = "npm_deprecated";
+    pub const NPM_UNPUBLISHED: &'static str = "npm_unpublished";
+    pub const NPM_MODIFIED: &'static str = "npm_modified";
+    pub const CRATES_IO_LATEST: &'static str = "crates_io_latest";
+    pub const CRATES_IO_YANKED: &'static str = "crates_io_yanked";
+    pub const PYPI_LATEST: &'static str = "pypi_latest";
+    pub const PYPI_YANKED: &'static str = "pypi_yanked";
+    pub const MAVEN_LATEST: &'static str = "maven_latest";
+    pub const GO_MODULE: &'static str = "go_module";
+    pub const OCI_DIGEST: &'static str = "oci_digest";
+    pub const OCI_TAGS: &'static str = "oci_tags";
+    pub const WHATSAPP_WEB_VERSION: &'static str = "whatsapp_web_version";
+    pub const HTTP_JSON: &'static str = "http_json";
+    pub const HTTP_PAGE: &'static str = "http_page";
+    pub const FEED: &'static str = "feed";
+    pub const ADVISORIES: &'static str = "advisories";
+
+    /// Release target on any forge; GitHub keeps its own kind (and ids / event types).
+    pub fn release(forge: Forge, repo: RepoId) -> Self {
+        match forge {
+            Forge::GitHub => WatchKind::GitHubRelease { repo },
+            _ => WatchKind::ForgeRelease { forge, repo },
+        }
+    }
+
+    /// Branch target on any forge; see `release`.
+    pub fn branch(forge: Forge, repo: RepoId, branch: String) -> Self {
+        match forge {
+            Forge::GitHub => WatchKind::GitHubBranch { repo, branch },
+            _ => WatchKind::ForgeBranch {
+                forge,
+                repo,
+                branch,
+            },
+        }
+    }
+
+    /// Key used to look up the provider in the registry.
+    pub fn key(&self) -> &str {
+        match self {
+            WatchKind::GitHubRelease { .. } => Self::GITHUB_RELEASE,
+            WatchKind::GitHubBranch { .. } => Self::GITHUB_BRANCH,
+            WatchKind::GitHubTag { .. } => Self::GITHUB_TAG,
+            WatchKind::GitHubWorkflow { .. } => Self::GITHUB_WORKFLOW,
+            WatchKind::GitHubSearch { .. } => Self::GITHUB_SEARCH,
+            WatchKind::ForgeRelease { forge, .. } => match forge {
+                Forge::GitHub => Self::GITHUB_RELEASE,
+                Forge::GitLab => Self::GITLAB_RELEASE,
+                Forge::Gitea => Self::GITEA_RELEASE,
+            },
+            WatchKind::ForgeBranch { forge, .. } => match forge {
+                Forge::GitHub => Self::GITHUB_BRANCH,
+                Forge::GitLab => Self::GITLAB_BRANCH,
+                Forge::Gitea => Self::GITEA_BRANCH,
+            },
+            WatchKind::NpmLatest { .. } => Self::NPM_LATEST,
+            WatchKind::NpmDistTag { .. } => Self::NPM_DIST_TAG,
+            WatchKind::NpmDeprecated { .. } => Self::NPM_DEPRECATED,
+            WatchKind::NpmUnpublished { .. } => Self::NPM_UNPUBLISHED,
+            WatchKind::NpmModified { .. } => Self::NPM_MODIFIED,
+            WatchKind::CratesIoLatest { .. } => Self::CRATES_IO_LATEST,
+            WatchKind::CratesIoYanked { .. } => Self::CRATES_IO_YANKED,
+            WatchKind::PyPiLatest { .. } => Self::PYPI_LATEST,
+            WatchKind::PyPiYanked { .. } => Self::PYPI_YANKED,
+            WatchKind::MavenLatest { .. } => Self::MAVEN_LATEST,
+            WatchKind::GoModule { .. } => Self::GO_MODULE,
+            WatchKind::OciDigest { .. } => Self::OCI_DIGEST,
+            WatchKind::OciTags { .. } => Self::OCI_TAGS,
+            WatchKind::WhatsAppWebVersion { .. } => Self::WHATSAPP_WEB_VERSION,
+            WatchKind::HttpJson { .. } => Self::HTTP_JSON,
+            WatchKind::HttpPage { .. } => Self::HTTP_PAGE,
+            WatchKind::Feed { .. } => Self::FEED,
+            WatchKind::Advisories { .. } => Self::ADVISORIES,
+            WatchKind::Custom { provider, .. } => provider,
+        }
+    }
+
     pub fn source(&self) -> Source {
         match self {
             WatchKind::GitHubRelease { .. } => Source::GitHub,
             WatchKind::GitHubBranch { .. } => Source::GitHub,
-            WatchKind::NpmLatest { .. } => Source::Npm,
+            WatchKind::GitHubTag { .. } => Source::GitHub,
+            WatchKind::GitHubWorkflow { .. } => Source::GitHub,
+            WatchKind::GitHubSearch { .. } => Source::GitHub,
+            WatchKind::ForgeRelease { forge, .. } | WatchKind::ForgeBranch { forge, .. } => {
+                forge.source()
+            }
+            WatchKind::NpmLatest { .. }
+            | WatchKind::NpmDistTag { .. }
+            | WatchKind::NpmDeprecated { .. }
+            | WatchKind::NpmUnpublished { .. }
+            | WatchKind::NpmModified { .. } => Source::Npm,
+            WatchKind::CratesIoLatest { .. } | WatchKind::CratesIoYanked { .. } => Source::CratesIo,
+            WatchKind::PyPiLatest { .. } | WatchKind::PyPiYanked { .. } => Source::PyPi,
+            WatchKind::MavenLatest { .. } => Source::Maven,
+            WatchKind::GoModule { .. } => Source::Go,
+            WatchKind::OciDigest { .. } | WatchKind::OciTags { .. } => Source::Oci,
             WatchKind::WhatsAppWebVersion { .. } => Source::WhatsAppWeb,
+            WatchKind::HttpJson { .. } | WatchKind::HttpPage { .. } => Source::Http,
+            WatchKind::Feed { .. } => Source::Feed,
+            WatchKind::Advisories { .. } => Source::Osv,
+            WatchKind::Custom { .. } => Source::Custom,
         }
     }
 
@@ -32,8 +307,86 @@ impl WatchKind {
         match self {
             WatchKind::GitHubRelease { repo } => repo.as_str(),
             WatchKind::GitHubBranch { repo, branch } => format!("{}#{}", repo.as_str(), branch),
-            WatchKind::NpmLatest { package } => package.clone(),
+            WatchKind::GitHubTag { repo, .. } => repo.as_str(),
+            WatchKind::GitHubWorkflow {
+                repo,
+                workflow,
+                branch,
+            } => format!("{}:{}#{}", repo.as_str(), workflow, branch),
+            WatchKind::GitHubSearch { query } => query.clone(),
+            WatchKind::ForgeRelease { repo, .. } => repo.as_str(),
+            WatchKind::ForgeBranch { repo, branch, .. } => {
+                format!("{}#{}", repo.as_str(), branch)
+            }
+            WatchKind::NpmLatest { package }
+            | WatchKind::NpmDeprecated { package }
+            | WatchKind::NpmUnpublished { package }
+            | WatchKind::NpmModified { package } => package.clone(),
+            WatchKind::NpmDistTag { package, tag } => format!("{}@{}", package, tag),
+            WatchKind::CratesIoLatest { name, .. } | WatchKind::CratesIoYanked { name } => {
+                name.clone()
+            }
+            WatchKind::PyPiLatest { project, .. } | WatchKind::PyPiYanked { project } => {
+                project.clone()
+            }
+            WatchKind::MavenLatest {
+                group_id,
+                artifact_id,
+                ..
+            } => format!("{}:{}", group_id, artifact_id),
+            WatchKind::GoModule { module, .. } => module.clone(),
+            WatchKind::OciDigest { image, tag } => format!("{}:{}", image.as_str(), tag),
+            WatchKind::OciTags { image, .. } => image.as_str(),
             WatchKind::WhatsAppWebVersion { .. } => "whatsapp-web".to_string(),
+            WatchKind::HttpJson { url, .. } | WatchKind::HttpPage { url, .. } => url.clone(),
+            WatchKind::Feed { url } => url.clone(),
+            WatchKind::Advisories { package, .. } => package.clone(),
+            WatchKind::Custom { subject, .. } => subject.clone(),
+        }
+    }
+
+    /// Targets whose upstream is a list of items (feed entries): every item not
+    /// seen before is reported via `WatchProvider::check_items`, instead of
+    /// comparing one value with the last one.
+    pub fn reports_items(&self) -> bool {
+        matches!(
+            self,
+            WatchKind::Feed { .. } | WatchKind::Advisories { .. } | WatchKind::GitHubSearch { .. }
+        )
+    }
+
+    /// OSV ecosystem and package name of registry-backed targets, for watching
+    /// their security advisories.
+    pub fn osv_package(&self) -> Option<(&'static str, String)> {
+        match self {
+            WatchKind::NpmLatest { package } => Some(("npm", package.clone())),
+            WatchKind::CratesIoLatest { name, .. } => Some(("crates.io", name.clone())),
+            WatchKind::PyPiLatest { project, .. } => Some(("PyPI", project.clone())),
+            WatchKind::MavenLatest {
+                group_id,
+                artifact_id,
+                ..
+            } => Some(("Maven", format!("{}:{}", group_id, artifact_id))),
+            WatchKind::GoModule { module, .. } => Some(("Go", module.clone())),
+            _ => None,
         }
     }
+
+    /// Targets whose value is a version number, so `VersionPolicy` and bump
+    /// classification apply.
+    pub fn is_versioned(&self) -> bool {
+        matches!(
+            self,
+            WatchKind::GitHubRelease { .. }
+                | WatchKind::GitHubTag { .. }
+                | WatchKind::ForgeRelease { .. }
+                | WatchKind::NpmLatest { .. }
+                | WatchKind::NpmDistTag { .. }
+                | WatchKind::CratesIoLatest { .. }
+                | WatchKind::PyPiLatest { .. }
+                | WatchKind::MavenLatest { .. }
+                | WatchKind::GoModule { .. }
+                | WatchKind::WhatsAppWebVersion { .. }
+        )
+    }
 }
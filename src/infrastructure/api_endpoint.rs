@@ -0,0 +1,82 @@
+use crate::domain::Endpoint;
+
+/// API root + credentials a provider sends a request with: the provider-wide
+/// default, optionally overridden per target by `WatchTarget::endpoint`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ApiEndpoint {
+    pub base_url: String,
+    pub token: Option<String>,
+    pub username: Option<String>,
+}
+
+impl ApiEndpoint {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token: None,
+            username: None,
+        }
+    }
+
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+        self
+    }
+
+    pub fn with_username(mut self, username: Option<String>) -> Self {
+        self.username = username;
+        self
+    }
+
+    /// Apply a target's override on top of this default. A target that only
+    /// sets a token keeps the default base URL, and vice versa.
+    pub fn resolve(&self, target: Option<&Endpoint>) -> ApiEndpoint {
+        let Some(t) = target else {
+            return self.clone();
+        };
+        // 换了 base_url 就不要把默认 token 发给别的主机
+        let same_host = t.base_url.is_none();
+        ApiEndpoint {
+            base_url: t
+                .base_url
+                .as_deref()
+                .map(|u| u.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| self.base_url.clone()),
+            token: t
+                .token
+                .clone()
+                .or_else(|| same_host.then(|| self.token.clone()).flatten()),
+            username: t
+                .username
+                .clone()
+                .or_else(|| same_host.then(|| self.username.clone()).flatten()),
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Bearer token, or Basic auth when a username is configured.
+    pub fn authorize(&self, req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match (&self.username, &self.token) {
+            (Some(user), token) => req.basic_auth(user, token.as_deref()),
+            (None, Some(token)) => req.bearer_auth(token),
+            (None, None) => req,
+        }
+    }
+}
+
+/// Percent-encode `s` as a single URL path segment ("group/project" -> "group%2Fproject").
+pub fn encode_path_segment(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
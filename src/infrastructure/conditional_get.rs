@@ -0,0 +1,90 @@
+use reqwest::StatusCode;
+use reqwest::header::{ETAG, HeaderMap, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+
+use crate::application::{AppError, AppResult, HttpCacheEntry, HttpCacheStore};
+
+pub struct ConditionalResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+    /// true when the server answered 304 and `body` came from the cache
+    pub not_modified: bool,
+}
+
+/// Send `req` with If-None-Match / If-Modified-Since from the cache entry under `key`.
+///
+/// A 304 is answered with the cached body, so callers parse it exactly like a 200
+/// and the unchanged value is filtered out later by the last-value comparison.
+/// 2xx responses refresh the cache; other statuses are returned as-is, uncached.
+pub async fn send_conditional(
+    cache: Option<&dyn HttpCacheStore>,
+    key: &str,
+    mut req: reqwest::RequestBuilder,
+) -> AppResult<ConditionalResponse> {
+    let cached = match cache {
+        Some(c) => c.get_http_cache(key).await?,
+        None => None,
+    };
+
+    if let Some(entry) = &cached {
+        if let Some(etag) = &entry.etag {
+            req = req.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(lm) = &entry.last_modified {
+            req = req.header(IF_MODIFIED_SINCE, lm);
+        }
+    }
+
+    let resp = req
+        .send()
+        .await
+        .map_err(|e| AppError::Provider(e.to_string()))?;
+    let status = resp.status();
+    let headers = resp.headers().clone();
+
+    if status == StatusCode::NOT_MODIFIED {
+        if let Some(entry) = cached {
+            return Ok(ConditionalResponse {
+                status,
+                headers,
+                body: entry.body,
+                not_modified: true,
+            });
+        }
+        // 没发过条件头却收到 304: 当作上游异常
+        return Err(AppError::Provider("unexpected 304 without cache".into()));
+    }
+
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string())
+    };
+    let etag = header(ETAG);
+    let last_modified = header(LAST_MODIFIED);
+
+    let body = resp
+        .text()
+        .await
+        .map_err(|e| AppError::Provider(e.to_string()))?;
+
+    if status.is_success()
+        && (etag.is_some() || last_modified.is_some())
+        && let Some(c) = cache
+    {
+        let entry = HttpCacheEntry {
+            etag,
+            last_modified,
+            body: body.clone(),
+        };
+        c.set_http_cache(key, &entry).await?;
+    }
+
+    Ok(ConditionalResponse {
+        status,
+        headers,
+        body,
+        not_modified: false,
+    })
+}
@@ -12,6 +12,12 @@ impl ConsoleNotifier {
     }
 }
 
+impl Default for ConsoleNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[async_trait]
 impl Notifier for ConsoleNotifier {
     async fn notify(&self, event: &Event) -> AppResult<()> {
@@ -21,6 +27,7 @@ impl Notifier for ConsoleNotifier {
           old = %event.old_value.clone().unwrap_or_else(|| "(none)".into()),
           new = %event.new_value,
           url = %event.url.clone().unwrap_or_else(|| "(none)".into()),
+          meta = ?event.meta,
           "notify"
         );
         Ok(())
@@ -0,0 +1,235 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::application::{AppError, AppResult, WatchProvider};
+use crate::domain::{
+    Event, EventType, Source, WatchKind, WatchTarget, compare_versions, version_list_diff,
+};
+use crate::infrastructure::api_endpoint::ApiEndpoint;
+use crate::infrastructure::provider_registry::KindProvider;
+
+pub const DEFAULT_CRATES_IO_URL: &str = "https://crates.io";
+
+/// `Event::meta` keys set on yank events
+pub const META_YANKED: &str = "yanked";
+pub const META_UNYANKED: &str = "unyanked";
+
+/// crates.io 要求带可识别的 User-Agent
+const USER_AGENT: &str = "repopulse (https://github.com/Ray-56/RepoPulse)";
+
+/// Shared crates.io API access for the latest / yanked providers.
+#[derive(Clone)]
+struct CratesIoApi {
+    client: reqwest::Client,
+    registry: ApiEndpoint,
+}
+
+#[derive(Debug, Deserialize)]
+struct CrateResp {
+    versions: Vec<VersionResp>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionResp {
+    num: String,
+    yanked: bool,
+    created_at: Option<String>,
+}
+
+impl CratesIoApi {
+    async fn versions(
+        &self,
+        target: &WatchTarget,
+        name: &str,
+    ) -> AppResult<(ApiEndpoint, Vec<VersionResp>)> {
+        let registry = self.registry.resolve(target.endpoint.as_ref());
+        let url = registry.url(&format!("/api/v1/crates/{}", name));
+
+        let resp = registry
+            .authorize(self.client.get(url))
+            .header(reqwest::header::USER_AGENT, USER_AGENT)
+            .send()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+
+        let body: CrateResp = resp
+            .json()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+        Ok((registry, body.versions))
+    }
+}
+
+/// crate page (per version when given); crates.io serves pages and API from the same root
+fn crate_page_url(registry: &ApiEndpoint, name: &str, version: Option<&str>) -> String {
+    match version {
+        Some(v) => format!("{}/crates/{}/{}", registry.base_url, name, v),
+        None => format!("{}/crates/{}", registry.base_url, name),
+    }
+}
+
+pub struct CratesIoLatestProvider {
+    api: CratesIoApi,
+}
+
+impl CratesIoLatestProvider {
+    pub fn new() -> Self {
+        Self::with_registry(ApiEndpoint::new(DEFAULT_CRATES_IO_URL))
+    }
+
+    /// Use another crates.io-compatible API root (mirror, local stand-in).
+    pub fn with_registry(registry: ApiEndpoint) -> Self {
+        Self {
+            api: CratesIoApi {
+                client: reqwest::Client::new(),
+                registry,
+            },
+        }
+    }
+}
+
+impl Default for CratesIoLatestProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KindProvider for CratesIoLatestProvider {
+    const KIND: &'static str = WatchKind::CRATES_IO_LATEST;
+}
+
+#[async_trait]
+impl WatchProvider for CratesIoLatestProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let (name, include_prereleases) = match &target.kind {
+            WatchKind::CratesIoLatest {
+                name,
+                include_prereleases,
+            } => (name, *include_prereleases),
+            _ => return Ok(None),
+        };
+
+        let (registry, versions) = self.api.versions(target, name).await?;
+
+        // 不用 max_stable_version: 自己按 semver 选, 这样 pre-release 开关也能生效
+        let latest = versions
+            .into_iter()
+            .filter(|v| !v.yanked)
+            .filter_map(|v| semver::Version::parse(&v.num).ok().map(|sv| (sv, v)))
+            .filter(|(sv, _)| include_prereleases || sv.pre.is_empty())
+            .max_by(|a, b| a.0.cmp(&b.0))
+            .map(|(_, v)| v);
+        let Some(latest) = latest else {
+            return Ok(None);
+        };
+
+        let subject = name.to_string();
+        let event_id = Event::make_event_id(&EventType::CratesIoLatest, &subject, &latest.num);
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::CratesIoLatest,
+            source: Source::CratesIo,
+            subject,
+            old_value: None,
+            url: Some(crate_page_url(&registry, name, Some(&latest.num))),
+            new_value: latest.num,
+            occurred_at: latest.created_at,
+            detected_at: now_string(),
+            meta: Default::default(),
+        }))
+    }
+}
+
+/// Reports changes to the set of yanked versions; the value is the yanked
+/// versions in semver order, and the event meta says which were (un)yanked.
+pub struct CratesIoYankedProvider {
+    api: CratesIoApi,
+}
+
+impl CratesIoYankedProvider {
+    pub fn new() -> Self {
+        Self::with_registry(ApiEndpoint::new(DEFAULT_CRATES_IO_URL))
+    }
+
+    pub fn with_registry(registry: ApiEndpoint) -> Self {
+        Self {
+            api: CratesIoApi {
+                client: reqwest::Client::new(),
+                registry,
+            },
+        }
+    }
+}
+
+impl Default for CratesIoYankedProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KindProvider for CratesIoYankedProvider {
+    const KIND: &'static str = WatchKind::CRATES_IO_YANKED;
+}
+
+#[async_trait]
+impl WatchProvider for CratesIoYankedProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let name = match &target.kind {
+            WatchKind::CratesIoYanked { name } => name,
+            _ => return Ok(None),
+        };
+
+        let (registry, versions) = self.api.versions(target, name).await?;
+
+        let mut yanked: Vec<String> = versions
+            .into_iter()
+            .filter(|v| v.yanked)
+            .map(|v| v.num)
+            .collect();
+        yanked.sort_by(|a, b| compare_versions(a, b));
+        let value = yanked.join(", ");
+
+        let subject = name.to_string();
+        let event_id = Event::make_event_id(&EventType::CratesIoYanked, &subject, &value);
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::CratesIoYanked,
+            source: Source::CratesIo,
+            subject,
+            old_value: None,
+            new_value: value,
+            occurred_at: None,
+            detected_at: now_string(),
+            url: Some(crate_page_url(&registry, name, None)),
+            meta: Default::default(),
+        }))
+    }
+
+    async fn enrich(&self, _target: &WatchTarget, event: &mut Event) -> AppResult<()> {
+        let (yanked, unyanked) = version_list_diff(
+            event.old_value.as_deref().unwrap_or(""),
+            &event.new_value,
+            compare_versions,
+        );
+        if !yanked.is_empty() {
+            event.meta.insert(META_YANKED.into(), yanked.join(", "));
+        }
+        if !unyanked.is_empty() {
+            event.meta.insert(META_UNYANKED.into(), unyanked.join(", "));
+        }
+        Ok(())
+    }
+}
+
+fn now_string() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    format!("{}s_since_epoch", secs)
+}
@@ -11,6 +11,12 @@ impl FakeWatchProvider {
     }
 }
 
+impl Default for FakeWatchProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[async_trait]
 impl WatchProvider for FakeWatchProvider {
     async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
@@ -18,8 +24,38 @@ impl WatchProvider for FakeWatchProvider {
         let event_type = match &target.kind {
             crate::domain::WatchKind::GitHubRelease { .. } => EventType::GitHubRelease,
             crate::domain::WatchKind::GitHubBranch { .. } => EventType::GitHubBranch,
+            crate::domain::WatchKind::GitHubTag { .. } => EventType::GitHubTag,
+            crate::domain::WatchKind::GitHubWorkflow { .. } => EventType::GitHubWorkflow,
+            crate::domain::WatchKind::GitHubSearch { .. } => EventType::GitHubSearchItem,
+            crate::domain::WatchKind::ForgeRelease { forge, .. } => match forge {
+                crate::domain::Forge::GitHub => EventType::GitHubRelease,
+                crate::domain::Forge::GitLab => EventType::GitLabRelease,
+                crate::domain::Forge::Gitea => EventType::GiteaRelease,
+            },
+            crate::domain::WatchKind::ForgeBranch { forge, .. } => match forge {
+                crate::domain::Forge::GitHub => EventType::GitHubBranch,
+                crate::domain::Forge::GitLab => EventType::GitLabBranch,
+                crate::domain::Forge::Gitea => EventType::GiteaBranch,
+            },
             crate::domain::WatchKind::NpmLatest { .. } => EventType::NpmLatest,
-            crate::domain::WatchKind::WhatsAppWebVersion {} => EventType::WhatsAppWebVersion,
+            crate::domain::WatchKind::NpmDistTag { .. } => EventType::NpmDistTag,
+            crate::domain::WatchKind::NpmDeprecated { .. } => EventType::NpmDeprecated,
+            crate::domain::WatchKind::NpmUnpublished { .. } => EventType::NpmUnpublished,
+            crate::domain::WatchKind::NpmModified { .. } => EventType::NpmModified,
+            crate::domain::WatchKind::CratesIoLatest { .. } => EventType::CratesIoLatest,
+            crate::domain::WatchKind::CratesIoYanked { .. } => EventType::CratesIoYanked,
+            crate::domain::WatchKind::PyPiLatest { .. } => EventType::PyPiLatest,
+            crate::domain::WatchKind::PyPiYanked { .. } => EventType::PyPiYanked,
+            crate::domain::WatchKind::MavenLatest { .. } => EventType::MavenLatest,
+            crate::domain::WatchKind::GoModule { .. } => EventType::GoModule,
+            crate::domain::WatchKind::OciDigest { .. } => EventType::OciDigest,
+            crate::domain::WatchKind::OciTags { .. } => EventType::OciTags,
+            crate::domain::WatchKind::WhatsAppWebVersion { .. } => EventType::WhatsAppWebVersion,
+            crate::domain::WatchKind::HttpJson { .. } => EventType::HttpJson,
+            crate::domain::WatchKind::HttpPage { .. } => EventType::HttpPage,
+            crate::domain::WatchKind::Feed { .. } => EventType::FeedEntry,
+            crate::domain::WatchKind::Advisories { .. } => EventType::Advisory,
+            crate::domain::WatchKind::Custom { .. } => EventType::Custom,
         };
 
         let subject = target.kind.subject();
@@ -36,6 +72,7 @@ impl WatchProvider for FakeWatchProvider {
             occurred_at: None,
             detected_at: "2026-02-04T00:00:00+08:00".to_string(),
             url: Some("https://example.com".to_string()),
+            meta: Default::default(),
         }))
     }
 }
@@ -0,0 +1,238 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use reqwest::header::{ACCEPT, USER_AGENT};
+
+use crate::application::{AppError, AppResult, HttpCacheStore, WatchProvider};
+use crate::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use crate::infrastructure::api_endpoint::ApiEndpoint;
+use crate::infrastructure::conditional_get::send_conditional;
+use crate::infrastructure::provider_registry::KindProvider;
+
+/// `Event::meta` key: the entry's id / guid (what entries are deduplicated by)
+pub const META_ENTRY_ID: &str = "entry_id";
+
+/// RSS 2.0 / RSS 1.0 / Atom feeds (GitHub `releases.atom`, blogs, security
+/// bulletins); each entry becomes its own event.
+pub struct FeedProvider {
+    client: reqwest::Client,
+    cache: Option<Arc<dyn HttpCacheStore>>,
+}
+
+impl FeedProvider {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            cache: None,
+        }
+    }
+
+    /// Send conditional requests (ETag / Last-Modified) using validators from `cache`.
+    pub fn with_cache(mut self, cache: Arc<dyn HttpCacheStore>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    async fn entries(&self, target: &WatchTarget) -> AppResult<Option<Vec<Event>>> {
+        let url = match &target.kind {
+            WatchKind::Feed { url } => url,
+            _ => return Ok(None),
+        };
+
+        let req = self.client.get(url).header(USER_AGENT, "repopulse").header(
+            ACCEPT,
+            "application/atom+xml, application/rss+xml, application/xml;q=0.9, */*;q=0.8",
+        );
+        let req = ApiEndpoint::default()
+            .resolve(target.endpoint.as_ref())
+            .authorize(req);
+
+        // 304 时 body 来自缓存
+        let resp = send_conditional(self.cache.as_deref(), &target.id, req).await?;
+        if !resp.status.is_success() && !resp.not_modified {
+            return Err(AppError::Provider(format!("HTTP status {}", resp.status)));
+        }
+
+        let entries = parse_feed(&resp.body)?;
+        let base = reqwest::Url::parse(url).ok();
+        let events = entries
+            .into_iter()
+            .map(|e| {
+                let subject = url.clone();
+                let event_id = Event::make_event_id(&EventType::FeedEntry, &subject, &e.id);
+                // 相对链接按 feed 地址解析
+                let link = e
+                    .link
+                    .map(|l| match base.as_ref().and_then(|b| b.join(&l).ok()) {
+                        Some(abs) => abs.to_string(),
+                        None => l,
+                    });
+                Event {
+                    event_id,
+                    event_type: EventType::FeedEntry,
+                    source: Source::Feed,
+                    subject,
+                    old_value: None,
+                    new_value: e.title,
+                    occurred_at: e.date,
+                    detected_at: now_string(),
+                    url: link,
+                    meta: [(META_ENTRY_ID.to_string(), e.id)].into(),
+                }
+            })
+            .collect();
+        Ok(Some(events))
+    }
+}
+
+impl Default for FeedProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KindProvider for FeedProvider {
+    const KIND: &'static str = WatchKind::FEED;
+}
+
+#[async_trait]
+impl WatchProvider for FeedProvider {
+    /// The newest entry only; the run loop uses `check_items` for feeds.
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        Ok(self.entries(target).await?.and_then(|mut v| v.pop()))
+    }
+
+    async fn check_items(&self, target: &WatchTarget) -> AppResult<Vec<Event>> {
+        Ok(self.entries(target).await?.unwrap_or_default())
+    }
+}
+
+#[derive(Debug)]
+struct FeedEntry {
+    id: String,
+    title: String,
+    link: Option<String>,
+    /// RFC3339 when the feed's date could be parsed, else as given
+    date: Option<String>,
+    /// the parsed date, for ordering
+    timestamp: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// 不可信输入: 节点数设个上限
+const MAX_FEED_NODES: u32 = 200_000;
+
+/// Entries oldest first: by date when every entry's date could be parsed,
+/// otherwise reversed document order (feeds list newest first).
+fn parse_feed(xml: &str) -> AppResult<Vec<FeedEntry>> {
+    // 不解析 DTD: 带 DOCTYPE 的 feed 直接报错, 不展开实体
+    let opts = roxmltree::ParsingOptions {
+        allow_dtd: false,
+        nodes_limit: MAX_FEED_NODES,
+        ..Default::default()
+    };
+    let doc = roxmltree::Document::parse_with_options(xml, opts)
+        .map_err(|e| AppError::Provider(format!("invalid feed: {e}")))?;
+    let root = doc.root_element();
+
+    let mut entries: Vec<FeedEntry> = match root.tag_name().name() {
+        "feed" => root
+            .children()
+            .filter(|n| n.tag_name().name() == "entry")
+            .filter_map(atom_entry)
+            .collect(),
+        // RSS 2.0: rss/channel/item; RSS 1.0 (RDF): item 与 channel 同级
+        "rss" | "RDF" => root
+            .descendants()
+            .filter(|n| n.tag_name().name() == "item")
+            .filter_map(rss_item)
+            .collect(),
+        other => {
+            return Err(AppError::Provider(format!(
+                "not an RSS / Atom feed (root element {other})"
+            )));
+        }
+    };
+
+    entries.reverse();
+    // 稳定排序, 同一时间的保持文档顺序
+    if entries.iter().all(|e| e.timestamp.is_some()) {
+        entries.sort_by_key(|e| e.timestamp);
+    }
+    Ok(entries)
+}
+
+fn atom_entry(entry: roxmltree::Node) -> Option<FeedEntry> {
+    let link = entry
+        .children()
+        .filter(|n| n.tag_name().name() == "link")
+        .find(|n| matches!(n.attribute("rel"), None | Some("alternate")))
+        .and_then(|n| n.attribute("href"))
+        .map(|s| s.trim().to_string());
+    let date = child_text(entry, "published").or_else(|| child_text(entry, "updated"));
+    finish_entry(
+        child_text(entry, "id"),
+        child_text(entry, "title"),
+        link,
+        date,
+    )
+}
+
+fn rss_item(item: roxmltree::Node) -> Option<FeedEntry> {
+    let link = child_text(item, "link");
+    let date = child_text(item, "pubDate").or_else(|| child_text(item, "date"));
+    let id = child_text(item, "guid").or_else(|| {
+        item.attribute(("http://www.w3.org/1999/02/22-rdf-syntax-ns#", "about"))
+            .map(str::to_string)
+    });
+    finish_entry(id, child_text(item, "title"), link, date)
+}
+
+/// id 缺失时回退到 link, 再回退到 title; title 缺失时用 id
+fn finish_entry(
+    id: Option<String>,
+    title: Option<String>,
+    link: Option<String>,
+    date: Option<String>,
+) -> Option<FeedEntry> {
+    let id = id.or_else(|| link.clone()).or_else(|| title.clone())?;
+    let timestamp = date.as_deref().and_then(parse_date);
+    Some(FeedEntry {
+        title: title.unwrap_or_else(|| id.clone()),
+        id,
+        link,
+        date: match timestamp {
+            Some(t) => Some(t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+            None => date,
+        },
+        timestamp,
+    })
+}
+
+/// Whitespace-collapsed text of the first child element named `name` (any namespace).
+fn child_text(node: roxmltree::Node, name: &str) -> Option<String> {
+    let child = node.children().find(|n| n.tag_name().name() == name)?;
+    let text: String = child
+        .descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+/// RFC 2822 (RSS) or RFC3339 (Atom, dc:date), in UTC
+fn parse_date(s: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc2822(s)
+        .or_else(|_| chrono::DateTime::parse_from_rfc3339(s))
+        .map(|d| d.with_timezone(&chrono::Utc))
+        .ok()
+}
+
+fn now_string() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    format!("{}s_since_epoch", secs)
+}
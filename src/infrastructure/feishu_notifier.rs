@@ -56,7 +56,11 @@ impl Notifier for FeishuNotifier {
 fn format_event_text(event: &Event) -> String {
     let mut lines = vec![];
 
-    lines.push(format!("🔔 RepoPulse 检测到更新"));
+    if event.is_high_priority() {
+        lines.push("🚨 RepoPulse 高优先级事件".to_string());
+    } else {
+        lines.push("🔔 RepoPulse 检测到更新".to_string());
+    }
     lines.push(format!("📢 事件类型: {:?}", event.event_type));
     lines.push(format!("🎯 对象: {}", event.subject));
 
@@ -75,5 +79,14 @@ fn format_event_text(event: &Event) -> String {
         lines.push(format!("详情: {}", url));
     }
 
+    // provider 附加的细节, 例如分支移动时的提交范围
+    for (k, v) in &event.meta {
+        if v.contains('\n') {
+            lines.push(format!("{}:\n{}", k, v));
+        } else {
+            lines.push(format!("{}: {}", k, v));
+        }
+    }
+
     lines.join("\n")
 }
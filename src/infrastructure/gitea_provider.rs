@@ -0,0 +1,227 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::application::{AppError, AppResult, WatchProvider};
+use crate::domain::{Event, EventType, Forge, Source, WatchKind, WatchTarget};
+use crate::infrastructure::api_endpoint::ApiEndpoint;
+use crate::infrastructure::provider_registry::KindProvider;
+
+/// Gitea 没有"官方"实例; 默认用 Codeberg (Forgejo), 自建实例配 base_url
+pub const DEFAULT_GITEA_API_URL: &str = "https://codeberg.org/api/v1";
+
+/// Gitea / Forgejo REST (v1) access shared by the release / branch providers.
+#[derive(Clone)]
+struct GiteaApi {
+    client: reqwest::Client,
+    default: ApiEndpoint,
+}
+
+impl GiteaApi {
+    fn new(default: ApiEndpoint) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            default,
+        }
+    }
+
+    /// GET `{base_url}{path}`; returns None on 404.
+    async fn get(
+        &self,
+        target: &WatchTarget,
+        path: &str,
+    ) -> AppResult<(ApiEndpoint, Option<reqwest::Response>)> {
+        let api = self.default.resolve(target.endpoint.as_ref());
+        let resp = api
+            .authorize(self.client.get(api.url(path)))
+            .send()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok((api, None));
+        }
+        if !resp.status().is_success() {
+            return Err(AppError::Provider(format!("HTTP status {}", resp.status())));
+        }
+        Ok((api, Some(resp)))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseResp {
+    tag_name: String,
+    html_url: Option<String>,
+    published_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BranchResp {
+    commit: BranchCommit,
+}
+
+#[derive(Debug, Deserialize)]
+struct BranchCommit {
+    id: String,
+    timestamp: Option<String>,
+}
+
+/// Web root of the instance: `https://gitea.example.com/api/v1` -> `https://gitea.example.com`.
+fn web_url(api: &ApiEndpoint, path: &str) -> String {
+    format!("{}{}", api.base_url.trim_end_matches("/api/v1"), path)
+}
+
+pub struct GiteaReleaseProvider {
+    api: GiteaApi,
+}
+
+impl GiteaReleaseProvider {
+    pub fn new() -> Self {
+        Self::with_endpoint(ApiEndpoint::new(DEFAULT_GITEA_API_URL))
+    }
+
+    /// Use a self-hosted instance (its `/api/v1` root) by default.
+    pub fn with_endpoint(endpoint: ApiEndpoint) -> Self {
+        Self {
+            api: GiteaApi::new(endpoint),
+        }
+    }
+}
+
+impl Default for GiteaReleaseProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KindProvider for GiteaReleaseProvider {
+    const KIND: &'static str = WatchKind::GITEA_RELEASE;
+}
+
+#[async_trait]
+impl WatchProvider for GiteaReleaseProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let repo = match &target.kind {
+            WatchKind::ForgeRelease {
+                forge: Forge::Gitea,
+                repo,
+            } => repo,
+            _ => return Ok(None),
+        };
+
+        // latest 不含 draft / pre-release; 没有 release (404) 不是错误
+        let path = format!("/repos/{}/releases/latest", repo.as_str());
+        let (api, Some(resp)) = self.api.get(target, &path).await? else {
+            return Ok(None);
+        };
+        let release: ReleaseResp = resp
+            .json()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+
+        let subject = repo.as_str();
+        let event_id = Event::make_event_id(&EventType::GiteaRelease, &subject, &release.tag_name);
+        let url = release.html_url.unwrap_or_else(|| {
+            web_url(
+                &api,
+                &format!("/{}/releases/tag/{}", subject, release.tag_name),
+            )
+        });
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::GiteaRelease,
+            source: Source::Gitea,
+            subject,
+            old_value: None,
+            new_value: release.tag_name,
+            occurred_at: release.published_at,
+            detected_at: now_string(),
+            url: Some(url),
+            meta: Default::default(),
+        }))
+    }
+}
+
+pub struct GiteaBranchProvider {
+    api: GiteaApi,
+}
+
+impl GiteaBranchProvider {
+    pub fn new() -> Self {
+        Self::with_endpoint(ApiEndpoint::new(DEFAULT_GITEA_API_URL))
+    }
+
+    /// Use a self-hosted instance (its `/api/v1` root) by default.
+    pub fn with_endpoint(endpoint: ApiEndpoint) -> Self {
+        Self {
+            api: GiteaApi::new(endpoint),
+        }
+    }
+}
+
+impl Default for GiteaBranchProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KindProvider for GiteaBranchProvider {
+    const KIND: &'static str = WatchKind::GITEA_BRANCH;
+}
+
+#[async_trait]
+impl WatchProvider for GiteaBranchProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let (repo, branch) = match &target.kind {
+            WatchKind::ForgeBranch {
+                forge: Forge::Gitea,
+                repo,
+                branch,
+            } => (repo, branch),
+            _ => return Ok(None),
+        };
+
+        let path = format!("/repos/{}/branches/{}", repo.as_str(), branch);
+        let (api, resp) = self.api.get(target, &path).await?;
+        // 分支不存在 (被删/改名) 要报出来, 不能静默
+        let Some(resp) = resp else {
+            return Err(AppError::Provider(format!(
+                "branch {} not found in {}",
+                branch,
+                repo.as_str()
+            )));
+        };
+        let body: BranchResp = resp
+            .json()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+
+        let sha = body.commit.id;
+        let subject = format!("{}#{}", repo.as_str(), branch);
+        let event_id = Event::make_event_id(&EventType::GiteaBranch, &subject, &sha);
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::GiteaBranch,
+            source: Source::Gitea,
+            subject,
+            old_value: None,
+            new_value: sha,
+            occurred_at: body.commit.timestamp,
+            detected_at: now_string(),
+            url: Some(web_url(
+                &api,
+                &format!("/{}/src/branch/{}", repo.as_str(), branch),
+            )),
+            meta: Default::default(),
+        }))
+    }
+}
+
+fn now_string() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    format!("{}s_since_epoch", secs)
+}
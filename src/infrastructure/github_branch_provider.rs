@@ -1,21 +1,27 @@
 use async_trait::async_trait;
-use reqwest::header::{ACCEPT, AUTHORIZATION, USER_AGENT};
 use serde::Deserialize;
 
 use crate::application::{AppError, AppResult, WatchProvider};
-use crate::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use crate::domain::{Event, EventType, Forge, Source, WatchKind, WatchTarget};
+use crate::infrastructure::github_client::GitHubClient;
+use crate::infrastructure::provider_registry::KindProvider;
+
+/// `Event::meta` keys set when a branch moves
+pub const META_COMMIT_COUNT: &str = "commit_count";
+pub const META_COMMITS: &str = "commits";
+pub const META_COMPARE_URL: &str = "compare_url";
+pub const META_FORCE_PUSHED: &str = "force_pushed";
+
+/// 通知里最多列出多少个提交
+const MAX_LISTED_COMMITS: usize = 20;
 
 pub struct GitHubBranchProvider {
-    client: reqwest::Client,
-    token: Option<String>,
+    github: GitHubClient,
 }
 
 impl GitHubBranchProvider {
-    pub fn new(token: Option<String>) -> Self {
-        Self {
-            client: reqwest::Client::new(),
-            token,
-        }
+    pub fn new(github: GitHubClient) -> Self {
+        Self { github }
     }
 }
 
@@ -35,41 +41,73 @@ struct LinksObj {
     html: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct CompareResp {
+    /// "ahead" | "behind" | "diverged" | "identical"
+    status: String,
+    ahead_by: u64,
+    html_url: Option<String>,
+    #[serde(default)]
+    commits: Vec<CompareCommit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompareCommit {
+    sha: String,
+    commit: CompareCommitDetail,
+    author: Option<CompareUser>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompareCommitDetail {
+    message: String,
+    author: Option<CompareSignature>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompareSignature {
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompareUser {
+    login: String,
+}
+
+impl KindProvider for GitHubBranchProvider {
+    const KIND: &'static str = WatchKind::GITHUB_BRANCH;
+}
+
 #[async_trait]
 impl WatchProvider for GitHubBranchProvider {
     async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
         let (repo, branch) = match &target.kind {
-            WatchKind::GitHubBranch { repo, branch } => (repo, branch),
+            WatchKind::GitHubBranch { repo, branch }
+            | WatchKind::ForgeBranch {
+                forge: Forge::GitHub,
+                repo,
+                branch,
+            } => (repo, branch),
             _ => return Ok(None),
         };
 
-        let url = format!(
-            "https://api.github.com/repos/{}/branches/{}",
-            repo.as_str(),
-            branch,
-        );
-
-        let mut req = self
-            .client
-            .get(url)
-            .header(USER_AGENT, "repopulse")
-            .header(ACCEPT, "application/vnd.github+json");
-
-        if let Some(token) = &self.token {
-            req = req.header(AUTHORIZATION, format!("Bearer {}", token));
+        // 304 时 body 来自缓存, 不消耗 rate limit
+        let path = format!("/repos/{}/branches/{}", repo.as_str(), branch);
+        let resp = self
+            .github
+            .get(
+                target.endpoint.as_ref(),
+                &path,
+                "application/vnd.github+json",
+                Some(&target.id),
+            )
+            .await?;
+        if !resp.status.is_success() {
+            return Err(AppError::Provider(format!("HTTP status {}", resp.status)));
         }
 
-        let resp = req
-            .send()
-            .await
-            .map_err(|e| AppError::Provider(e.to_string()))?
-            .error_for_status()
-            .map_err(|e| AppError::Provider(e.to_string()))?;
-
-        let body: BranchResp = resp
-            .json()
-            .await
-            .map_err(|e| AppError::Provider(e.to_string()))?;
+        let body: BranchResp =
+            serde_json::from_str(&resp.body).map_err(|e| AppError::Provider(e.to_string()))?;
 
         let sha = body.commit.sha;
         let subject = format!("{}#{}", repo.as_str(), branch);
@@ -86,8 +124,96 @@ impl WatchProvider for GitHubBranchProvider {
             occurred_at: None,
             detected_at: now_string(),
             url: body._links.html,
+            meta: Default::default(),
         }))
     }
+
+    /// Attach the old..new compare range; a force-push is flagged when the old
+    /// head is no longer an ancestor of the new one (or is gone entirely).
+    async fn enrich(&self, target: &WatchTarget, event: &mut Event) -> AppResult<()> {
+        let (WatchKind::GitHubBranch { repo, .. }
+        | WatchKind::ForgeBranch {
+            forge: Forge::GitHub,
+            repo,
+            ..
+        }) = &target.kind
+        else {
+            return Ok(());
+        };
+        let Some(old) = event.old_value.clone() else {
+            return Ok(());
+        };
+        let new = &event.new_value;
+
+        let range_path = format!("/{}/compare/{}...{}", repo.as_str(), old, new);
+        let path = format!("/repos{}", range_path);
+        let resp = self
+            .github
+            .get(
+                target.endpoint.as_ref(),
+                &path,
+                "application/vnd.github+json",
+                None,
+            )
+            .await?;
+
+        let compare_url = self.github.web_url(target.endpoint.as_ref(), &range_path);
+
+        // 旧提交已被 force-push 掉并回收: 比较不了
+        if resp.status == reqwest::StatusCode::NOT_FOUND {
+            event.meta.insert(META_FORCE_PUSHED.into(), "true".into());
+            event.meta.insert(META_COMPARE_URL.into(), compare_url);
+            return Ok(());
+        }
+        if !resp.status.is_success() {
+            return Err(AppError::Provider(format!("HTTP status {}", resp.status)));
+        }
+
+        let body: CompareResp =
+            serde_json::from_str(&resp.body).map_err(|e| AppError::Provider(e.to_string()))?;
+
+        let force_pushed = matches!(body.status.as_str(), "diverged" | "behind");
+        let mut lines: Vec<String> = body
+            .commits
+            .iter()
+            .rev()
+            .take(MAX_LISTED_COMMITS)
+            .map(format_commit)
+            .collect();
+        if body.commits.len() > MAX_LISTED_COMMITS {
+            lines.push(format!(
+                "... and {} more",
+                body.commits.len() - MAX_LISTED_COMMITS
+            ));
+        }
+
+        event
+            .meta
+            .insert(META_COMMIT_COUNT.into(), body.ahead_by.to_string());
+        event.meta.insert(META_COMMITS.into(), lines.join("\n"));
+        event.meta.insert(
+            META_COMPARE_URL.into(),
+            body.html_url.unwrap_or(compare_url),
+        );
+        event
+            .meta
+            .insert(META_FORCE_PUSHED.into(), force_pushed.to_string());
+        Ok(())
+    }
+}
+
+/// "abc1234 author: subject line" (newest first)
+fn format_commit(c: &CompareCommit) -> String {
+    let short = c.sha.get(..7).unwrap_or(&c.sha);
+    let author = c
+        .commit
+        .author
+        .as_ref()
+        .and_then(|a| a.name.clone())
+        .or_else(|| c.author.as_ref().map(|u| u.login.clone()))
+        .unwrap_or_else(|| "unknown".into());
+    let subject = c.commit.message.lines().next().unwrap_or("");
+    format!("{short} {author}: {subject}")
 }
 
 fn now_string() -> String {
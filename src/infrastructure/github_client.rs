@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use reqwest::StatusCode;
+use reqwest::header::{ACCEPT, HeaderMap, RETRY_AFTER, USER_AGENT};
+use tracing::{debug, info, warn};
+
+use crate::application::{
+    AppError, AppResult, HttpCacheStore, RateLimitReporter, RateLimitSnapshot,
+};
+use crate::domain::Endpoint;
+use crate::infrastructure::api_endpoint::ApiEndpoint;
+use crate::infrastructure::conditional_get::{ConditionalResponse, send_conditional};
+
+pub const DEFAULT_GITHUB_API_URL: &str = "https://api.github.com";
+
+/// GitHub REST client shared by all GitHub providers.
+///
+/// Tracks `X-RateLimit-*` / `Retry-After` from every response, per API root
+/// (github.com and each GitHub Enterprise host have their own quota) and per
+/// `X-RateLimit-Resource` ("core", "search", ...); once a quota is exhausted,
+/// requests for that resource on that host fail fast with `AppError::RateLimited`
+/// until the reset time instead of hitting the API again.
+#[derive(Clone)]
+pub struct GitHubClient {
+    client: reqwest::Client,
+    default: ApiEndpoint,
+    cache: Option<Arc<dyn HttpCacheStore>>,
+    /// (base url, resource) -> 最近一次看到的配额
+    rates: Arc<Mutex<HashMap<(String, String), RateLimitSnapshot>>>,
+}
+
+impl GitHubClient {
+    pub fn new(token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            default: ApiEndpoint::new(DEFAULT_GITHUB_API_URL).with_token(token),
+            cache: None,
+            rates: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Point at another API root (GitHub Enterprise, or a local stand-in in tests).
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.default = ApiEndpoint::new(base_url).with_token(self.default.token.clone());
+        self
+    }
+
+    /// Send conditional requests (ETag / Last-Modified) using validators from `cache`.
+    pub fn with_cache(mut self, cache: Arc<dyn HttpCacheStore>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// API root + credentials used for a target (its own `endpoint` wins).
+    pub fn endpoint_for(&self, endpoint: Option<&Endpoint>) -> ApiEndpoint {
+        self.default.resolve(endpoint)
+    }
+
+    /// Web (html) URL on the same host as the target's API root:
+    /// api.github.com -> github.com, `https://ghe/api/v3` -> `https://ghe`.
+    pub fn web_url(&self, endpoint: Option<&Endpoint>, path: &str) -> String {
+        let api = self.endpoint_for(endpoint);
+        let base = if api.base_url == DEFAULT_GITHUB_API_URL {
+            "https://github.com"
+        } else {
+            api.base_url.trim_end_matches("/api/v3")
+        };
+        format!("{base}{path}")
+    }
+
+    /// GET `{base_url}{path}`. With `cache_key`, the request is conditional and a
+    /// 304 is answered from the cache (GitHub doesn't count 304s against the quota).
+    pub async fn get(
+        &self,
+        endpoint: Option<&Endpoint>,
+        path: &str,
+        accept: &str,
+        cache_key: Option<&str>,
+    ) -> AppResult<ConditionalResponse> {
+        let api = self.endpoint_for(endpoint);
+        let now = now_epoch();
+        let resource = resource_for(path);
+        if let Some(until) = self.paused_until(&api.base_url, resource, now) {
+            return Err(AppError::RateLimited(format!(
+                "{} {resource} paused for {}s (until epoch {until})",
+                source_name(&api.base_url),
+                until - now
+            )));
+        }
+
+        let req = self
+            .client
+            .get(api.url(path))
+            .header(USER_AGENT, "repopulse")
+            .header(ACCEPT, accept);
+        let req = api.authorize(req);
+
+        let resp = match cache_key {
+            Some(key) => send_conditional(self.cache.as_deref(), key, req).await?,
+            None => send_conditional(None, "", req).await?,
+        };
+
+        if let Some(until) = self.observe(&api.base_url, resource, resp.status, &resp.headers, now)
+        {
+            return Err(AppError::RateLimited(format!(
+                "{} returned {} (paused until epoch {until})",
+                source_name(&api.base_url),
+                resp.status
+            )));
+        }
+        Ok(resp)
+    }
+
+    fn paused_until(&self, base_url: &str, resource: &str, now: i64) -> Option<i64> {
+        let rates = self.rates.lock().ok()?;
+        rates
+            .get(&(base_url.to_string(), resource.to_string()))?
+            .paused_until_epoch
+            .filter(|until| *until > now)
+    }
+
+    /// Record rate-limit headers; returns the pause deadline if this response
+    /// was a rate-limit rejection.
+    fn observe(
+        &self,
+        base_url: &str,
+        resource: &str,
+        status: StatusCode,
+        headers: &HeaderMap,
+        now: i64,
+    ) -> Option<i64> {
+        let header_num = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|s| s.trim().parse::<i64>().ok())
+        };
+        let limit = header_num("x-ratelimit-limit");
+        let remaining = header_num("x-ratelimit-remaining");
+        let reset = header_num("x-ratelimit-reset");
+        let retry_after = headers
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| s.trim().parse::<i64>().ok());
+
+        // 响应头里的 resource 为准, 没有时按请求路径推断
+        let resource = headers
+            .get("x-ratelimit-resource")
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .unwrap_or(resource);
+
+        let mut rates = self.rates.lock().ok()?;
+        let rate = rates
+            .entry((base_url.to_string(), resource.to_string()))
+            .or_insert_with(|| RateLimitSnapshot {
+                source: source_name(base_url),
+                resource: Some(resource.to_string()),
+                ..Default::default()
+            });
+        if limit.is_some() {
+            rate.limit = limit.map(|v| v as u64);
+        }
+        if remaining.is_some() {
+            rate.remaining = remaining.map(|v| v as u64);
+        }
+        if reset.is_some() {
+            rate.reset_epoch = reset;
+        }
+
+        let rejected = (status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS)
+            && (remaining == Some(0) || retry_after.is_some());
+
+        // 被拒绝, 或者额度已用完: 暂停到 Retry-After / reset
+        let pause_until = if rejected || remaining == Some(0) {
+            retry_after
+                .map(|s| now + s.max(1))
+                .or(reset)
+                .or(Some(now + 60))
+        } else {
+            None
+        };
+        rate.paused_until_epoch = pause_until;
+
+        match pause_until {
+            Some(until) => warn!(
+                source = %rate.source,
+                resource = %resource,
+                status = %status,
+                remaining = ?rate.remaining,
+                limit = ?rate.limit,
+                paused_for_seconds = until - now,
+                "github rate limit reached, pausing github requests for this resource"
+            ),
+            // 剩余不足 10% 时提升到 info, 方便在日志里看到配额
+            None if matches!((rate.remaining, rate.limit), (Some(r), Some(l)) if r * 10 <= l) => {
+                info!(
+                    source = %rate.source,
+                    resource = %resource,
+                    remaining = ?rate.remaining,
+                    limit = ?rate.limit,
+                    reset = ?rate.reset_epoch,
+                    "github rate limit running low"
+                )
+            }
+            None => debug!(
+                source = %rate.source,
+                resource = %resource,
+                remaining = ?rate.remaining,
+                limit = ?rate.limit,
+                reset = ?rate.reset_epoch,
+                "github rate limit"
+            ),
+        }
+
+        if rejected { pause_until } else { None }
+    }
+}
+
+impl RateLimitReporter for GitHubClient {
+    fn rate_limits(&self) -> Vec<RateLimitSnapshot> {
+        match self.rates.lock() {
+            Ok(r) => {
+                let mut v: Vec<RateLimitSnapshot> = r.values().cloned().collect();
+                v.sort_by(|a, b| (&a.source, &a.resource).cmp(&(&b.source, &b.resource)));
+                v
+            }
+            Err(_) => vec![],
+        }
+    }
+}
+
+/// Quota bucket a REST path counts against (`/search/*` has its own, much
+/// smaller quota); the `X-RateLimit-Resource` response header wins when present.
+fn resource_for(path: &str) -> &'static str {
+    if path.starts_with("/search/") {
+        "search"
+    } else {
+        "core"
+    }
+}
+
+/// "github" for api.github.com, "github@host" for everything else
+fn source_name(base_url: &str) -> String {
+    if base_url == DEFAULT_GITHUB_API_URL {
+        return "github".to_string();
+    }
+    let host = base_url
+        .split("://")
+        .nth(1)
+        .unwrap_or(base_url)
+        .split('/')
+        .next()
+        .unwrap_or(base_url);
+    format!("github@{host}")
+}
+
+fn now_epoch() -> i64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
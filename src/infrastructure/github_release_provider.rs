@@ -1,21 +1,18 @@
 use async_trait::async_trait;
-use reqwest::header::{ACCEPT, AUTHORIZATION, USER_AGENT};
 use serde::Deserialize;
 
 use crate::application::{AppError, AppResult, WatchProvider};
-use crate::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use crate::domain::{Event, EventType, Forge, Source, WatchKind, WatchTarget};
+use crate::infrastructure::github_client::GitHubClient;
+use crate::infrastructure::provider_registry::KindProvider;
 
 pub struct GitHubReleaseProvider {
-    client: reqwest::Client,
-    token: Option<String>,
+    github: GitHubClient,
 }
 
 impl GitHubReleaseProvider {
-    pub fn new(token: Option<String>) -> Self {
-        Self {
-            client: reqwest::Client::new(),
-            token,
-        }
+    pub fn new(github: GitHubClient) -> Self {
+        Self { github }
     }
 }
 
@@ -26,47 +23,44 @@ struct ReleaseResp {
     published_at: Option<String>,
 }
 
+impl KindProvider for GitHubReleaseProvider {
+    const KIND: &'static str = WatchKind::GITHUB_RELEASE;
+}
+
 #[async_trait]
 impl WatchProvider for GitHubReleaseProvider {
     async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
         let repo = match &target.kind {
-            WatchKind::GitHubRelease { repo } => repo,
+            WatchKind::GitHubRelease { repo }
+            | WatchKind::ForgeRelease {
+                forge: Forge::GitHub,
+                repo,
+            } => repo,
             _ => return Ok(None),
         };
 
-        let url = format!(
-            "https://api.github.com/repos/{}/releases/latest",
-            repo.as_str(),
-        );
-
-        let mut req = self
-            .client
-            .get(url)
-            .header(USER_AGENT, "repopulse")
-            .header(ACCEPT, "application/vnd.github.v3+json");
-
-        if let Some(token) = &self.token {
-            req = req.header(AUTHORIZATION, format!("Bearer {}", token));
-        }
-
-        let resp = req
-            .send()
-            .await
-            .map_err(|e| AppError::Provider(e.to_string()))?;
+        // 304 时 body 来自缓存, 不消耗 rate limit
+        let path = format!("/repos/{}/releases/latest", repo.as_str());
+        let resp = self
+            .github
+            .get(
+                target.endpoint.as_ref(),
+                &path,
+                "application/vnd.github.v3+json",
+                Some(&target.id),
+            )
+            .await?;
 
         // 没有 release (404) 不是错误
-        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        if resp.status == reqwest::StatusCode::NOT_FOUND {
             return Ok(None);
         }
+        if !resp.status.is_success() {
+            return Err(AppError::Provider(format!("HTTP status {}", resp.status)));
+        }
 
-        let resp = resp
-            .error_for_status()
-            .map_err(|e| AppError::Provider(e.to_string()))?;
-
-        let body: ReleaseResp = resp
-            .json()
-            .await
-            .map_err(|e| AppError::Provider(e.to_string()))?;
+        let body: ReleaseResp =
+            serde_json::from_str(&resp.body).map_err(|e| AppError::Provider(e.to_string()))?;
 
         let tag = match body.tag_name {
             Some(t) => t,
@@ -81,11 +75,12 @@ impl WatchProvider for GitHubReleaseProvider {
             event_type: EventType::GitHubRelease,
             source: Source::GitHub,
             subject,
-            old_value: None, // v1: 不在 provider 里算 old
+            old_value: None, // 由 RunOnceUseCase 根据 last value 填充
             new_value: tag,
             occurred_at: body.published_at,
             detected_at: chrono_now_rfc3339(),
             url: body.html_url,
+            meta: Default::default(),
         }))
     }
 }
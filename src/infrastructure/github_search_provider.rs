@@ -0,0 +1,329 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::application::{AppError, AppResult, ProviderStateStore, WatchProvider};
+use crate::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use crate::infrastructure::api_endpoint::encode_path_segment;
+use crate::infrastructure::github_client::GitHubClient;
+use crate::infrastructure::provider_registry::KindProvider;
+
+/// `Event::meta` keys set on search item events
+pub const META_ACTION: &str = "action";
+pub const META_REPO: &str = "repo";
+pub const META_NUMBER: &str = "number";
+/// "issue" | "pull_request"
+pub const META_ITEM_KIND: &str = "item_kind";
+pub const META_STATE: &str = "state";
+pub const META_LABELS: &str = "labels";
+/// the label a "labeled" event is about
+pub const META_LABEL: &str = "label";
+
+/// `META_ACTION` values
+pub const ACTION_OPENED: &str = "opened";
+pub const ACTION_CLOSED: &str = "closed";
+pub const ACTION_MERGED: &str = "merged";
+pub const ACTION_LABELED: &str = "labeled";
+
+/// 搜索结果最多取多少条 (GitHub search 单页上限 100)
+const PER_PAGE: usize = 50;
+
+/// Issues and pull requests matching a GitHub search query. Only transitions
+/// are reported: an item showing up open (new or reopened), a tracked open
+/// item closing / merging, a tracked item gaining a label. Ids carry the item's
+/// `updated_at` ("owner/repo#12:closed@2024-02-03T00:00:00Z"), so a reopen and
+/// a second close are reported again. The open items seen last time are kept
+/// as provider state so closes / merges are noticed even when the query
+/// (`is:open`) no longer matches them.
+pub struct GitHubSearchProvider {
+    github: GitHubClient,
+    store: Arc<dyn ProviderStateStore>,
+    /// target id -> 新快照, 事件处理完 (`commit`) 才写入 store
+    pending: Mutex<HashMap<String, String>>,
+}
+
+impl GitHubSearchProvider {
+    pub fn new(github: GitHubClient, store: Arc<dyn ProviderStateStore>) -> Self {
+        Self {
+            github,
+            store,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn items(&self, target: &WatchTarget) -> AppResult<Option<Vec<Event>>> {
+        let query = match &target.kind {
+            WatchKind::GitHubSearch { query } => query,
+            _ => return Ok(None),
+        };
+
+        let path = format!(
+            "/search/issues?q={}&sort=updated&order=desc&per_page={}",
+            encode_path_segment(query),
+            PER_PAGE
+        );
+        let resp = self
+            .github
+            .get(
+                target.endpoint.as_ref(),
+                &path,
+                "application/vnd.github+json",
+                Some(&target.id),
+            )
+            .await?;
+        // 304 时 body 来自缓存
+        if !resp.status.is_success() && !resp.not_modified {
+            return Err(AppError::Provider(format!("HTTP status {}", resp.status)));
+        }
+        let body: SearchResp =
+            serde_json::from_str(&resp.body).map_err(|e| AppError::Provider(e.to_string()))?;
+
+        let previous: BTreeMap<String, Tracked> =
+            match self.store.get_provider_state(&target.id).await? {
+                Some(state) => serde_json::from_str(&state).unwrap_or_default(),
+                None => BTreeMap::new(),
+            };
+
+        let mut current: Vec<IssueResp> = body.items;
+        // 单独查询失败的条目原样留在快照里, 下一轮再查
+        let mut unresolved: BTreeMap<String, Tracked> = BTreeMap::new();
+        // 之前匹配、现在不在结果里的 open 条目: 单独查一下是不是关了 / 合并了
+        let matched: BTreeSet<String> = current.iter().map(|i| i.key()).collect();
+        for (key, tracked) in &previous {
+            if matched.contains(key) || tracked.state != "open" {
+                continue;
+            }
+            let Some((repo, number)) = key.split_once('#') else {
+                continue;
+            };
+            match self.lookup(target, repo, number, key).await {
+                Ok(Some(issue)) if issue.state() != "open" => current.push(issue),
+                // 还开着 (只是不再匹配 query) / 删除 / 转移了: 不再跟踪
+                Ok(_) => {}
+                Err(e) => {
+                    warn!(target_id = %target.id, item = %key, error = %e, "look up search item failed");
+                    unresolved.insert(key.clone(), tracked.clone());
+                }
+            }
+        }
+
+        // 最早更新的在前
+        current.sort_by(|a, b| a.updated_at.cmp(&b.updated_at));
+
+        let subject = query.clone();
+        let mut events = vec![];
+        let mut snapshot: BTreeMap<String, Tracked> = BTreeMap::new();
+        for item in current {
+            let key = item.key();
+            let state = item.state().to_string();
+            let labels: Vec<String> = item.labels.iter().map(|l| l.name.clone()).collect();
+            let updated_at = item.updated_at.clone().unwrap_or_default();
+            let base_meta = |action: &str| -> BTreeMap<String, String> {
+                let (repo, number) = key.split_once('#').unwrap_or_default();
+                [
+                    (META_ACTION.to_string(), action.to_string()),
+                    (META_REPO.to_string(), repo.to_string()),
+                    (META_NUMBER.to_string(), number.to_string()),
+                    (META_ITEM_KIND.to_string(), item.kind().to_string()),
+                    (META_STATE.to_string(), state.clone()),
+                    (META_LABELS.to_string(), labels.join(", ")),
+                ]
+                .into()
+            };
+            let event = |id_suffix: &str, meta: BTreeMap<String, String>| Event {
+                event_id: Event::make_event_id(
+                    &EventType::GitHubSearchItem,
+                    &subject,
+                    &format!("{key}:{id_suffix}"),
+                ),
+                event_type: EventType::GitHubSearchItem,
+                source: Source::GitHub,
+                subject: subject.clone(),
+                old_value: None,
+                new_value: format!("{key} {}", item.title),
+                occurred_at: item.updated_at.clone(),
+                detected_at: now_string(),
+                url: Some(item.html_url.clone()),
+                meta,
+            };
+
+            let action = match state.as_str() {
+                "open" => ACTION_OPENED,
+                "merged" => ACTION_MERGED,
+                _ => ACTION_CLOSED,
+            };
+            match previous.get(&key) {
+                // 还开着: 只看新标签
+                Some(prev) if state == "open" => {
+                    for label in labels.iter().filter(|l| !prev.labels.contains(l)) {
+                        let mut meta = base_meta(ACTION_LABELED);
+                        meta.insert(META_LABEL.to_string(), label.clone());
+                        events.push(event(&format!("label:{label}@{updated_at}"), meta));
+                    }
+                }
+                // open -> closed / merged
+                Some(_) => events.push(event(&format!("{state}@{updated_at}"), base_meta(action))),
+                // 新出现 / 重新打开: 标签随 opened 事件一起
+                None if state == "open" => {
+                    events.push(event(&format!("{state}@{updated_at}"), base_meta(action)))
+                }
+                // 没跟踪过的已关闭条目 (query 本身匹配关闭的): 只报一次
+                None => events.push(event(&state, base_meta(action))),
+            }
+
+            if state == "open" {
+                snapshot.insert(key, Tracked { state, labels });
+            }
+        }
+        snapshot.extend(unresolved);
+
+        let state =
+            serde_json::to_string(&snapshot).map_err(|e| AppError::Provider(e.to_string()))?;
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(target.id.clone(), state);
+        }
+        Ok(Some(events))
+    }
+
+    /// GET one item that dropped out of the search results; `None` if it is
+    /// gone (deleted, or transferred to another repo).
+    async fn lookup(
+        &self,
+        target: &WatchTarget,
+        repo: &str,
+        number: &str,
+        key: &str,
+    ) -> AppResult<Option<IssueResp>> {
+        let resp = self
+            .github
+            .get(
+                target.endpoint.as_ref(),
+                &format!("/repos/{}/issues/{}", repo, number),
+                "application/vnd.github+json",
+                Some(&format!("{}:issue:{}", target.id, key)),
+            )
+            .await?;
+        if matches!(resp.status, StatusCode::NOT_FOUND | StatusCode::GONE) {
+            return Ok(None);
+        }
+        // 304 时 body 来自缓存
+        if !resp.status.is_success() && !resp.not_modified {
+            return Err(AppError::Provider(format!("HTTP status {}", resp.status)));
+        }
+        serde_json::from_str(&resp.body)
+            .map(Some)
+            .map_err(|e| AppError::Provider(e.to_string()))
+    }
+}
+
+impl KindProvider for GitHubSearchProvider {
+    const KIND: &'static str = WatchKind::GITHUB_SEARCH;
+}
+
+#[async_trait]
+impl WatchProvider for GitHubSearchProvider {
+    /// The most recently updated item only; the run loop uses `check_items`.
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        Ok(self.items(target).await?.and_then(|mut v| v.pop()))
+    }
+
+    async fn check_items(&self, target: &WatchTarget) -> AppResult<Vec<Event>> {
+        Ok(self.items(target).await?.unwrap_or_default())
+    }
+
+    async fn commit(&self, target: &WatchTarget) -> AppResult<()> {
+        let state = self
+            .pending
+            .lock()
+            .map_err(|_| AppError::Provider("lock poisoned".into()))?
+            .remove(&target.id);
+        if let Some(state) = state {
+            self.store.set_provider_state(&target.id, &state).await?;
+        }
+        Ok(())
+    }
+}
+
+/// What the snapshot remembers about an open item.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Tracked {
+    state: String,
+    labels: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResp {
+    #[serde(default)]
+    items: Vec<IssueResp>,
+}
+
+/// Search results and GET /repos/{repo}/issues/{n} share this shape.
+#[derive(Debug, Deserialize)]
+struct IssueResp {
+    number: u64,
+    title: String,
+    html_url: String,
+    /// "open" | "closed"
+    state: String,
+    /// "https://api.github.com/repos/owner/repo"
+    repository_url: String,
+    #[serde(default)]
+    labels: Vec<LabelResp>,
+    pull_request: Option<PullRequestRef>,
+    updated_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LabelResp {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestRef {
+    merged_at: Option<String>,
+}
+
+impl IssueResp {
+    /// "owner/repo#12"
+    fn key(&self) -> String {
+        let repo = self
+            .repository_url
+            .rsplitn(3, '/')
+            .take(2)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect::<Vec<_>>()
+            .join("/");
+        format!("{}#{}", repo, self.number)
+    }
+
+    /// "open" | "closed" | "merged"
+    fn state(&self) -> &str {
+        match &self.pull_request {
+            Some(pr) if pr.merged_at.is_some() => "merged",
+            _ => &self.state,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        if self.pull_request.is_some() {
+            "pull_request"
+        } else {
+            "issue"
+        }
+    }
+}
+
+fn now_string() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    format!("{}s_since_epoch", secs)
+}
@@ -0,0 +1,263 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use reqwest::header::LINK;
+use serde::Deserialize;
+
+use crate::application::{AppError, AppResult, WatchProvider};
+use crate::domain::{
+    Event, EventType, NameFilter, Source, TagOrder, WatchKind, WatchTarget, parse_version_lenient,
+};
+use crate::infrastructure::github_client::GitHubClient;
+use crate::infrastructure::provider_registry::KindProvider;
+
+const PER_PAGE: usize = 100;
+/// 标签列表最多翻多少页
+const MAX_TAG_PAGES: usize = 20;
+/// `created` 排序时每次 check 最多查多少个还不知道提交时间的 tag (每个 tag 一次请求)
+const MAX_DATE_LOOKUPS: usize = 100;
+
+/// Newest tag of a repository, by semver or by commit date.
+///
+/// All pages of `/repos/{repo}/tags` are read (GitHub sorts them by name, not
+/// by version or date) and sorted here. Commit dates for `created` order are
+/// looked up once per commit and remembered; a repository with many tags takes
+/// a few checks before all dates are known.
+pub struct GitHubTagProvider {
+    github: GitHubClient,
+    /// commit sha -> (commit date, commit html url); 提交内容不会变
+    dates: Mutex<HashMap<String, CommitInfo>>,
+}
+
+type CommitInfo = (Option<String>, Option<String>);
+
+impl GitHubTagProvider {
+    pub fn new(github: GitHubClient) -> Self {
+        Self {
+            github,
+            dates: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TagResp {
+    name: String,
+    commit: CommitRef,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitRef {
+    sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitCommitResp {
+    committer: Option<Signature>,
+    html_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Signature {
+    date: Option<String>,
+}
+
+impl KindProvider for GitHubTagProvider {
+    const KIND: &'static str = WatchKind::GITHUB_TAG;
+}
+
+#[async_trait]
+impl WatchProvider for GitHubTagProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let (repo, filter, order) = match &target.kind {
+            WatchKind::GitHubTag {
+                repo,
+                filter,
+                order,
+            } => (repo, filter, order),
+            _ => return Ok(None),
+        };
+
+        let tags = self.all_tags(target, &repo.as_str()).await?;
+        let tags = filter_tags(tags, filter.as_ref())?;
+
+        let newest = match order {
+            TagOrder::Semver => tags
+                .into_iter()
+                .filter_map(|t| parse_version_lenient(&t.name).map(|v| (v, t)))
+                .max_by(|a, b| a.0.cmp(&b.0))
+                .map(|(_, t)| (t, None, None)),
+            TagOrder::Created => self.newest_by_date(target, &repo.as_str(), tags).await?,
+        };
+        let Some((tag, date, html_url)) = newest else {
+            return Ok(None);
+        };
+
+        let subject = repo.as_str();
+        let url = html_url.unwrap_or_else(|| {
+            self.github.web_url(
+                target.endpoint.as_ref(),
+                &format!("/{}/commit/{}", subject, tag.commit.sha),
+            )
+        });
+        let event_id = Event::make_event_id(&EventType::GitHubTag, &subject, &tag.name);
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::GitHubTag,
+            source: Source::GitHub,
+            subject,
+            old_value: None,
+            new_value: tag.name,
+            occurred_at: date,
+            detected_at: now_string(),
+            url: Some(url),
+            meta: Default::default(),
+        }))
+    }
+}
+
+impl GitHubTagProvider {
+    /// Every tag of the repository, following `Link: <...>; rel="next"`.
+    async fn all_tags(&self, target: &WatchTarget, repo: &str) -> AppResult<Vec<TagResp>> {
+        let mut out = vec![];
+        for page in 1..=MAX_TAG_PAGES {
+            let path = format!("/repos/{}/tags?per_page={}&page={}", repo, PER_PAGE, page);
+            let cache_key = format!("{}:tags:{}", target.id, page);
+            let resp = self
+                .github
+                .get(
+                    target.endpoint.as_ref(),
+                    &path,
+                    "application/vnd.github+json",
+                    Some(&cache_key),
+                )
+                .await?;
+            // 304 时 body 来自缓存
+            if !resp.status.is_success() && !resp.not_modified {
+                return Err(AppError::Provider(format!("HTTP status {}", resp.status)));
+            }
+            let tags: Vec<TagResp> =
+                serde_json::from_str(&resp.body).map_err(|e| AppError::Provider(e.to_string()))?;
+            let full = tags.len() == PER_PAGE;
+            out.extend(tags);
+
+            // 304 的响应不一定带 Link: 整页满了就继续翻
+            let has_next = resp
+                .headers
+                .get(LINK)
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|l| l.contains("rel=\"next\""));
+            if !(has_next || (resp.not_modified && full)) {
+                break;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Pick the tag whose commit is most recent; returns (tag, commit date, commit html url).
+    async fn newest_by_date(
+        &self,
+        target: &WatchTarget,
+        repo: &str,
+        mut tags: Vec<TagResp>,
+    ) -> AppResult<Option<(TagResp, Option<String>, Option<String>)>> {
+        // 先查版本号高的, 它们最可能是最新的
+        tags.sort_by(|a, b| {
+            parse_version_lenient(&b.name)
+                .cmp(&parse_version_lenient(&a.name))
+                .then_with(|| b.name.cmp(&a.name))
+        });
+
+        let unknown: Vec<String> = {
+            let dates = self
+                .dates
+                .lock()
+                .map_err(|_| AppError::Provider("lock poisoned".into()))?;
+            let mut queued = HashSet::new();
+            tags.iter()
+                .map(|t| t.commit.sha.clone())
+                .filter(|sha| !dates.contains_key(sha) && queued.insert(sha.clone()))
+                .collect()
+        };
+        for sha in unknown.iter().take(MAX_DATE_LOOKUPS) {
+            let info = self.commit_info(target, repo, sha).await?;
+            if let Ok(mut dates) = self.dates.lock() {
+                dates.insert(sha.clone(), info);
+            }
+        }
+        // 还有没查到时间的 tag 时不给结果, 免得把不是最新的 tag 记成 last value
+        if unknown.len() > MAX_DATE_LOOKUPS {
+            return Err(AppError::Provider(format!(
+                "commit dates of {} tags still unknown, continuing next check",
+                unknown.len() - MAX_DATE_LOOKUPS
+            )));
+        }
+
+        let dates = self
+            .dates
+            .lock()
+            .map_err(|_| AppError::Provider("lock poisoned".into()))?;
+        let mut best: Option<(TagResp, Option<String>, Option<String>)> = None;
+        for tag in tags {
+            let (date, html_url) = dates.get(&tag.commit.sha).cloned().unwrap_or_default();
+            // RFC 3339 (UTC, "Z") 可以直接按字符串比较
+            let newer = match (&best, &date) {
+                (None, _) => true,
+                (Some((_, Some(b), _)), Some(d)) => d > b,
+                (Some((_, None, _)), Some(_)) => true,
+                (Some(_), None) => false,
+            };
+            if newer {
+                best = Some((tag, date, html_url));
+            }
+        }
+        Ok(best)
+    }
+
+    async fn commit_info(
+        &self,
+        target: &WatchTarget,
+        repo: &str,
+        sha: &str,
+    ) -> AppResult<CommitInfo> {
+        // 按 sha 缓存, 重启后也只是 304
+        let path = format!("/repos/{}/git/commits/{}", repo, sha);
+        let cache_key = format!("{}:commit:{}", target.id, sha);
+        let resp = self
+            .github
+            .get(
+                target.endpoint.as_ref(),
+                &path,
+                "application/vnd.github+json",
+                Some(&cache_key),
+            )
+            .await?;
+        if !resp.status.is_success() && !resp.not_modified {
+            return Err(AppError::Provider(format!("HTTP status {}", resp.status)));
+        }
+        let commit: GitCommitResp =
+            serde_json::from_str(&resp.body).map_err(|e| AppError::Provider(e.to_string()))?;
+        Ok((commit.committer.and_then(|c| c.date), commit.html_url))
+    }
+}
+
+fn filter_tags(tags: Vec<TagResp>, filter: Option<&NameFilter>) -> AppResult<Vec<TagResp>> {
+    let Some(filter) = filter else {
+        return Ok(tags);
+    };
+    let re = filter
+        .compile()
+        .map_err(|e| AppError::Provider(e.to_string()))?;
+    Ok(tags.into_iter().filter(|t| re.is_match(&t.name)).collect())
+}
+
+fn now_string() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    format!("{}s_since_epoch", secs)
+}
@@ -0,0 +1,128 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::application::{AppError, AppResult, WatchProvider};
+use crate::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use crate::infrastructure::api_endpoint::encode_path_segment;
+use crate::infrastructure::github_client::GitHubClient;
+use crate::infrastructure::provider_registry::KindProvider;
+
+/// `Event::meta` keys set on workflow run events
+pub const META_RUN_ID: &str = "run_id";
+pub const META_RUN_NUMBER: &str = "run_number";
+pub const META_HEAD_SHA: &str = "head_sha";
+
+/// 这些结论不说明代码好坏, 跳过它们看更早的 run
+const IGNORED_CONCLUSIONS: &[&str] = &["cancelled", "skipped", "neutral", "stale"];
+
+/// Conclusion ("success", "failure", "timed_out", ...) of the latest finished
+/// run of a workflow on a branch; the value only changes on transitions such as
+/// success -> failure, so that's when events fire.
+pub struct GitHubWorkflowProvider {
+    github: GitHubClient,
+}
+
+impl GitHubWorkflowProvider {
+    pub fn new(github: GitHubClient) -> Self {
+        Self { github }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RunsResp {
+    #[serde(default)]
+    workflow_runs: Vec<RunResp>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RunResp {
+    id: u64,
+    run_number: u64,
+    conclusion: Option<String>,
+    head_sha: String,
+    html_url: String,
+    updated_at: Option<String>,
+}
+
+impl KindProvider for GitHubWorkflowProvider {
+    const KIND: &'static str = WatchKind::GITHUB_WORKFLOW;
+}
+
+#[async_trait]
+impl WatchProvider for GitHubWorkflowProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let (repo, workflow, branch) = match &target.kind {
+            WatchKind::GitHubWorkflow {
+                repo,
+                workflow,
+                branch,
+            } => (repo, workflow, branch),
+            _ => return Ok(None),
+        };
+
+        // 304 时 body 来自缓存, 不消耗 rate limit
+        let path = format!(
+            "/repos/{}/actions/workflows/{}/runs?branch={}&status=completed&exclude_pull_requests=true&per_page=20",
+            repo.as_str(),
+            encode_path_segment(workflow),
+            encode_path_segment(branch)
+        );
+        let resp = self
+            .github
+            .get(
+                target.endpoint.as_ref(),
+                &path,
+                "application/vnd.github+json",
+                Some(&target.id),
+            )
+            .await?;
+        if !resp.status.is_success() {
+            return Err(AppError::Provider(format!("HTTP status {}", resp.status)));
+        }
+
+        let body: RunsResp =
+            serde_json::from_str(&resp.body).map_err(|e| AppError::Provider(e.to_string()))?;
+        // runs 按创建时间倒序
+        let Some((run, conclusion)) = body.workflow_runs.into_iter().find_map(|r| {
+            let c = r.conclusion.clone()?;
+            (!IGNORED_CONCLUSIONS.contains(&c.as_str())).then_some((r, c))
+        }) else {
+            return Ok(None);
+        };
+
+        let subject = target.kind.subject();
+        // 同一结论会反复出现 (failure -> success -> failure), id 里带上 run
+        let event_id = Event::make_event_id(
+            &EventType::GitHubWorkflow,
+            &subject,
+            &format!("{}@{}", conclusion, run.id),
+        );
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::GitHubWorkflow,
+            source: Source::GitHub,
+            subject,
+            old_value: None,
+            new_value: conclusion,
+            occurred_at: run.updated_at,
+            detected_at: now_string(),
+            url: Some(run.html_url),
+            meta: [
+                (META_RUN_ID.to_string(), run.id.to_string()),
+                (META_RUN_NUMBER.to_string(), run.run_number.to_string()),
+                (META_HEAD_SHA.to_string(), run.head_sha),
+            ]
+            .into(),
+        }))
+    }
+}
+
+fn now_string() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    format!("{}s_since_epoch", secs)
+}
@@ -0,0 +1,249 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::application::{AppError, AppResult, WatchProvider};
+use crate::domain::{Event, EventType, Forge, RepoId, Source, WatchKind, WatchTarget};
+use crate::infrastructure::api_endpoint::{ApiEndpoint, encode_path_segment};
+use crate::infrastructure::provider_registry::KindProvider;
+
+pub const DEFAULT_GITLAB_API_URL: &str = "https://gitlab.com/api/v4";
+
+/// 一页里跳过 upcoming release 后总能找到已发布的
+const RELEASES_PAGE_SIZE: u32 = 20;
+
+/// GitLab REST (v4) access shared by the release / branch providers.
+#[derive(Clone)]
+struct GitLabApi {
+    client: reqwest::Client,
+    default: ApiEndpoint,
+}
+
+impl GitLabApi {
+    fn new(default: ApiEndpoint) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            default,
+        }
+    }
+
+    /// GET `/projects/{id}{path}`; the project is addressed by its url-encoded path.
+    async fn get_project(
+        &self,
+        target: &WatchTarget,
+        repo: &RepoId,
+        path: &str,
+    ) -> AppResult<(ApiEndpoint, reqwest::Response)> {
+        let api = self.default.resolve(target.endpoint.as_ref());
+        let url = api.url(&format!(
+            "/projects/{}{}",
+            encode_path_segment(&repo.as_str()),
+            path
+        ));
+        let resp = api
+            .authorize(self.client.get(url))
+            .send()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+        if !resp.status().is_success() {
+            return Err(AppError::Provider(format!("HTTP status {}", resp.status())));
+        }
+        Ok((api, resp))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseResp {
+    tag_name: String,
+    released_at: Option<String>,
+    #[serde(default)]
+    upcoming_release: bool,
+    #[serde(rename = "_links")]
+    links: Option<ReleaseLinks>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseLinks {
+    #[serde(rename = "self")]
+    self_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BranchResp {
+    commit: BranchCommit,
+    web_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BranchCommit {
+    id: String,
+    committed_date: Option<String>,
+}
+
+/// Web root of the instance: `https://gitlab.example.com/api/v4` -> `https://gitlab.example.com`.
+fn web_url(api: &ApiEndpoint, path: &str) -> String {
+    format!("{}{}", api.base_url.trim_end_matches("/api/v4"), path)
+}
+
+pub struct GitLabReleaseProvider {
+    api: GitLabApi,
+}
+
+impl GitLabReleaseProvider {
+    pub fn new() -> Self {
+        Self::with_endpoint(ApiEndpoint::new(DEFAULT_GITLAB_API_URL))
+    }
+
+    /// Use a self-managed instance (its `/api/v4` root) by default.
+    pub fn with_endpoint(endpoint: ApiEndpoint) -> Self {
+        Self {
+            api: GitLabApi::new(endpoint),
+        }
+    }
+}
+
+impl Default for GitLabReleaseProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KindProvider for GitLabReleaseProvider {
+    const KIND: &'static str = WatchKind::GITLAB_RELEASE;
+}
+
+#[async_trait]
+impl WatchProvider for GitLabReleaseProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let repo = match &target.kind {
+            WatchKind::ForgeRelease {
+                forge: Forge::GitLab,
+                repo,
+            } => repo,
+            _ => return Ok(None),
+        };
+
+        // 默认按 released_at 倒序
+        let (api, resp) = self
+            .api
+            .get_project(
+                target,
+                repo,
+                &format!("/releases?per_page={}", RELEASES_PAGE_SIZE),
+            )
+            .await?;
+        let releases: Vec<ReleaseResp> = resp
+            .json()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+
+        // 没有 release 不是错误; upcoming release 还没发布
+        let Some(release) = releases.into_iter().find(|r| !r.upcoming_release) else {
+            return Ok(None);
+        };
+
+        let subject = repo.as_str();
+        let event_id = Event::make_event_id(&EventType::GitLabRelease, &subject, &release.tag_name);
+        let url = release.links.and_then(|l| l.self_url).unwrap_or_else(|| {
+            web_url(
+                &api,
+                &format!("/{}/-/releases/{}", subject, release.tag_name),
+            )
+        });
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::GitLabRelease,
+            source: Source::GitLab,
+            subject,
+            old_value: None,
+            new_value: release.tag_name,
+            occurred_at: release.released_at,
+            detected_at: now_string(),
+            url: Some(url),
+            meta: Default::default(),
+        }))
+    }
+}
+
+pub struct GitLabBranchProvider {
+    api: GitLabApi,
+}
+
+impl GitLabBranchProvider {
+    pub fn new() -> Self {
+        Self::with_endpoint(ApiEndpoint::new(DEFAULT_GITLAB_API_URL))
+    }
+
+    /// Use a self-managed instance (its `/api/v4` root) by default.
+    pub fn with_endpoint(endpoint: ApiEndpoint) -> Self {
+        Self {
+            api: GitLabApi::new(endpoint),
+        }
+    }
+}
+
+impl Default for GitLabBranchProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KindProvider for GitLabBranchProvider {
+    const KIND: &'static str = WatchKind::GITLAB_BRANCH;
+}
+
+#[async_trait]
+impl WatchProvider for GitLabBranchProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let (repo, branch) = match &target.kind {
+            WatchKind::ForgeBranch {
+                forge: Forge::GitLab,
+                repo,
+                branch,
+            } => (repo, branch),
+            _ => return Ok(None),
+        };
+
+        let (api, resp) = self
+            .api
+            .get_project(
+                target,
+                repo,
+                &format!("/repository/branches/{}", encode_path_segment(branch)),
+            )
+            .await?;
+        let body: BranchResp = resp
+            .json()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+
+        let sha = body.commit.id;
+        let subject = format!("{}#{}", repo.as_str(), branch);
+        let event_id = Event::make_event_id(&EventType::GitLabBranch, &subject, &sha);
+        let url = body
+            .web_url
+            .unwrap_or_else(|| web_url(&api, &format!("/{}/-/tree/{}", repo.as_str(), branch)));
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::GitLabBranch,
+            source: Source::GitLab,
+            subject,
+            old_value: None,
+            new_value: sha,
+            occurred_at: body.commit.committed_date,
+            detected_at: now_string(),
+            url: Some(url),
+            meta: Default::default(),
+        }))
+    }
+}
+
+fn now_string() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    format!("{}s_since_epoch", secs)
+}
@@ -0,0 +1,276 @@
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::application::{AppError, AppResult, WatchProvider};
+use crate::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use crate::infrastructure::api_endpoint::ApiEndpoint;
+use crate::infrastructure::provider_registry::KindProvider;
+
+pub const DEFAULT_GOPROXY_URL: &str = "https://proxy.golang.org";
+
+/// `Event::meta` key: module path of the next major version, when it exists
+pub const META_NEWER_MAJOR: &str = "newer_major";
+
+/// Latest version of a Go module from a GOPROXY (`/@v/list`, falling back to
+/// `/@latest` for modules that only have pseudo-versions).
+///
+/// Semantic import versioning: `example.com/m` only covers v0/v1 (plus legacy
+/// `+incompatible` tags), `example.com/m/v3` / `gopkg.in/m.v3` only v3. When the
+/// next major version's module path exists it is reported in the event meta.
+pub struct GoModuleProvider {
+    client: reqwest::Client,
+    proxy: ApiEndpoint,
+}
+
+impl GoModuleProvider {
+    pub fn new() -> Self {
+        Self::with_proxy(ApiEndpoint::new(DEFAULT_GOPROXY_URL))
+    }
+
+    /// Use another GOPROXY (Athens, Artifactory, local stand-in).
+    pub fn with_proxy(proxy: ApiEndpoint) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            proxy,
+        }
+    }
+}
+
+impl Default for GoModuleProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct InfoResp {
+    #[serde(rename = "Version")]
+    version: String,
+    #[serde(rename = "Time")]
+    time: Option<String>,
+}
+
+/// Module path split into the part before the major suffix and the major it pins.
+struct ModulePath<'a> {
+    prefix: &'a str,
+    /// Some(n) for ".../vN" (n >= 2) and "gopkg.in/x.vN"
+    major: Option<u64>,
+    gopkg_in: bool,
+}
+
+impl<'a> ModulePath<'a> {
+    fn parse(path: &'a str) -> Self {
+        let gopkg_in = path.starts_with("gopkg.in/");
+        let (sep, min) = if gopkg_in { (".v", 0) } else { ("/v", 2) };
+        if let Some((prefix, n)) = path.rsplit_once(sep)
+            && let Ok(n) = n.parse::<u64>()
+            && n >= min
+        {
+            return Self {
+                prefix,
+                major: Some(n),
+                gopkg_in,
+            };
+        }
+        Self {
+            prefix: path,
+            major: None,
+            gopkg_in,
+        }
+    }
+
+    /// Whether `v` belongs to this module path; `None` if it doesn't,
+    /// `Some(false)` for a legacy `+incompatible` version.
+    fn accepts(&self, v: &semver::Version) -> Option<bool> {
+        let incompatible = v.build.as_str() == "incompatible";
+        match self.major {
+            Some(n) => (v.major == n && !incompatible).then_some(true),
+            None if v.major <= 1 && !incompatible => Some(true),
+            None if incompatible => Some(false),
+            None => None,
+        }
+    }
+
+    fn next_major(&self) -> String {
+        let next = self.major.unwrap_or(1).max(1) + 1;
+        if self.gopkg_in {
+            format!("{}.v{}", self.prefix, next)
+        } else {
+            format!("{}/v{}", self.prefix, next)
+        }
+    }
+}
+
+/// GOPROXY case-encoding: upper-case letters become "!" + lower-case.
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('!');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+impl GoModuleProvider {
+    /// GET `{proxy}/{module}/{suffix}`; `None` for 404/410 (the proxy's "no such module").
+    async fn get(
+        &self,
+        proxy: &ApiEndpoint,
+        module: &str,
+        suffix: &str,
+    ) -> AppResult<Option<reqwest::Response>> {
+        let url = proxy.url(&format!("/{}/{}", escape(module), suffix));
+        let resp = proxy
+            .authorize(self.client.get(url))
+            .send()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+        if matches!(resp.status(), StatusCode::NOT_FOUND | StatusCode::GONE) {
+            return Ok(None);
+        }
+        let resp = resp
+            .error_for_status()
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+        Ok(Some(resp))
+    }
+
+    async fn list(&self, proxy: &ApiEndpoint, module: &str) -> AppResult<Option<Vec<String>>> {
+        let Some(resp) = self.get(proxy, module, "@v/list").await? else {
+            return Ok(None);
+        };
+        let body = resp
+            .text()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+        Ok(Some(
+            body.lines()
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty())
+                .collect(),
+        ))
+    }
+
+    async fn info(
+        &self,
+        proxy: &ApiEndpoint,
+        module: &str,
+        suffix: &str,
+    ) -> AppResult<Option<InfoResp>> {
+        let Some(resp) = self.get(proxy, module, suffix).await? else {
+            return Ok(None);
+        };
+        let info = resp
+            .json()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+        Ok(Some(info))
+    }
+}
+
+impl KindProvider for GoModuleProvider {
+    const KIND: &'static str = WatchKind::GO_MODULE;
+}
+
+#[async_trait]
+impl WatchProvider for GoModuleProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let (module, include_prereleases) = match &target.kind {
+            WatchKind::GoModule {
+                module,
+                include_prereleases,
+            } => (module, *include_prereleases),
+            _ => return Ok(None),
+        };
+
+        let proxy = self.proxy.resolve(target.endpoint.as_ref());
+        let path = ModulePath::parse(module);
+
+        let Some(listed) = self.list(&proxy, module).await? else {
+            return Err(AppError::Provider(format!("module not found: {module}")));
+        };
+
+        let candidates: Vec<(semver::Version, bool, String)> = listed
+            .into_iter()
+            .filter_map(|v| {
+                let parsed = semver::Version::parse(v.strip_prefix('v')?).ok()?;
+                let compatible = path.accepts(&parsed)?;
+                (include_prereleases || parsed.pre.is_empty()).then_some((parsed, compatible, v))
+            })
+            .collect();
+        // 有正常版本时不考虑 +incompatible (和 go 命令的选择一致)
+        let any_compatible = candidates.iter().any(|c| c.1);
+        let best = candidates
+            .into_iter()
+            .filter(|c| c.1 || !any_compatible)
+            .max_by(|a, b| a.0.cmp(&b.0))
+            .map(|c| c.2);
+
+        // 只有伪版本的模块: 用 @latest
+        let info = match best {
+            Some(v) => self
+                .info(&proxy, module, &format!("@v/{}.info", escape(&v)))
+                .await?
+                .unwrap_or(InfoResp {
+                    version: v,
+                    time: None,
+                }),
+            None => match self.info(&proxy, module, "@latest").await? {
+                Some(info) => info,
+                None => return Ok(None),
+            },
+        };
+
+        let mut meta = std::collections::BTreeMap::new();
+        let next = path.next_major();
+        // 探测失败不影响本次结果
+        if let Ok(Some(versions)) = self.list(&proxy, &next).await
+            && !versions.is_empty()
+        {
+            meta.insert(META_NEWER_MAJOR.to_string(), next);
+        }
+
+        let subject = module.to_string();
+        let event_id = Event::make_event_id(&EventType::GoModule, &subject, &info.version);
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::GoModule,
+            source: Source::Go,
+            subject,
+            old_value: None,
+            url: Some(module_page_url(&proxy, module, &info.version)),
+            new_value: info.version,
+            occurred_at: info.time,
+            detected_at: now_string(),
+            meta,
+        }))
+    }
+}
+
+/// pkg.go.dev for the public proxy, the proxy's `.info` document otherwise
+fn module_page_url(proxy: &ApiEndpoint, module: &str, version: &str) -> String {
+    if proxy.base_url == DEFAULT_GOPROXY_URL {
+        format!("https://pkg.go.dev/{}@{}", module, version)
+    } else {
+        format!(
+            "{}/{}/@v/{}.info",
+            proxy.base_url,
+            escape(module),
+            escape(version)
+        )
+    }
+}
+
+fn now_string() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    format!("{}s_since_epoch", secs)
+}
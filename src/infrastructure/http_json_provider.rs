@@ -0,0 +1,131 @@
+use async_trait::async_trait;
+use reqwest::header::{ACCEPT, USER_AGENT};
+use serde_json::Value;
+use serde_json_path::JsonPath;
+
+use crate::application::{AppError, AppResult, WatchProvider};
+use crate::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use crate::infrastructure::api_endpoint::ApiEndpoint;
+use crate::infrastructure::provider_registry::KindProvider;
+
+/// Watches a value selected from any JSON endpoint (vendor status pages,
+/// internal version APIs) without a dedicated provider.
+pub struct HttpJsonProvider {
+    client: reqwest::Client,
+}
+
+impl HttpJsonProvider {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+impl Default for HttpJsonProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KindProvider for HttpJsonProvider {
+    const KIND: &'static str = WatchKind::HTTP_JSON;
+}
+
+#[async_trait]
+impl WatchProvider for HttpJsonProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let (url, headers, selector, expected_status, link_template) = match &target.kind {
+            WatchKind::HttpJson {
+                url,
+                headers,
+                selector,
+                expected_status,
+                link_template,
+            } => (url, headers, selector, *expected_status, link_template),
+            _ => return Ok(None),
+        };
+        let path = JsonPath::parse(selector)
+            .map_err(|e| AppError::Provider(format!("invalid selector {selector}: {e}")))?;
+
+        let mut req = self
+            .client
+            .get(url)
+            .header(USER_AGENT, "repopulse")
+            .header(ACCEPT, "application/json");
+        for (name, value) in headers {
+            req = req.header(name, value);
+        }
+        // target 级 token / username 同样可用 (base_url 对这里没有意义)
+        let req = ApiEndpoint::default()
+            .resolve(target.endpoint.as_ref())
+            .authorize(req);
+
+        let resp = req
+            .send()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+        if resp.status().as_u16() != expected_status {
+            return Err(AppError::Provider(format!(
+                "HTTP status {} (expected {})",
+                resp.status(),
+                expected_status
+            )));
+        }
+        let body: Value = resp
+            .json()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+
+        let value = select_value(&path, &body)
+            .ok_or_else(|| AppError::Provider(format!("selector {selector} matched nothing")))?;
+
+        let subject = url.clone();
+        // 状态会来回切换 (none -> minor -> none): 确认变化后 run loop 按 old -> new 重新生成 id
+        let event_id = Event::make_event_id(&EventType::HttpJson, &subject, &value);
+        let link = match link_template {
+            Some(t) => t.replace("{value}", &value),
+            None => url.clone(),
+        };
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::HttpJson,
+            source: Source::Http,
+            subject,
+            old_value: None,
+            new_value: value,
+            occurred_at: None,
+            detected_at: now_string(),
+            url: Some(link),
+            meta: Default::default(),
+        }))
+    }
+}
+
+/// Strings are taken as-is, other values as compact JSON; several matches are
+/// joined with ", " in document order.
+fn select_value(path: &JsonPath, body: &Value) -> Option<String> {
+    let values: Vec<String> = path
+        .query(body)
+        .all()
+        .into_iter()
+        .map(|v| match v {
+            Value::String(s) => s.trim().to_string(),
+            other => other.to_string(),
+        })
+        .collect();
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.join(", "))
+}
+
+fn now_string() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    format!("{}s_since_epoch", secs)
+}
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use reqwest::header::{ACCEPT, USER_AGENT};
+use scraper::{Html, Node, Selector};
+use sha2::{Digest, Sha256};
+use similar::{ChangeTag, TextDiff};
+
+use crate::application::{AppError, AppResult, HttpCacheStore, ProviderStateStore, WatchProvider};
+use crate::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use crate::infrastructure::api_endpoint::ApiEndpoint;
+use crate::infrastructure::conditional_get::send_conditional;
+use crate::infrastructure::provider_registry::KindProvider;
+
+/// `Event::meta` key: changed lines ("- old" / "+ new") since the previous snapshot
+pub const META_DIFF: &str = "diff";
+
+/// 通知里最多列出多少行变化, 每行最多多少字符
+const MAX_DIFF_LINES: usize = 20;
+const MAX_LINE_CHARS: usize = 200;
+
+/// Watches the text of a web page (changelog, download page). The value is a
+/// short hash of the normalized text; the text itself is kept as provider
+/// state so the next change can be shown as a diff.
+pub struct HttpPageProvider {
+    client: reqwest::Client,
+    cache: Arc<dyn HttpCacheStore>,
+    state: Arc<dyn ProviderStateStore>,
+    /// target id -> 新文本, 事件处理完 (`commit`) 才写入 state
+    pending: Mutex<HashMap<String, String>>,
+}
+
+impl HttpPageProvider {
+    pub fn new(cache: Arc<dyn HttpCacheStore>, state: Arc<dyn ProviderStateStore>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            cache,
+            state,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl KindProvider for HttpPageProvider {
+    const KIND: &'static str = WatchKind::HTTP_PAGE;
+}
+
+#[async_trait]
+impl WatchProvider for HttpPageProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let (url, headers, selector, regex) = match &target.kind {
+            WatchKind::HttpPage {
+                url,
+                headers,
+                selector,
+                regex,
+            } => (url, headers, selector, regex),
+            _ => return Ok(None),
+        };
+
+        let mut req = self
+            .client
+            .get(url)
+            .header(USER_AGENT, "repopulse")
+            .header(ACCEPT, "text/html,*/*");
+        for (name, value) in headers {
+            req = req.header(name, value);
+        }
+        let req = ApiEndpoint::default()
+            .resolve(target.endpoint.as_ref())
+            .authorize(req);
+
+        // 304 时 body 来自缓存
+        let resp = send_conditional(Some(self.cache.as_ref()), &target.id, req).await?;
+        if !resp.status.is_success() && !resp.not_modified {
+            return Err(AppError::Provider(format!("HTTP status {}", resp.status)));
+        }
+
+        let text = extract(&resp.body, selector.as_deref(), regex.as_deref())?;
+        if text.is_empty() {
+            return Err(AppError::Provider("selected content is empty".into()));
+        }
+        let hash = short_hash(&text);
+
+        // 和上次处理过的文本比较
+        let previous = self.state.get_provider_state(&target.id).await?;
+        let mut meta = std::collections::BTreeMap::new();
+        if previous.as_deref() != Some(text.as_str()) {
+            if let Some(prev) = &previous {
+                meta.insert(META_DIFF.to_string(), short_diff(prev, &text));
+            }
+            if let Ok(mut pending) = self.pending.lock() {
+                pending.insert(target.id.clone(), text);
+            }
+        }
+
+        let subject = url.clone();
+        let event_id = Event::make_event_id(&EventType::HttpPage, &subject, &hash);
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::HttpPage,
+            source: Source::Http,
+            subject,
+            old_value: None,
+            new_value: hash,
+            occurred_at: None,
+            detected_at: now_string(),
+            url: Some(url.clone()),
+            meta,
+        }))
+    }
+
+    async fn commit(&self, target: &WatchTarget) -> AppResult<()> {
+        let text = self
+            .pending
+            .lock()
+            .map_err(|_| AppError::Provider("lock poisoned".into()))?
+            .remove(&target.id);
+        if let Some(text) = text {
+            self.state.set_provider_state(&target.id, &text).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Narrow the page and normalize it to one trimmed, whitespace-collapsed line per
+/// text block:
+/// - selector only: text of the matched elements
+/// - regex only: captures over the raw HTML
+/// - both: captures over the matched elements' HTML
+/// - neither: text of the whole document (scripts / styles excluded)
+fn extract(body: &str, selector: Option<&str>, regex: Option<&str>) -> AppResult<String> {
+    let doc = Html::parse_document(body);
+    let selected: Option<Vec<scraper::ElementRef>> = match selector {
+        Some(s) => {
+            let sel = Selector::parse(s)
+                .map_err(|e| AppError::Provider(format!("invalid css selector {s}: {e}")))?;
+            let elements: Vec<_> = doc.select(&sel).collect();
+            if elements.is_empty() {
+                return Err(AppError::Provider(format!("selector {s} matched nothing")));
+            }
+            Some(elements)
+        }
+        None => None,
+    };
+
+    let lines: Vec<String> = match regex {
+        Some(pattern) => {
+            let re = regex::Regex::new(pattern).map_err(|e| AppError::Provider(e.to_string()))?;
+            let haystack = match &selected {
+                Some(elements) => elements
+                    .iter()
+                    .map(|e| e.html())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                None => body.to_string(),
+            };
+            let captures: Vec<String> = re
+                .captures_iter(&haystack)
+                .filter_map(|c| c.get(1).or_else(|| c.get(0)))
+                .map(|m| m.as_str().to_string())
+                .collect();
+            if captures.is_empty() {
+                return Err(AppError::Provider(format!(
+                    "regex {pattern} matched nothing"
+                )));
+            }
+            captures
+        }
+        None => match &selected {
+            Some(elements) => elements.iter().flat_map(|e| text_lines(e)).collect(),
+            None => text_lines(&doc.root_element()),
+        },
+    };
+
+    Ok(lines
+        .iter()
+        .map(|l| normalize_whitespace(l))
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Text nodes under `element`, skipping script / style / noscript / template.
+fn text_lines(element: &scraper::ElementRef) -> Vec<String> {
+    const SKIPPED: [&str; 4] = ["script", "style", "noscript", "template"];
+    element
+        .descendants()
+        .filter_map(|node| match node.value() {
+            Node::Text(t) => {
+                let hidden = node.ancestors().any(|a| {
+                    a.value()
+                        .as_element()
+                        .is_some_and(|e| SKIPPED.contains(&e.name()))
+                });
+                (!hidden).then(|| t.to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn normalize_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// first 16 hex chars of sha256
+fn short_hash(text: &str) -> String {
+    let digest = Sha256::digest(text.as_bytes());
+    digest
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Changed lines only, "- " removed / "+ " added, capped for notifications.
+fn short_diff(old: &str, new: &str) -> String {
+    let diff = TextDiff::from_lines(old, new);
+    let changes: Vec<String> = diff
+        .iter_all_changes()
+        .filter_map(|c| {
+            let sign = match c.tag() {
+                ChangeTag::Delete => "-",
+                ChangeTag::Insert => "+",
+                ChangeTag::Equal => return None,
+            };
+            let line = c.value().trim_end_matches('\n');
+            let line: String = match line.char_indices().nth(MAX_LINE_CHARS) {
+                Some((i, _)) => format!("{}...", &line[..i]),
+                None => line.to_string(),
+            };
+            Some(format!("{sign} {line}"))
+        })
+        .collect();
+
+    let mut out: Vec<String> = changes.iter().take(MAX_DIFF_LINES).cloned().collect();
+    if changes.len() > MAX_DIFF_LINES {
+        out.push(format!("... and {} more", changes.len() - MAX_DIFF_LINES));
+    }
+    out.join("\n")
+}
+
+fn now_string() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    format!("{}s_since_epoch", secs)
+}
@@ -0,0 +1,579 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use regex::Regex;
+
+use crate::application::{AppError, AppResult, TargetRepository};
+use crate::domain::{WatchKind, WatchTarget};
+
+/// Label prefix of discovered targets, e.g. "manifest:apps/web/package.json".
+pub const MANIFEST_LABEL_PREFIX: &str = "manifest:";
+
+/// 发现时会扫到的文件名
+const MANIFEST_FILES: &[&str] = &[
+    "package.json",
+    "package-lock.json",
+    "Cargo.toml",
+    "Cargo.lock",
+    "requirements.txt",
+];
+
+/// 目录递归时跳过 (依赖 / 构建产物 / 虚拟环境)
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "venv", "__pycache__"];
+
+/// A manifest file, or a directory searched recursively for manifests, whose
+/// dependencies become `npm_latest` / `crates_io_latest` / `pypi_latest` targets.
+#[derive(Debug, Clone)]
+pub struct ManifestSource {
+    pub path: PathBuf,
+    /// added to every target found here, besides the "manifest:<path>" label
+    pub labels: Vec<String>,
+    /// also devDependencies / dev- and build-dependencies
+    pub dev_dependencies: bool,
+}
+
+impl ManifestSource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            labels: vec![],
+            dev_dependencies: false,
+        }
+    }
+
+    pub fn with_labels(mut self, labels: Vec<String>) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    pub fn with_dev_dependencies(mut self, dev: bool) -> Self {
+        self.dev_dependencies = dev;
+        self
+    }
+
+    /// Manifest files under this source, in path order.
+    pub fn manifest_files(&self) -> Vec<PathBuf> {
+        let mut out = vec![];
+        if self.path.is_dir() {
+            walk(&self.path, &mut out);
+        } else if is_manifest(&self.path) {
+            out.push(self.path.clone());
+        }
+        out.sort();
+        out
+    }
+
+    /// Targets for every dependency of every manifest; a package listed in
+    /// several manifests becomes one target carrying all their labels.
+    pub fn discover(&self) -> Vec<WatchTarget> {
+        let mut targets: BTreeMap<(&'static str, String), WatchTarget> = BTreeMap::new();
+        for file in self.manifest_files() {
+            let deps = match read_dependencies(&file, self.dev_dependencies) {
+                Ok(d) => d,
+                Err(e) => {
+                    tracing::warn!(manifest = %file.display(), "skipping manifest: {e}");
+                    continue;
+                }
+            };
+            let label = format!(
+                "{}{}",
+                MANIFEST_LABEL_PREFIX,
+                file.to_string_lossy().replace('\\', "/")
+            );
+            for kind in deps {
+                let id = target_id(&kind);
+                let key = package_key(&kind).unwrap_or_else(|| ("", id.clone()));
+                let target = targets.entry(key).or_insert_with(|| WatchTarget {
+                    id,
+                    enabled: true,
+                    labels: self.labels.clone(),
+                    kind,
+                    schedule: None,
+                    endpoint: None,
+                    version_policy: None,
+                });
+                if !target.labels.contains(&label) {
+                    target.labels.push(label.clone());
+                }
+            }
+        }
+        targets.into_values().collect()
+    }
+}
+
+/// Same ids as the equivalent `config.yaml` targets.
+fn target_id(kind: &WatchKind) -> String {
+    match kind {
+        WatchKind::NpmLatest { package } => format!("npm:{}:latest", package),
+        WatchKind::CratesIoLatest { name, .. } => format!("crates-io:{}:latest", name),
+        WatchKind::PyPiLatest { project, .. } => format!("pypi:{}:latest", project),
+        other => format!("{}:{}", other.key(), other.subject()),
+    }
+}
+
+/// (kind, package name as the registry compares it) of a target discovery can
+/// produce: PyPI names per PEP 503, crates.io names ignore case and `-` / `_`,
+/// npm names are exact. A configured target with the same key, whatever its id,
+/// takes precedence over a discovered one.
+fn package_key(kind: &WatchKind) -> Option<(&'static str, String)> {
+    match kind {
+        WatchKind::NpmLatest { package } => Some((WatchKind::NPM_LATEST, package.clone())),
+        WatchKind::CratesIoLatest { name, .. } => Some((
+            WatchKind::CRATES_IO_LATEST,
+            name.to_ascii_lowercase().replace('_', "-"),
+        )),
+        WatchKind::PyPiLatest { project, .. } => {
+            Some((WatchKind::PYPI_LATEST, normalize_pypi_name(project)))
+        }
+        _ => None,
+    }
+}
+
+/// PEP 503: lowercase, runs of `-` `_` `.` become one `-`
+fn normalize_pypi_name(name: &str) -> String {
+    static SEPARATORS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[-_.]+").unwrap());
+    SEPARATORS.replace_all(name, "-").to_ascii_lowercase()
+}
+
+fn is_manifest(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| MANIFEST_FILES.contains(&n))
+}
+
+fn walk(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if path.is_dir() {
+            if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str()) {
+                walk(&path, out);
+            }
+        } else if is_manifest(&path) {
+            out.push(path);
+        }
+    }
+}
+
+/// Registry packages a manifest depends on directly; local path / git / URL
+/// dependencies are skipped.
+pub fn read_dependencies(path: &Path, dev: bool) -> Result<Vec<WatchKind>, String> {
+    let raw = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let npm = |names: BTreeSet<String>| {
+        names
+            .into_iter()
+            .map(|package| WatchKind::NpmLatest { package })
+            .collect()
+    };
+    let crates = |names: BTreeSet<String>| {
+        names
+            .into_iter()
+            .map(|name| WatchKind::CratesIoLatest {
+                name,
+                include_prereleases: false,
+            })
+            .collect()
+    };
+    Ok(match name {
+        "package.json" => npm(package_json_deps(&raw, dev)?),
+        "package-lock.json" => npm(package_lock_deps(&raw, dev)?),
+        "Cargo.toml" => crates(cargo_toml_deps(&raw, dev)),
+        "Cargo.lock" => crates(cargo_lock_deps(&raw)),
+        "requirements.txt" => requirements_deps(&raw)
+            .into_iter()
+            .map(|project| WatchKind::PyPiLatest {
+                project,
+                include_prereleases: false,
+            })
+            .collect(),
+        _ => return Err(format!("not a supported manifest: {name}")),
+    })
+}
+
+fn npm_sections(dev: bool) -> Vec<&'static str> {
+    let mut sections = vec!["dependencies", "optionalDependencies"];
+    if dev {
+        sections.push("devDependencies");
+    }
+    sections
+}
+
+/// 不在 registry 上的依赖 (本地路径 / git / tarball)
+const NPM_LOCAL_SPECS: &[&str] = &[
+    "file:",
+    "link:",
+    "workspace:",
+    "portal:",
+    "git",
+    "github:",
+    "http:",
+    "https:",
+];
+
+/// `"name": "spec"` maps; `npm:real@^1` aliases resolve to the real package.
+fn npm_deps(obj: &serde_json::Value, dev: bool) -> BTreeSet<String> {
+    let mut out = BTreeSet::new();
+    for section in npm_sections(dev) {
+        let Some(deps) = obj.get(section).and_then(|d| d.as_object()) else {
+            continue;
+        };
+        for (name, spec) in deps {
+            let spec = spec.as_str().unwrap_or("");
+            if let Some(alias) = spec.strip_prefix("npm:") {
+                // "@scope/pkg@^1" -> "@scope/pkg"
+                let name = match alias.get(1..).and_then(|a| a.find('@')) {
+                    Some(i) => &alias[..i + 1],
+                    None => alias,
+                };
+                out.insert(name.to_string());
+                continue;
+            }
+            if !NPM_LOCAL_SPECS.iter().any(|p| spec.starts_with(p)) {
+                out.insert(name.clone());
+            }
+        }
+    }
+    out
+}
+
+fn package_json_deps(raw: &str, dev: bool) -> Result<BTreeSet<String>, String> {
+    let v: serde_json::Value = serde_json::from_str(raw).map_err(|e| e.to_string())?;
+    Ok(npm_deps(&v, dev))
+}
+
+/// lockfileVersion 2+: the root package entry (`packages[""]`) lists the direct
+/// dependencies; v1 lock files only have the flattened tree and are skipped.
+fn package_lock_deps(raw: &str, dev: bool) -> Result<BTreeSet<String>, String> {
+    let v: serde_json::Value = serde_json::from_str(raw).map_err(|e| e.to_string())?;
+    match v.get("packages").and_then(|p| p.get("")) {
+        Some(root) => Ok(npm_deps(root, dev)),
+        None => Err("package-lock.json v1 is not supported (no packages[\"\"])".into()),
+    }
+}
+
+/// 去掉行尾注释 (引号里的 # 保留)
+fn strip_toml_comment(line: &str) -> &str {
+    let mut quote: Option<char> = None;
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '#') => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+static TOML_PACKAGE_FIELD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?:^|[\s{,])package\s*=\s*["']([^"']+)["']"#).unwrap());
+static TOML_LOCAL_DEP: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|[\s{,])(path|git|workspace)\s*=").unwrap());
+
+/// Value of `package = "..."` (a renamed dependency) inside an inline table / table body.
+fn toml_package_field(text: &str) -> Option<String> {
+    TOML_PACKAGE_FIELD.captures(text).map(|c| c[1].to_string())
+}
+
+fn is_local_toml_dep(text: &str) -> bool {
+    TOML_LOCAL_DEP.is_match(text)
+}
+
+/// Cargo.toml is read line by line: `[dependencies]`-like tables (also
+/// `[workspace.dependencies]`, `[target.'cfg(..)'.dependencies]`) and
+/// `[dependencies.name]` tables; `package = "..."` renames are followed.
+fn cargo_toml_deps(raw: &str, dev: bool) -> BTreeSet<String> {
+    let wanted = |table: &str| {
+        let last = table.rsplit('.').next().unwrap_or(table);
+        match last {
+            "dependencies" => true,
+            "dev-dependencies" | "build-dependencies" => dev,
+            _ => false,
+        }
+    };
+
+    let mut out = BTreeSet::new();
+    // 当前 [dependencies] 类 table, 或 [dependencies.name] 的 (name, 内容)
+    let mut in_deps = false;
+    let mut dep_table: Option<(String, String)> = None;
+    let flush = |table: &mut Option<(String, String)>, out: &mut BTreeSet<String>| {
+        if let Some((name, body)) = table.take()
+            && !is_local_toml_dep(&body)
+        {
+            out.insert(toml_package_field(&body).unwrap_or(name));
+        }
+    };
+
+    for line in raw.lines() {
+        let line = strip_toml_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('[') {
+            flush(&mut dep_table, &mut out);
+            let table = line.trim_matches(|c| c == '[' || c == ']').trim();
+            in_deps = wanted(table);
+            if !in_deps
+                && let Some((parent, name)) = table.rsplit_once('.')
+                && wanted(parent)
+            {
+                dep_table = Some((name.trim_matches('"').to_string(), String::new()));
+            }
+            continue;
+        }
+        if let Some((_, body)) = dep_table.as_mut() {
+            body.push_str(line);
+            body.push('\n');
+            continue;
+        }
+        if !in_deps {
+            continue;
+        }
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        let name = name.trim().trim_matches('"');
+        let value = value.trim();
+        if value.starts_with('{') {
+            if !is_local_toml_dep(value) {
+                out.insert(toml_package_field(value).unwrap_or_else(|| name.into()));
+            }
+        } else if !name.is_empty() && !name.contains('.') {
+            out.insert(name.to_string());
+        }
+    }
+    flush(&mut dep_table, &mut out);
+    out
+}
+
+/// Cargo.lock: the direct dependencies of the workspace's own packages (the
+/// ones without `source`) that come from a registry.
+fn cargo_lock_deps(raw: &str) -> BTreeSet<String> {
+    #[derive(Default)]
+    struct Package {
+        name: String,
+        source: Option<String>,
+        dependencies: Vec<String>,
+    }
+
+    let mut packages: Vec<Package> = vec![];
+    let mut in_deps = false;
+    for line in raw.lines() {
+        let line = line.trim();
+        if line == "[[package]]" {
+            packages.push(Package::default());
+            in_deps = false;
+            continue;
+        }
+        let Some(pkg) = packages.last_mut() else {
+            continue;
+        };
+        if in_deps {
+            if line.starts_with(']') {
+                in_deps = false;
+            } else if let Some(dep) = line.trim_end_matches(',').strip_prefix('"') {
+                pkg.dependencies.push(dep.trim_end_matches('"').to_string());
+            }
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim().trim_matches('"').to_string();
+            match key.trim() {
+                "name" => pkg.name = value,
+                "source" => pkg.source = Some(value),
+                // 一行写完的 dependencies = ["a", "b"] 也接受
+                "dependencies" if value.starts_with('[') => {
+                    in_deps = !value.ends_with(']');
+                    pkg.dependencies.extend(
+                        value
+                            .trim_matches(|c| c == '[' || c == ']')
+                            .split(',')
+                            .map(|d| d.trim().trim_matches('"').to_string())
+                            .filter(|d| !d.is_empty()),
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let registry: BTreeSet<&str> = packages
+        .iter()
+        .filter(|p| {
+            p.source
+                .as_deref()
+                .is_some_and(|s| s.starts_with("registry+") || s.starts_with("sparse+"))
+        })
+        .map(|p| p.name.as_str())
+        .collect();
+    packages
+        .iter()
+        .filter(|p| p.source.is_none())
+        .flat_map(|p| &p.dependencies)
+        // "name" 或 "name 1.2.3" 或 "name 1.2.3 (registry+...)"
+        .filter_map(|d| d.split_whitespace().next())
+        .filter(|d| registry.contains(d))
+        .map(String::from)
+        .collect()
+}
+
+/// requirements.txt: one requirement per line; options (`-r`, `-e`, `--index-url`),
+/// paths, URLs and `name @ url` direct references are skipped. Names are
+/// normalized as PyPI does (PEP 503).
+fn requirements_deps(raw: &str) -> BTreeSet<String> {
+    static NAME: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^([A-Za-z0-9][A-Za-z0-9._-]*)").unwrap());
+    let mut out = BTreeSet::new();
+    for line in raw.lines() {
+        let line = match line.find(" #") {
+            Some(i) => &line[..i],
+            None => line,
+        }
+        .trim();
+        if line.is_empty() || line.starts_with(['#', '-', '.', '/']) || line.contains("://") {
+            continue;
+        }
+        let Some(name) = NAME.captures(line).map(|c| c[1].to_string()) else {
+            continue;
+        };
+        if line[name.len()..].trim_start().starts_with('@') {
+            continue;
+        }
+        out.insert(normalize_pypi_name(&name));
+    }
+    out
+}
+
+/// (mtime, size) of every manifest file; a change triggers a rescan.
+type Stamps = Vec<(PathBuf, Option<SystemTime>, u64)>;
+
+#[derive(Default)]
+struct Discovered {
+    stamps: Option<Stamps>,
+    targets: Vec<WatchTarget>,
+}
+
+/// Configured targets plus the ones discovered from dependency manifests.
+/// Manifests are rescanned whenever one of them is added, removed or modified;
+/// a configured target for the same package (compared as the registry does,
+/// see `package_key`) wins over a discovered one.
+pub struct ManifestTargetRepository {
+    targets: Vec<WatchTarget>,
+    // 扫描是阻塞的文件系统操作, 放到 spawn_blocking 里跑
+    scanner: Arc<Scanner>,
+}
+
+struct Scanner {
+    sources: Vec<ManifestSource>,
+    /// `package_key` of every configured target (disabled ones too)
+    configured: BTreeSet<(&'static str, String)>,
+    discovered: Mutex<Discovered>,
+}
+
+impl ManifestTargetRepository {
+    /// Kinds discovered targets can have (their providers must be registered
+    /// even when no manifest mentions them yet).
+    pub const KINDS: &'static [&'static str] = &[
+        WatchKind::NPM_LATEST,
+        WatchKind::CRATES_IO_LATEST,
+        WatchKind::PYPI_LATEST,
+    ];
+
+    pub fn new(targets: Vec<WatchTarget>, sources: Vec<ManifestSource>) -> Self {
+        let configured = targets
+            .iter()
+            .filter_map(|t| package_key(&t.kind))
+            .collect();
+        Self {
+            targets,
+            scanner: Arc::new(Scanner {
+                sources,
+                configured,
+                discovered: Mutex::new(Discovered::default()),
+            }),
+        }
+    }
+
+    /// Discovered targets, rescanning the manifests if any of them changed.
+    /// Blocks on the file system; async callers go through `list_enabled_targets`.
+    pub fn discovered_targets(&self) -> AppResult<Vec<WatchTarget>> {
+        self.scanner.discovered_targets()
+    }
+}
+
+impl Scanner {
+    fn stamps(&self) -> Stamps {
+        self.sources
+            .iter()
+            .flat_map(|s| s.manifest_files())
+            .map(|path| {
+                let meta = std::fs::metadata(&path).ok();
+                let modified = meta.as_ref().and_then(|m| m.modified().ok());
+                let len = meta.map(|m| m.len()).unwrap_or(0);
+                (path, modified, len)
+            })
+            .collect()
+    }
+
+    fn discovered_targets(&self) -> AppResult<Vec<WatchTarget>> {
+        let stamps = self.stamps();
+        let mut state = self
+            .discovered
+            .lock()
+            .map_err(|_| AppError::Storage("lock poisoned".into()))?;
+        if state.stamps.as_ref() != Some(&stamps) {
+            let mut found: BTreeMap<(&'static str, String), WatchTarget> = BTreeMap::new();
+            for source in &self.sources {
+                for t in source.discover() {
+                    let key = package_key(&t.kind);
+                    if key.as_ref().is_some_and(|k| self.configured.contains(k)) {
+                        continue;
+                    }
+                    // 按 registry 规则合并: foo_bar 和 foo-bar 是同一个 crate
+                    let key = key.unwrap_or_else(|| ("", t.id.clone()));
+                    match found.get_mut(&key) {
+                        Some(existing) => {
+                            for label in t.labels {
+                                if !existing.labels.contains(&label) {
+                                    existing.labels.push(label);
+                                }
+                            }
+                        }
+                        None => {
+                            found.insert(key, t);
+                        }
+                    }
+                }
+            }
+            tracing::info!(
+                manifests = stamps.len(),
+                targets = found.len(),
+                "manifest targets refreshed"
+            );
+            state.targets = found.into_values().collect();
+            state.stamps = Some(stamps);
+        }
+        Ok(state.targets.clone())
+    }
+}
+
+#[async_trait]
+impl TargetRepository for ManifestTargetRepository {
+    async fn list_enabled_targets(&self) -> AppResult<Vec<WatchTarget>> {
+        let mut out: Vec<WatchTarget> =
+            self.targets.iter().filter(|t| t.enabled).cloned().collect();
+        let scanner = self.scanner.clone();
+        let discovered = tokio::task::spawn_blocking(move || scanner.discovered_targets())
+            .await
+            .map_err(|e| AppError::Storage(format!("manifest scan failed: {e}")))??;
+        out.extend(discovered);
+        Ok(out)
+    }
+}
@@ -0,0 +1,205 @@
+use async_trait::async_trait;
+
+use crate::application::{AppError, AppResult, WatchProvider};
+use crate::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use crate::infrastructure::api_endpoint::ApiEndpoint;
+use crate::infrastructure::provider_registry::KindProvider;
+
+pub const DEFAULT_MAVEN_REPOSITORY_URL: &str = "https://repo1.maven.org/maven2";
+
+/// Newest version of an artifact from `{repo}/{group/path}/{artifact}/maven-metadata.xml`.
+///
+/// Uses `<release>` (or `<latest>` when snapshots are included) and falls back
+/// to the last entry of `<versions>`, which repositories keep in deploy order.
+pub struct MavenLatestProvider {
+    client: reqwest::Client,
+    repository: ApiEndpoint,
+}
+
+impl MavenLatestProvider {
+    pub fn new() -> Self {
+        Self::with_repository(ApiEndpoint::new(DEFAULT_MAVEN_REPOSITORY_URL))
+    }
+
+    /// Use another Maven repository (Nexus, Artifactory, local stand-in).
+    pub fn with_repository(repository: ApiEndpoint) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            repository,
+        }
+    }
+}
+
+impl Default for MavenLatestProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The parts of maven-metadata.xml we use.
+#[derive(Debug, Default)]
+struct Metadata {
+    latest: Option<String>,
+    release: Option<String>,
+    versions: Vec<String>,
+    /// yyyyMMddHHmmss (UTC)
+    last_updated: Option<String>,
+}
+
+fn parse_metadata(xml: &str) -> AppResult<Metadata> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| AppError::Provider(e.to_string()))?;
+    let Some(versioning) = doc.descendants().find(|n| n.has_tag_name("versioning")) else {
+        return Ok(Metadata::default());
+    };
+
+    let text = |name: &str| {
+        versioning
+            .children()
+            .find(|n| n.has_tag_name(name))
+            .and_then(|n| n.text())
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+    };
+    let versions = versioning
+        .children()
+        .find(|n| n.has_tag_name("versions"))
+        .map(|vs| {
+            vs.children()
+                .filter(|n| n.has_tag_name("version"))
+                .filter_map(|n| n.text())
+                .map(|t| t.trim().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(Metadata {
+        latest: text("latest"),
+        release: text("release"),
+        versions,
+        last_updated: text("lastUpdated"),
+    })
+}
+
+fn is_snapshot(version: &str) -> bool {
+    version.ends_with("-SNAPSHOT")
+}
+
+/// 20240102030405 -> 2024-01-02T03:04:05Z
+fn last_updated_rfc3339(s: &str) -> Option<String> {
+    if s.len() != 14 || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(format!(
+        "{}-{}-{}T{}:{}:{}Z",
+        &s[0..4],
+        &s[4..6],
+        &s[6..8],
+        &s[8..10],
+        &s[10..12],
+        &s[12..14]
+    ))
+}
+
+impl KindProvider for MavenLatestProvider {
+    const KIND: &'static str = WatchKind::MAVEN_LATEST;
+}
+
+#[async_trait]
+impl WatchProvider for MavenLatestProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let (group_id, artifact_id, include_snapshots) = match &target.kind {
+            WatchKind::MavenLatest {
+                group_id,
+                artifact_id,
+                include_snapshots,
+            } => (group_id, artifact_id, *include_snapshots),
+            _ => return Ok(None),
+        };
+
+        let repository = self.repository.resolve(target.endpoint.as_ref());
+        let artifact_path = format!("/{}/{}", group_id.replace('.', "/"), artifact_id);
+        let url = repository.url(&format!("{}/maven-metadata.xml", artifact_path));
+
+        let resp = repository
+            .authorize(self.client.get(url))
+            .send()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+        let body = resp
+            .text()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+        let metadata = parse_metadata(&body)?;
+
+        let preferred = if include_snapshots {
+            metadata.latest.clone().or(metadata.release.clone())
+        } else {
+            metadata.release.clone().filter(|v| !is_snapshot(v))
+        };
+        let version = preferred.or_else(|| {
+            metadata
+                .versions
+                .iter()
+                .rev()
+                .find(|v| include_snapshots || !is_snapshot(v))
+                .cloned()
+        });
+        let Some(version) = version else {
+            return Ok(None);
+        };
+
+        let subject = format!("{}:{}", group_id, artifact_id);
+        let event_id = Event::make_event_id(&EventType::MavenLatest, &subject, &version);
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::MavenLatest,
+            source: Source::Maven,
+            subject,
+            old_value: None,
+            url: Some(artifact_page_url(
+                &repository,
+                group_id,
+                artifact_id,
+                &artifact_path,
+                &version,
+            )),
+            new_value: version,
+            occurred_at: metadata
+                .last_updated
+                .as_deref()
+                .and_then(last_updated_rfc3339),
+            detected_at: now_string(),
+            meta: Default::default(),
+        }))
+    }
+}
+
+/// central.sonatype.com for Maven Central, the version directory otherwise
+fn artifact_page_url(
+    repository: &ApiEndpoint,
+    group_id: &str,
+    artifact_id: &str,
+    artifact_path: &str,
+    version: &str,
+) -> String {
+    if repository.base_url == DEFAULT_MAVEN_REPOSITORY_URL {
+        format!(
+            "https://central.sonatype.com/artifact/{}/{}/{}",
+            group_id, artifact_id, version
+        )
+    } else {
+        repository.url(&format!("{}/{}/", artifact_path, version))
+    }
+}
+
+fn now_string() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    format!("{}s_since_epoch", secs)
+}
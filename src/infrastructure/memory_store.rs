@@ -3,7 +3,10 @@ use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
 
-use crate::application::{AppError, AppResult, EventStore, TargetRepository};
+use crate::application::{
+    AppError, AppResult, EventStore, HttpCacheEntry, HttpCacheStore, ProviderStateStore,
+    TargetRepository,
+};
 use crate::domain::{Event, WatchTarget};
 
 #[derive(Clone, Default)]
@@ -17,6 +20,10 @@ struct Inner {
     events: Vec<Event>,
     // 预留：cooldown、notify_log 等
     meta: HashMap<String, String>,
+    // target_id -> (last observed value, revision)
+    last_values: HashMap<String, (String, u64)>,
+    http_cache: HashMap<String, HttpCacheEntry>,
+    provider_state: HashMap<String, String>,
 }
 
 impl InMemoryEventStore {
@@ -103,6 +110,34 @@ impl EventStore for InMemoryEventStore {
         Ok(())
     }
 
+    async fn get_last_value(&self, target_id: &str) -> AppResult<Option<String>> {
+        let inner = self
+            .inner
+            .lock()
+            .map_err(|_| AppError::Storage("lock poisoned".into()))?;
+        Ok(inner.last_values.get(target_id).map(|(v, _)| v.clone()))
+    }
+
+    async fn set_last_value(&self, target_id: &str, value: &str) -> AppResult<()> {
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|_| AppError::Storage("lock poisoned".into()))?;
+        let revision = inner.last_values.get(target_id).map_or(0, |(_, r)| r + 1);
+        inner
+            .last_values
+            .insert(target_id.to_string(), (value.to_string(), revision));
+        Ok(())
+    }
+
+    async fn get_last_value_revision(&self, target_id: &str) -> AppResult<Option<(String, u64)>> {
+        let inner = self
+            .inner
+            .lock()
+            .map_err(|_| AppError::Storage("lock poisoned".into()))?;
+        Ok(inner.last_values.get(target_id).cloned())
+    }
+
     async fn list_events(&self, limit: u32) -> AppResult<Vec<Event>> {
         let inner = self
             .inner
@@ -148,6 +183,48 @@ impl EventStore for InMemoryEventStore {
     }
 }
 
+#[async_trait]
+impl HttpCacheStore for InMemoryEventStore {
+    async fn get_http_cache(&self, key: &str) -> AppResult<Option<HttpCacheEntry>> {
+        let inner = self
+            .inner
+            .lock()
+            .map_err(|_| AppError::Storage("lock poisoned".into()))?;
+        Ok(inner.http_cache.get(key).cloned())
+    }
+
+    async fn set_http_cache(&self, key: &str, entry: &HttpCacheEntry) -> AppResult<()> {
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|_| AppError::Storage("lock poisoned".into()))?;
+        inner.http_cache.insert(key.to_string(), entry.clone());
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ProviderStateStore for InMemoryEventStore {
+    async fn get_provider_state(&self, target_id: &str) -> AppResult<Option<String>> {
+        let inner = self
+            .inner
+            .lock()
+            .map_err(|_| AppError::Storage("lock poisoned".into()))?;
+        Ok(inner.provider_state.get(target_id).cloned())
+    }
+
+    async fn set_provider_state(&self, target_id: &str, state: &str) -> AppResult<()> {
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|_| AppError::Storage("lock poisoned".into()))?;
+        inner
+            .provider_state
+            .insert(target_id.to_string(), state.to_string());
+        Ok(())
+    }
+}
+
 #[derive(Clone)]
 pub struct InMemoryTargetRepository {
     targets: Arc<Vec<WatchTarget>>,
@@ -164,6 +241,6 @@ impl InMemoryTargetRepository {
 #[async_trait]
 impl TargetRepository for InMemoryTargetRepository {
     async fn list_enabled_targets(&self) -> AppResult<Vec<WatchTarget>> {
-        Ok(self.targets.iter().cloned().filter(|t| t.enabled).collect())
+        Ok(self.targets.iter().filter(|t| t.enabled).cloned().collect())
     }
 }
@@ -1,12 +1,31 @@
+pub mod api_endpoint;
 pub mod broadcast_publisher;
-pub mod composite_provider;
+pub mod conditional_get;
 pub mod console_notifier;
+pub mod crates_io_provider;
 pub mod event_bus;
 pub mod fake_provider;
+pub mod feed_provider;
 pub mod feishu_notifier;
+pub mod gitea_provider;
 pub mod github_branch_provider;
+pub mod github_client;
 pub mod github_release_provider;
+pub mod github_search_provider;
+pub mod github_tag_provider;
+pub mod github_workflow_provider;
+pub mod gitlab_provider;
+pub mod go_module_provider;
+pub mod http_json_provider;
+pub mod http_page_provider;
+pub mod manifest_discovery;
+pub mod maven_latest_provider;
 pub mod memory_store;
 pub mod multi_notifier;
 pub mod npm_latest_provider;
+pub mod oci_registry_provider;
+pub mod osv_provider;
+pub mod provider_registry;
+pub mod pypi_provider;
 pub mod sqlite_store;
+pub mod whatsapp_web_version_provider;
@@ -1,30 +1,199 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 use async_trait::async_trait;
 use serde::Deserialize;
 
-use crate::application::{AppError, AppResult, WatchProvider};
-use crate::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use crate::application::{AppError, AppResult, HttpCacheStore, WatchProvider};
+use crate::domain::{
+    Event, EventType, Source, WatchKind, WatchTarget, compare_versions, version_list_diff,
+};
+use crate::infrastructure::api_endpoint::ApiEndpoint;
+use crate::infrastructure::conditional_get::send_conditional;
+use crate::infrastructure::provider_registry::KindProvider;
 
-pub struct NpmLatestProvider {
+pub const DEFAULT_NPM_REGISTRY_URL: &str = "https://registry.npmjs.org";
+
+/// `Event::meta` keys set on deprecation / unpublish events
+pub const META_DEPRECATED: &str = "deprecated";
+pub const META_UNDEPRECATED: &str = "undeprecated";
+pub const META_UNPUBLISHED: &str = "unpublished";
+pub const META_REPUBLISHED: &str = "republished";
+
+/// 一轮检查里同一个包的几个 target 共用一次拉取
+pub const DEFAULT_PACKUMENT_TTL: Duration = Duration::from_secs(30);
+
+/// Shared registry access for the npm providers: all of them read the packument.
+///
+/// The derived targets of one package (latest, dist-tags, deprecated, ...) are
+/// checked in the same run, so a fetched packument is reused for
+/// `DEFAULT_PACKUMENT_TTL`; concurrent checks of one package wait for a single request.
+/// Build one client and hand clones to every npm provider.
+#[derive(Clone)]
+pub struct NpmRegistryClient {
     client: reqwest::Client,
+    registry: ApiEndpoint,
+    cache: Option<Arc<dyn HttpCacheStore>>,
+    ttl: Duration,
+    packuments: Arc<Mutex<HashMap<String, PackumentSlot>>>,
 }
 
-impl NpmLatestProvider {
-    pub fn new() -> Self {
+/// packument URL -> (fetched at, body)
+type PackumentSlot = Arc<tokio::sync::Mutex<Option<(Instant, Arc<Packument>)>>>;
+
+/// The parts of a packument (GET /{package}) the providers use.
+#[derive(Debug, Deserialize)]
+struct Packument {
+    #[serde(rename = "dist-tags", default)]
+    dist_tags: BTreeMap<String, String>,
+    #[serde(default)]
+    versions: BTreeMap<String, VersionDoc>,
+    /// version -> publish time, plus "created" / "modified" (and "unpublished"
+    /// once the whole package is gone)
+    #[serde(default)]
+    time: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionDoc {
+    /// deprecation message; npm clears it with ""
+    deprecated: Option<serde_json::Value>,
+}
+
+impl Packument {
+    fn time(&self, key: &str) -> Option<String> {
+        self.time.get(key)?.as_str().map(|s| s.to_string())
+    }
+
+    fn deprecated_versions(&self) -> Vec<String> {
+        let mut out: Vec<String> = self
+            .versions
+            .iter()
+            .filter(|(_, v)| match &v.deprecated {
+                Some(serde_json::Value::String(msg)) => !msg.is_empty(),
+                Some(serde_json::Value::Bool(b)) => *b,
+                _ => false,
+            })
+            .map(|(num, _)| num.clone())
+            .collect();
+        out.sort_by(|a, b| compare_versions(a, b));
+        out
+    }
+
+    /// Versions that have a publish time but no manifest any more, plus the
+    /// ones listed in `time.unpublished` when the whole package was removed.
+    fn unpublished_versions(&self) -> Vec<String> {
+        let mut out: BTreeSet<String> = self
+            .time
+            .keys()
+            .filter(|k| !matches!(k.as_str(), "created" | "modified" | "unpublished"))
+            .filter(|k| !self.versions.contains_key(*k))
+            .cloned()
+            .collect();
+        if let Some(versions) = self
+            .time
+            .get("unpublished")
+            .and_then(|u| u.get("versions"))
+            .and_then(|v| v.as_array())
+        {
+            out.extend(versions.iter().filter_map(|v| v.as_str().map(String::from)));
+        }
+        let mut out: Vec<String> = out.into_iter().collect();
+        out.sort_by(|a, b| compare_versions(a, b));
+        out
+    }
+}
+
+impl NpmRegistryClient {
+    pub fn new(registry: ApiEndpoint) -> Self {
         Self {
             client: reqwest::Client::new(),
+            registry,
+            cache: None,
+            ttl: DEFAULT_PACKUMENT_TTL,
+            packuments: Default::default(),
+        }
+    }
+
+    /// Revalidate packuments with If-None-Match once the shared copy is stale.
+    pub fn with_cache(mut self, cache: Arc<dyn HttpCacheStore>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// How long a fetched packument is reused without asking the registry.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    async fn packument(
+        &self,
+        target: &WatchTarget,
+        pkg: &str,
+    ) -> AppResult<(ApiEndpoint, Arc<Packument>)> {
+        let registry = self.registry.resolve(target.endpoint.as_ref());
+        // scoped 包: @scope/name -> @scope%2Fname
+        let url = registry.url(&format!("/{}", pkg.replace('/', "%2F")));
+
+        let slot = self
+            .packuments
+            .lock()
+            .unwrap()
+            .entry(url.clone())
+            .or_default()
+            .clone();
+        // 并发 check 同一个包时后来的等第一个拉完
+        let mut slot = slot.lock().await;
+        if let Some((fetched_at, body)) = slot.as_ref()
+            && fetched_at.elapsed() < self.ttl
+        {
+            return Ok((registry, body.clone()));
+        }
+
+        let req = registry.authorize(self.client.get(&url));
+        let resp = send_conditional(self.cache.as_deref(), &format!("npm:{url}"), req).await?;
+        if !resp.status.is_success() && !resp.not_modified {
+            return Err(AppError::Provider(format!("HTTP status {}", resp.status)));
         }
+
+        let body: Arc<Packument> = Arc::new(
+            serde_json::from_str(&resp.body).map_err(|e| AppError::Provider(e.to_string()))?,
+        );
+        *slot = Some((Instant::now(), body.clone()));
+        Ok((registry, body))
     }
 }
 
-#[derive(Debug, Deserialize)]
-struct NpmResp {
-    #[serde(rename = "dist-tags")]
-    dist_tags: DistTags,
+pub struct NpmLatestProvider {
+    api: NpmRegistryClient,
 }
 
-#[derive(Debug, Deserialize)]
-struct DistTags {
-    latest: String,
+impl NpmLatestProvider {
+    pub fn new() -> Self {
+        Self::with_registry(ApiEndpoint::new(DEFAULT_NPM_REGISTRY_URL))
+    }
+
+    /// Use a private registry (Verdaccio, Artifactory, ...) by default.
+    pub fn with_registry(registry: ApiEndpoint) -> Self {
+        Self::with_client(NpmRegistryClient::new(registry))
+    }
+
+    /// Share the packument fetches with the other npm providers.
+    pub fn with_client(api: NpmRegistryClient) -> Self {
+        Self { api }
+    }
+}
+
+impl Default for NpmLatestProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KindProvider for NpmLatestProvider {
+    const KIND: &'static str = WatchKind::NPM_LATEST;
 }
 
 #[async_trait]
@@ -35,23 +204,13 @@ impl WatchProvider for NpmLatestProvider {
             _ => return Ok(None),
         };
 
-        let url = format!("https://registry.npmjs.org/{}", pkg);
-
-        let resp = self
-            .client
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| AppError::Provider(e.to_string()))?
-            .error_for_status()
-            .map_err(|e| AppError::Provider(e.to_string()))?;
-
-        let body: NpmResp = resp
-            .json()
-            .await
-            .map_err(|e| AppError::Provider(e.to_string()))?;
+        let (registry, body) = self.api.packument(target, pkg).await?;
+        let latest = body
+            .dist_tags
+            .get("latest")
+            .cloned()
+            .ok_or_else(|| AppError::Provider(format!("{pkg}: no latest dist-tag")))?;
 
-        let latest = body.dist_tags.latest;
         let subject = pkg.to_string();
         let event_id = Event::make_event_id(&EventType::NpmLatest, &subject, &latest);
 
@@ -64,11 +223,289 @@ impl WatchProvider for NpmLatestProvider {
             new_value: latest,
             occurred_at: None,
             detected_at: now_string(),
-            url: Some(format!("https://www.npmjs.com/package/{}", pkg)),
+            url: Some(package_page_url(&registry, pkg)),
+            meta: Default::default(),
         }))
     }
 }
 
+/// Version a dist-tag points at; no value while the tag doesn't exist.
+pub struct NpmDistTagProvider {
+    api: NpmRegistryClient,
+}
+
+impl NpmDistTagProvider {
+    pub fn new() -> Self {
+        Self::with_registry(ApiEndpoint::new(DEFAULT_NPM_REGISTRY_URL))
+    }
+
+    pub fn with_registry(registry: ApiEndpoint) -> Self {
+        Self::with_client(NpmRegistryClient::new(registry))
+    }
+
+    /// Share the packument fetches with the other npm providers.
+    pub fn with_client(api: NpmRegistryClient) -> Self {
+        Self { api }
+    }
+}
+
+impl Default for NpmDistTagProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KindProvider for NpmDistTagProvider {
+    const KIND: &'static str = WatchKind::NPM_DIST_TAG;
+}
+
+#[async_trait]
+impl WatchProvider for NpmDistTagProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let (pkg, tag) = match &target.kind {
+            WatchKind::NpmDistTag { package, tag } => (package, tag),
+            _ => return Ok(None),
+        };
+
+        let (registry, body) = self.api.packument(target, pkg).await?;
+        let Some(version) = body.dist_tags.get(tag).cloned() else {
+            return Ok(None);
+        };
+
+        let subject = target.kind.subject();
+        let event_id = Event::make_event_id(&EventType::NpmDistTag, &subject, &version);
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::NpmDistTag,
+            source: Source::Npm,
+            subject,
+            old_value: None,
+            occurred_at: body.time(&version),
+            new_value: version,
+            detected_at: now_string(),
+            url: Some(package_page_url(&registry, pkg)),
+            meta: Default::default(),
+        }))
+    }
+}
+
+/// Reports changes to the set of deprecated versions; the value is the
+/// deprecated versions in semver order, and the event meta says which changed.
+pub struct NpmDeprecatedProvider {
+    api: NpmRegistryClient,
+}
+
+impl NpmDeprecatedProvider {
+    pub fn new() -> Self {
+        Self::with_registry(ApiEndpoint::new(DEFAULT_NPM_REGISTRY_URL))
+    }
+
+    pub fn with_registry(registry: ApiEndpoint) -> Self {
+        Self::with_client(NpmRegistryClient::new(registry))
+    }
+
+    /// Share the packument fetches with the other npm providers.
+    pub fn with_client(api: NpmRegistryClient) -> Self {
+        Self { api }
+    }
+}
+
+impl Default for NpmDeprecatedProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KindProvider for NpmDeprecatedProvider {
+    const KIND: &'static str = WatchKind::NPM_DEPRECATED;
+}
+
+#[async_trait]
+impl WatchProvider for NpmDeprecatedProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let pkg = match &target.kind {
+            WatchKind::NpmDeprecated { package } => package,
+            _ => return Ok(None),
+        };
+
+        let (registry, body) = self.api.packument(target, pkg).await?;
+        let value = body.deprecated_versions().join(", ");
+
+        let subject = pkg.to_string();
+        let event_id = Event::make_event_id(&EventType::NpmDeprecated, &subject, &value);
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::NpmDeprecated,
+            source: Source::Npm,
+            subject,
+            old_value: None,
+            new_value: value,
+            occurred_at: body.time("modified"),
+            detected_at: now_string(),
+            url: Some(package_page_url(&registry, pkg)),
+            meta: Default::default(),
+        }))
+    }
+
+    async fn enrich(&self, _target: &WatchTarget, event: &mut Event) -> AppResult<()> {
+        set_list_diff_meta(event, META_DEPRECATED, META_UNDEPRECATED);
+        Ok(())
+    }
+}
+
+/// Reports changes to the set of unpublished versions (same shape as
+/// `NpmDeprecatedProvider`).
+pub struct NpmUnpublishedProvider {
+    api: NpmRegistryClient,
+}
+
+impl NpmUnpublishedProvider {
+    pub fn new() -> Self {
+        Self::with_registry(ApiEndpoint::new(DEFAULT_NPM_REGISTRY_URL))
+    }
+
+    pub fn with_registry(registry: ApiEndpoint) -> Self {
+        Self::with_client(NpmRegistryClient::new(registry))
+    }
+
+    /// Share the packument fetches with the other npm providers.
+    pub fn with_client(api: NpmRegistryClient) -> Self {
+        Self { api }
+    }
+}
+
+impl Default for NpmUnpublishedProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KindProvider for NpmUnpublishedProvider {
+    const KIND: &'static str = WatchKind::NPM_UNPUBLISHED;
+}
+
+#[async_trait]
+impl WatchProvider for NpmUnpublishedProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let pkg = match &target.kind {
+            WatchKind::NpmUnpublished { package } => package,
+            _ => return Ok(None),
+        };
+
+        let (registry, body) = self.api.packument(target, pkg).await?;
+        let value = body.unpublished_versions().join(", ");
+
+        let subject = pkg.to_string();
+        let event_id = Event::make_event_id(&EventType::NpmUnpublished, &subject, &value);
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::NpmUnpublished,
+            source: Source::Npm,
+            subject,
+            old_value: None,
+            new_value: value,
+            occurred_at: body.time("modified"),
+            detected_at: now_string(),
+            url: Some(package_page_url(&registry, pkg)),
+            meta: Default::default(),
+        }))
+    }
+
+    async fn enrich(&self, _target: &WatchTarget, event: &mut Event) -> AppResult<()> {
+        set_list_diff_meta(event, META_UNPUBLISHED, META_REPUBLISHED);
+        Ok(())
+    }
+}
+
+/// The packument's `time.modified`: changes on any publish, deprecation,
+/// dist-tag move or unpublish.
+pub struct NpmModifiedProvider {
+    api: NpmRegistryClient,
+}
+
+impl NpmModifiedProvider {
+    pub fn new() -> Self {
+        Self::with_registry(ApiEndpoint::new(DEFAULT_NPM_REGISTRY_URL))
+    }
+
+    pub fn with_registry(registry: ApiEndpoint) -> Self {
+        Self::with_client(NpmRegistryClient::new(registry))
+    }
+
+    /// Share the packument fetches with the other npm providers.
+    pub fn with_client(api: NpmRegistryClient) -> Self {
+        Self { api }
+    }
+}
+
+impl Default for NpmModifiedProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KindProvider for NpmModifiedProvider {
+    const KIND: &'static str = WatchKind::NPM_MODIFIED;
+}
+
+#[async_trait]
+impl WatchProvider for NpmModifiedProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let pkg = match &target.kind {
+            WatchKind::NpmModified { package } => package,
+            _ => return Ok(None),
+        };
+
+        let (registry, body) = self.api.packument(target, pkg).await?;
+        let Some(modified) = body.time("modified") else {
+            return Ok(None);
+        };
+
+        let subject = pkg.to_string();
+        let event_id = Event::make_event_id(&EventType::NpmModified, &subject, &modified);
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::NpmModified,
+            source: Source::Npm,
+            subject,
+            old_value: None,
+            occurred_at: Some(modified.clone()),
+            new_value: modified,
+            detected_at: now_string(),
+            url: Some(package_page_url(&registry, pkg)),
+            meta: Default::default(),
+        }))
+    }
+}
+
+/// old/new are ", "-joined version lists: record which were added / removed.
+fn set_list_diff_meta(event: &mut Event, added_key: &str, removed_key: &str) {
+    let (added, removed) = version_list_diff(
+        event.old_value.as_deref().unwrap_or(""),
+        &event.new_value,
+        compare_versions,
+    );
+    if !added.is_empty() {
+        event.meta.insert(added_key.into(), added.join(", "));
+    }
+    if !removed.is_empty() {
+        event.meta.insert(removed_key.into(), removed.join(", "));
+    }
+}
+
+/// npmjs.com package page for the public registry, the registry document otherwise
+fn package_page_url(registry: &ApiEndpoint, pkg: &str) -> String {
+    if registry.base_url == DEFAULT_NPM_REGISTRY_URL {
+        format!("https://www.npmjs.com/package/{}", pkg)
+    } else {
+        format!("{}/{}", registry.base_url, pkg)
+    }
+}
+
 fn now_string() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
     let secs = SystemTime::now()
@@ -0,0 +1,479 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use reqwest::header::{ACCEPT, LINK, WWW_AUTHENTICATE};
+use reqwest::{Method, StatusCode};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::application::{AppError, AppResult, ProviderStateStore, WatchProvider};
+use crate::domain::{
+    Event, EventType, ImageRef, NameFilter, Source, WatchKind, WatchTarget, compare_versions,
+    version_list_diff,
+};
+use crate::infrastructure::api_endpoint::ApiEndpoint;
+use crate::infrastructure::provider_registry::KindProvider;
+
+/// `Event::meta` keys set on tag-list events
+pub const META_ADDED: &str = "added";
+pub const META_REMOVED: &str = "removed";
+
+/// Docker Hub 的 registry API 不在 docker.io 上
+const DOCKER_HUB_REGISTRY_URL: &str = "https://registry-1.docker.io";
+
+/// manifest list / index first, so a multi-arch tag reports the digest people pull by
+const MANIFEST_ACCEPT: &str = "application/vnd.oci.image.index.v1+json, \
+application/vnd.docker.distribution.manifest.list.v2+json, \
+application/vnd.oci.image.manifest.v1+json, \
+application/vnd.docker.distribution.manifest.v2+json";
+
+/// 标签列表最多翻多少页
+const MAX_TAG_PAGES: usize = 20;
+
+/// OCI Distribution API client shared by the digest / tags providers.
+///
+/// Registries answer anonymous requests with `401` + `WWW-Authenticate`; a Bearer
+/// challenge is answered by fetching a token from the realm (with the target's
+/// username/token as Basic credentials, if any) and cached until it expires.
+/// A target token without username is sent as a Bearer token directly.
+#[derive(Clone, Default)]
+pub struct OciRegistryClient {
+    client: reqwest::Client,
+    tokens: Arc<Mutex<HashMap<TokenKey, (String, i64)>>>,
+}
+
+/// (base_url, scope, credentials); value is (token, expires_at_epoch)
+type TokenKey = (String, String, String);
+
+#[derive(Debug, Deserialize)]
+struct TokenResp {
+    token: Option<String>,
+    access_token: Option<String>,
+    expires_in: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagListResp {
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+}
+
+impl OciRegistryClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry API root for an image; a target `base_url` overrides it.
+    pub fn endpoint_for(&self, image: &ImageRef, target: &WatchTarget) -> ApiEndpoint {
+        let default = if image.registry == ImageRef::DOCKER_HUB {
+            DOCKER_HUB_REGISTRY_URL.to_string()
+        } else {
+            format!("https://{}", image.registry)
+        };
+        ApiEndpoint::new(default).resolve(target.endpoint.as_ref())
+    }
+
+    /// Send `method url`, answering an auth challenge once.
+    async fn send(
+        &self,
+        api: &ApiEndpoint,
+        repository: &str,
+        method: Method,
+        url: &str,
+        accept: Option<&str>,
+    ) -> AppResult<reqwest::Response> {
+        let scope = format!("repository:{}:pull", repository);
+        // 同一个仓库, 不同凭据换到的 token 权限不同, 不能共用
+        let credentials = format!(
+            "{}:{}",
+            api.username.as_deref().unwrap_or(""),
+            api.token.as_deref().map(short_hash).unwrap_or_default()
+        );
+        let cache_key = (api.base_url.clone(), scope.clone(), credentials);
+
+        let build = |bearer: Option<&str>| {
+            let mut req = self.client.request(method.clone(), url);
+            if let Some(a) = accept {
+                req = req.header(ACCEPT, a);
+            }
+            match (bearer, &api.username, &api.token) {
+                (Some(t), _, _) => req.bearer_auth(t),
+                (None, None, Some(t)) => req.bearer_auth(t),
+                _ => req,
+            }
+        };
+
+        let cached = self.cached_token(&cache_key);
+        let resp = build(cached.as_deref())
+            .send()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+        if resp.status() != StatusCode::UNAUTHORIZED {
+            return Ok(resp);
+        }
+
+        let challenge = resp
+            .headers()
+            .get(WWW_AUTHENTICATE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+        let (scheme, params) = parse_challenge(&challenge);
+
+        let retry = if scheme.eq_ignore_ascii_case("bearer") {
+            let token = self.fetch_token(api, &params, &scope).await?;
+            // 缓存只是优化, 锁坏了就每次重新换 token
+            if let Ok(mut tokens) = self.tokens.lock() {
+                tokens.insert(cache_key, token.clone());
+            }
+            build(Some(&token.0))
+        } else if scheme.eq_ignore_ascii_case("basic") && api.username.is_some() {
+            api.authorize(build(None))
+        } else {
+            return Err(AppError::Provider(format!(
+                "registry requires authentication ({})",
+                if challenge.is_empty() {
+                    "no challenge"
+                } else {
+                    &challenge
+                }
+            )));
+        };
+
+        retry
+            .send()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))
+    }
+
+    fn cached_token(&self, key: &TokenKey) -> Option<String> {
+        let tokens = self.tokens.lock().ok()?;
+        let (token, expires_at) = tokens.get(key)?;
+        // 提前 10 秒过期
+        (*expires_at > now_epoch() + 10).then(|| token.clone())
+    }
+
+    async fn fetch_token(
+        &self,
+        api: &ApiEndpoint,
+        params: &HashMap<String, String>,
+        scope: &str,
+    ) -> AppResult<(String, i64)> {
+        let realm = params
+            .get("realm")
+            .ok_or_else(|| AppError::Provider("bearer challenge without realm".into()))?;
+        let mut query: Vec<(&str, &str)> = vec![("scope", scope)];
+        if let Some(service) = params.get("service") {
+            query.push(("service", service));
+        }
+
+        let mut req = self.client.get(realm).query(&query);
+        if let Some(user) = &api.username {
+            req = req.basic_auth(user, api.token.as_deref());
+        }
+        let body: TokenResp = req
+            .send()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| AppError::Provider(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+
+        let token = body
+            .token
+            .or(body.access_token)
+            .ok_or_else(|| AppError::Provider("token response without token".into()))?;
+        // 规范: 没给 expires_in 时按 60 秒算
+        let expires_at = now_epoch() + body.expires_in.unwrap_or(60);
+        Ok((token, expires_at))
+    }
+
+    /// Digest of the manifest `tag` points at.
+    pub async fn manifest_digest(
+        &self,
+        api: &ApiEndpoint,
+        image: &ImageRef,
+        tag: &str,
+    ) -> AppResult<String> {
+        let url = api.url(&format!("/v2/{}/manifests/{}", image.repository, tag));
+
+        // HEAD 不计入 Docker Hub 的拉取次数
+        let resp = self
+            .send(
+                api,
+                &image.repository,
+                Method::HEAD,
+                &url,
+                Some(MANIFEST_ACCEPT),
+            )
+            .await?;
+        if resp.status().is_success()
+            && let Some(d) = content_digest(&resp)
+        {
+            return Ok(d);
+        }
+
+        // 有的 registry HEAD 不带 Docker-Content-Digest: GET 一次, 必要时自己算
+        let resp = self
+            .send(
+                api,
+                &image.repository,
+                Method::GET,
+                &url,
+                Some(MANIFEST_ACCEPT),
+            )
+            .await?
+            .error_for_status()
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+        if let Some(d) = content_digest(&resp) {
+            return Ok(d);
+        }
+        let bytes = resp
+            .bytes()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+        Ok(format!("sha256:{:x}", Sha256::digest(&bytes)))
+    }
+
+    /// All tags of the repository, following `Link: <...>; rel="next"` pagination.
+    pub async fn tags(&self, api: &ApiEndpoint, image: &ImageRef) -> AppResult<Vec<String>> {
+        let mut out = Vec::new();
+        let mut next = Some(api.url(&format!("/v2/{}/tags/list?n=1000", image.repository)));
+
+        for _ in 0..MAX_TAG_PAGES {
+            let Some(url) = next.take() else { break };
+            let resp = self
+                .send(api, &image.repository, Method::GET, &url, None)
+                .await?
+                .error_for_status()
+                .map_err(|e| AppError::Provider(e.to_string()))?;
+
+            next = resp
+                .headers()
+                .get(LINK)
+                .and_then(|v| v.to_str().ok())
+                .and_then(next_link)
+                .map(|l| if l.starts_with('/') { api.url(&l) } else { l });
+
+            let page: TagListResp = resp
+                .json()
+                .await
+                .map_err(|e| AppError::Provider(e.to_string()))?;
+            out.extend(page.tags.unwrap_or_default());
+        }
+        Ok(out)
+    }
+}
+
+/// `Bearer realm="...",service="...",scope="..."` -> ("Bearer", {realm, service, scope})
+fn parse_challenge(header: &str) -> (String, HashMap<String, String>) {
+    let (scheme, rest) = header.trim().split_once(' ').unwrap_or((header.trim(), ""));
+    let mut params = HashMap::new();
+    let mut rest = rest.trim();
+    while let Some((key, after)) = rest.split_once('=') {
+        let key = key
+            .trim()
+            .trim_start_matches(',')
+            .trim()
+            .to_ascii_lowercase();
+        let (value, remaining) = match after.strip_prefix('"') {
+            Some(quoted) => match quoted.split_once('"') {
+                Some((v, r)) => (v, r),
+                None => (quoted, ""),
+            },
+            None => after.split_once(',').unwrap_or((after, "")),
+        };
+        params.insert(key, value.to_string());
+        rest = remaining.trim_start_matches(',').trim();
+    }
+    (scheme.to_string(), params)
+}
+
+/// `<url>; rel="next"` -> url
+fn next_link(header: &str) -> Option<String> {
+    header.split(',').find_map(|part| {
+        let (url, params) = part.split_once(';')?;
+        params.contains("rel=\"next\"").then(|| {
+            url.trim()
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string()
+        })
+    })
+}
+
+fn content_digest(resp: &reqwest::Response) -> Option<String> {
+    resp.headers()
+        .get("docker-content-digest")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+}
+
+/// Docker Hub page for hub images, `https://{registry}/{repository}` otherwise
+/// (ghcr.io / quay.io serve a page there).
+fn image_page_url(image: &ImageRef) -> String {
+    if image.registry == ImageRef::DOCKER_HUB {
+        match image.repository.strip_prefix("library/") {
+            Some(official) => format!("https://hub.docker.com/_/{}", official),
+            None => format!("https://hub.docker.com/r/{}", image.repository),
+        }
+    } else {
+        format!("https://{}/{}", image.registry, image.repository)
+    }
+}
+
+pub struct OciDigestProvider {
+    registry: OciRegistryClient,
+}
+
+impl OciDigestProvider {
+    pub fn new(registry: OciRegistryClient) -> Self {
+        Self { registry }
+    }
+}
+
+impl KindProvider for OciDigestProvider {
+    const KIND: &'static str = WatchKind::OCI_DIGEST;
+}
+
+#[async_trait]
+impl WatchProvider for OciDigestProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let (image, tag) = match &target.kind {
+            WatchKind::OciDigest { image, tag } => (image, tag),
+            _ => return Ok(None),
+        };
+
+        let api = self.registry.endpoint_for(image, target);
+        let digest = self.registry.manifest_digest(&api, image, tag).await?;
+
+        let subject = target.kind.subject();
+        let event_id = Event::make_event_id(&EventType::OciDigest, &subject, &digest);
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::OciDigest,
+            source: Source::Oci,
+            subject,
+            old_value: None,
+            new_value: digest,
+            occurred_at: None,
+            detected_at: now_string(),
+            url: Some(image_page_url(image)),
+            meta: Default::default(),
+        }))
+    }
+}
+
+/// Reports changes to the (filtered) tag list. The value is the tag count plus
+/// a short hash of the list (a repository can have thousands of tags); the list
+/// itself is kept as provider state, and the event meta says which tags were
+/// added / removed since it.
+pub struct OciTagsProvider {
+    registry: OciRegistryClient,
+    store: Arc<dyn ProviderStateStore>,
+    /// target id -> 新标签列表, 事件处理完 (`commit`) 才写入 store
+    pending: Mutex<HashMap<String, String>>,
+}
+
+impl OciTagsProvider {
+    pub fn new(registry: OciRegistryClient, store: Arc<dyn ProviderStateStore>) -> Self {
+        Self {
+            registry,
+            store,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl KindProvider for OciTagsProvider {
+    const KIND: &'static str = WatchKind::OCI_TAGS;
+}
+
+#[async_trait]
+impl WatchProvider for OciTagsProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let (image, filter) = match &target.kind {
+            WatchKind::OciTags { image, filter } => (image, filter),
+            _ => return Ok(None),
+        };
+
+        let api = self.registry.endpoint_for(image, target);
+        let mut tags = self.registry.tags(&api, image).await?;
+        if let Some(filter) = filter {
+            let re = NameFilter::compile(filter).map_err(|e| AppError::Provider(e.to_string()))?;
+            tags.retain(|t| re.is_match(t));
+        }
+        tags.sort_by(|a, b| compare_versions(a, b));
+        let list = tags.join(", ");
+        let value = format!("{} tags @ {}", tags.len(), short_hash(&list));
+
+        let subject = target.kind.subject();
+        let event_id = Event::make_event_id(&EventType::OciTags, &subject, &value);
+        let mut meta = std::collections::BTreeMap::new();
+
+        // 和上次处理过的完整列表比较
+        if let Some(prev) = self.store.get_provider_state(&target.id).await? {
+            let (added, removed) = version_list_diff(&prev, &list, compare_versions);
+            if !added.is_empty() {
+                meta.insert(META_ADDED.to_string(), added.join(", "));
+            }
+            if !removed.is_empty() {
+                meta.insert(META_REMOVED.to_string(), removed.join(", "));
+            }
+        }
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(target.id.clone(), list);
+        }
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::OciTags,
+            source: Source::Oci,
+            subject,
+            old_value: None,
+            new_value: value,
+            occurred_at: None,
+            detected_at: now_string(),
+            url: Some(image_page_url(image)),
+            meta,
+        }))
+    }
+
+    async fn commit(&self, target: &WatchTarget) -> AppResult<()> {
+        let list = self
+            .pending
+            .lock()
+            .map_err(|_| AppError::Provider("lock poisoned".into()))?
+            .remove(&target.id);
+        if let Some(list) = list {
+            self.store.set_provider_state(&target.id, &list).await?;
+        }
+        Ok(())
+    }
+}
+
+/// first 16 hex chars of sha256
+fn short_hash(text: &str) -> String {
+    let digest = Sha256::digest(text.as_bytes());
+    digest
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn now_epoch() -> i64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+fn now_string() -> String {
+    format!("{}s_since_epoch", now_epoch())
+}
@@ -0,0 +1,270 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::application::{AppError, AppResult, WatchProvider};
+use crate::domain::{
+    Event, EventType, META_PRIORITY, Source, WatchKind, WatchTarget, compare_versions,
+};
+use crate::infrastructure::api_endpoint::ApiEndpoint;
+use crate::infrastructure::provider_registry::KindProvider;
+
+pub const DEFAULT_OSV_API_URL: &str = "https://api.osv.dev";
+
+/// `Event::meta` keys set on advisory events
+pub const META_ADVISORY_ID: &str = "advisory_id";
+pub const META_SUMMARY: &str = "summary";
+pub const META_SEVERITY: &str = "severity";
+pub const META_ALIASES: &str = "aliases";
+/// affected ranges, e.g. ">=1.0.0 <1.2.3; <0.9.5"
+pub const META_AFFECTED: &str = "affected";
+pub const META_FIXED: &str = "fixed";
+
+/// 防止分页异常时无限翻页
+const MAX_PAGES: usize = 10;
+
+/// Security advisories for a package from an OSV-compatible API (osv.dev,
+/// or a mirror); every advisory is a high-priority event of its own.
+pub struct OsvAdvisoryProvider {
+    client: reqwest::Client,
+    api: ApiEndpoint,
+}
+
+impl OsvAdvisoryProvider {
+    pub fn new() -> Self {
+        Self::with_endpoint(ApiEndpoint::new(DEFAULT_OSV_API_URL))
+    }
+
+    pub fn with_endpoint(api: ApiEndpoint) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api,
+        }
+    }
+
+    async fn advisories(&self, target: &WatchTarget) -> AppResult<Option<Vec<Event>>> {
+        let (ecosystem, package) = match &target.kind {
+            WatchKind::Advisories { ecosystem, package } => (ecosystem, package),
+            _ => return Ok(None),
+        };
+
+        let api = self.api.resolve(target.endpoint.as_ref());
+        let mut vulns: Vec<Vuln> = vec![];
+        let mut page_token: Option<String> = None;
+        for _ in 0..MAX_PAGES {
+            let mut body = json!({ "package": { "name": package, "ecosystem": ecosystem } });
+            if let Some(token) = &page_token {
+                body["page_token"] = json!(token);
+            }
+            let resp: QueryResp = api
+                .authorize(self.client.post(api.url("/v1/query")))
+                .json(&body)
+                .send()
+                .await
+                .map_err(|e| AppError::Provider(e.to_string()))?
+                .error_for_status()
+                .map_err(|e| AppError::Provider(e.to_string()))?
+                .json()
+                .await
+                .map_err(|e| AppError::Provider(e.to_string()))?;
+            vulns.extend(resp.vulns);
+            page_token = resp.next_page_token.filter(|t| !t.is_empty());
+            if page_token.is_none() {
+                break;
+            }
+        }
+
+        // 最早发布的在前, 与 feed 一致
+        vulns.sort_by(|a, b| a.date().cmp(&b.date()).then_with(|| a.id.cmp(&b.id)));
+
+        let events = vulns
+            .into_iter()
+            .map(|v| {
+                let subject = package.clone();
+                let event_id = Event::make_event_id(&EventType::Advisory, &subject, &v.id);
+                let url = if api.base_url == DEFAULT_OSV_API_URL {
+                    format!("https://osv.dev/vulnerability/{}", v.id)
+                } else {
+                    api.url(&format!("/v1/vulns/{}", v.id))
+                };
+                let mut meta = std::collections::BTreeMap::new();
+                meta.insert(META_PRIORITY.to_string(), "high".to_string());
+                meta.insert(META_ADVISORY_ID.to_string(), v.id.clone());
+                let mut put = |k: &str, val: String| {
+                    if !val.is_empty() {
+                        meta.insert(k.to_string(), val);
+                    }
+                };
+                put(META_SUMMARY, v.summary.clone().unwrap_or_default());
+                put(META_SEVERITY, v.severity().unwrap_or_default());
+                put(META_ALIASES, v.aliases.join(", "));
+                let (affected, fixed) = v.ranges(package);
+                put(META_AFFECTED, affected);
+                put(META_FIXED, fixed);
+
+                Event {
+                    event_id,
+                    event_type: EventType::Advisory,
+                    source: Source::Osv,
+                    subject,
+                    old_value: None,
+                    occurred_at: v.date().map(|d| d.to_string()),
+                    new_value: v.id,
+                    detected_at: now_string(),
+                    url: Some(url),
+                    meta,
+                }
+            })
+            .collect();
+        Ok(Some(events))
+    }
+}
+
+impl Default for OsvAdvisoryProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KindProvider for OsvAdvisoryProvider {
+    const KIND: &'static str = WatchKind::ADVISORIES;
+}
+
+#[async_trait]
+impl WatchProvider for OsvAdvisoryProvider {
+    /// The newest advisory only; the run loop uses `check_items` for advisories.
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        Ok(self.advisories(target).await?.and_then(|mut v| v.pop()))
+    }
+
+    async fn check_items(&self, target: &WatchTarget) -> AppResult<Vec<Event>> {
+        Ok(self.advisories(target).await?.unwrap_or_default())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct QueryResp {
+    #[serde(default)]
+    vulns: Vec<Vuln>,
+    next_page_token: Option<String>,
+}
+
+/// The parts of an OSV record (https://ossf.github.io/osv-schema/) we report.
+#[derive(Debug, Deserialize)]
+struct Vuln {
+    id: String,
+    summary: Option<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+    published: Option<String>,
+    modified: Option<String>,
+    #[serde(default)]
+    severity: Vec<SeverityResp>,
+    #[serde(default)]
+    affected: Vec<AffectedResp>,
+    database_specific: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SeverityResp {
+    #[serde(rename = "type")]
+    kind: String,
+    score: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AffectedResp {
+    package: Option<AffectedPackage>,
+    #[serde(default)]
+    ranges: Vec<RangeResp>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AffectedPackage {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RangeResp {
+    #[serde(default)]
+    events: Vec<serde_json::Map<String, serde_json::Value>>,
+}
+
+impl Vuln {
+    fn date(&self) -> Option<&str> {
+        self.published.as_deref().or(self.modified.as_deref())
+    }
+
+    /// GHSA-style label ("HIGH", "MODERATE") when present, else the first CVSS vector.
+    fn severity(&self) -> Option<String> {
+        let label = self
+            .database_specific
+            .as_ref()
+            .and_then(|d| d.get("severity"))
+            .and_then(|s| s.as_str());
+        match label {
+            Some(l) => Some(l.to_ascii_uppercase()),
+            None => self
+                .severity
+                .first()
+                .map(|s| format!("{} {}", s.kind, s.score)),
+        }
+    }
+
+    /// (affected ranges, fixed versions) for the watched package; entries for
+    /// other packages in the same advisory are skipped.
+    fn ranges(&self, package: &str) -> (String, String) {
+        let mut affected: Vec<String> = vec![];
+        let mut fixed: Vec<String> = vec![];
+        let entries = self.affected.iter().filter(|a| match &a.package {
+            Some(p) => p.name.eq_ignore_ascii_case(package),
+            None => true,
+        });
+        for range in entries.flat_map(|a| &a.ranges) {
+            // events 按顺序成对出现: introduced, 然后 fixed / last_affected
+            let mut lower: Option<String> = None;
+            for event in &range.events {
+                let get = |k: &str| event.get(k).and_then(|v| v.as_str()).map(String::from);
+                if let Some(v) = get("introduced") {
+                    lower = (v != "0").then_some(v);
+                } else if let Some(v) = get("fixed") {
+                    affected.push(bounds(lower.take(), Some(format!("<{v}"))));
+                    fixed.push(v);
+                } else if let Some(v) = get("last_affected") {
+                    affected.push(bounds(lower.take(), Some(format!("<={v}"))));
+                } else if let Some(v) = get("limit") {
+                    affected.push(bounds(lower.take(), Some(format!("<{v}"))));
+                }
+            }
+            // 只有 introduced: 之后的版本都受影响
+            if let Some(v) = lower {
+                affected.push(bounds(Some(v), None));
+            }
+        }
+        if affected.is_empty() && !self.affected.is_empty() {
+            affected.push("*".to_string());
+        }
+        affected.dedup();
+        fixed.sort_by(|a, b| compare_versions(a, b));
+        fixed.dedup();
+        (affected.join("; "), fixed.join(", "))
+    }
+}
+
+fn bounds(lower: Option<String>, upper: Option<String>) -> String {
+    match (lower, upper) {
+        (Some(l), Some(u)) => format!(">={l} {u}"),
+        (Some(l), None) => format!(">={l}"),
+        (None, Some(u)) => u,
+        (None, None) => "*".to_string(),
+    }
+}
+
+fn now_string() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    format!("{}s_since_epoch", secs)
+}
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::application::{AppError, AppResult, WatchProvider};
+use crate::domain::{Event, WatchTarget};
+
+/// A provider that knows which watch kind it serves, so it can register itself.
+pub trait KindProvider: WatchProvider + 'static {
+    const KIND: &'static str;
+}
+
+/// Dispatches each target to the provider registered for its `WatchKind::key()`.
+///
+/// Built-in providers are registered under the `WatchKind::*` key constants;
+/// embedders can register their own provider for a `WatchKind::Custom` target
+/// (keyed by its `provider` name) or replace a built-in one.
+#[derive(Clone, Default)]
+pub struct ProviderRegistry {
+    providers: HashMap<String, Arc<dyn WatchProvider>>,
+}
+
+impl ProviderRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `provider` for `kind`, returning the provider it replaced (if any).
+    pub fn register(
+        &mut self,
+        kind: impl Into<String>,
+        provider: Arc<dyn WatchProvider>,
+    ) -> Option<Arc<dyn WatchProvider>> {
+        self.providers.insert(kind.into(), provider)
+    }
+
+    /// Register a provider under its own `KindProvider::KIND`.
+    pub fn register_provider<P: KindProvider>(
+        &mut self,
+        provider: P,
+    ) -> Option<Arc<dyn WatchProvider>> {
+        self.register(P::KIND, Arc::new(provider))
+    }
+
+    pub fn with(mut self, kind: impl Into<String>, provider: Arc<dyn WatchProvider>) -> Self {
+        self.register(kind, provider);
+        self
+    }
+
+    pub fn get(&self, kind: &str) -> Option<&Arc<dyn WatchProvider>> {
+        self.providers.get(kind)
+    }
+
+    pub fn contains(&self, kind: &str) -> bool {
+        self.providers.contains_key(kind)
+    }
+
+    pub fn kinds(&self) -> Vec<&str> {
+        let mut v: Vec<&str> = self.providers.keys().map(|k| k.as_str()).collect();
+        v.sort_unstable();
+        v
+    }
+}
+
+#[async_trait]
+impl WatchProvider for ProviderRegistry {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let kind = target.kind.key();
+        match self.providers.get(kind) {
+            Some(p) => p.check(target).await,
+            None => Err(AppError::Provider(format!(
+                "no provider registered for kind {kind}"
+            ))),
+        }
+    }
+
+    async fn enrich(&self, target: &WatchTarget, event: &mut Event) -> AppResult<()> {
+        match self.providers.get(target.kind.key()) {
+            Some(p) => p.enrich(target, event).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn check_items(&self, target: &WatchTarget) -> AppResult<Vec<Event>> {
+        let kind = target.kind.key();
+        match self.providers.get(kind) {
+            Some(p) => p.check_items(target).await,
+            None => Err(AppError::Provider(format!(
+                "no provider registered for kind {kind}"
+            ))),
+        }
+    }
+
+    async fn commit(&self, target: &WatchTarget) -> AppResult<()> {
+        match self.providers.get(target.kind.key()) {
+            Some(p) => p.commit(target).await,
+            None => Ok(()),
+        }
+    }
+}
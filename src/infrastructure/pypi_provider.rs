@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::application::{AppError, AppResult, WatchProvider};
+use crate::domain::{
+    Event, EventType, Pep440Version, Source, WatchKind, WatchTarget, version_list_diff,
+};
+use crate::infrastructure::api_endpoint::ApiEndpoint;
+use crate::infrastructure::provider_registry::KindProvider;
+
+pub const DEFAULT_PYPI_URL: &str = "https://pypi.org";
+
+/// `Event::meta` keys set on yank events
+pub const META_YANKED: &str = "yanked";
+pub const META_UNYANKED: &str = "unyanked";
+
+/// Shared PyPI JSON API access for the latest / yanked providers.
+struct PyPiApi {
+    client: reqwest::Client,
+    index: ApiEndpoint,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectResp {
+    /// version -> uploaded files
+    releases: HashMap<String, Vec<FileResp>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FileResp {
+    #[serde(default)]
+    yanked: bool,
+    upload_time_iso_8601: Option<String>,
+}
+
+/// One release with at least one uploaded file.
+struct Release {
+    version: String,
+    parsed: Pep440Version,
+    /// PyPI yanks whole releases, i.e. every file
+    yanked: bool,
+    uploaded_at: Option<String>,
+}
+
+impl PyPiApi {
+    async fn releases(
+        &self,
+        target: &WatchTarget,
+        project: &str,
+    ) -> AppResult<(ApiEndpoint, Vec<Release>)> {
+        let index = self.index.resolve(target.endpoint.as_ref());
+        let url = index.url(&format!("/pypi/{}/json", project));
+
+        let resp = index
+            .authorize(self.client.get(url))
+            .send()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+
+        let body: ProjectResp = resp
+            .json()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+
+        // 没有文件的 release (还没上传完) 和不符合 PEP 440 的旧版本号都跳过
+        let releases = body
+            .releases
+            .into_iter()
+            .filter(|(_, files)| !files.is_empty())
+            .filter_map(|(version, files)| {
+                let parsed = Pep440Version::parse(&version)?;
+                Some(Release {
+                    yanked: files.iter().all(|f| f.yanked),
+                    uploaded_at: files
+                        .iter()
+                        .filter_map(|f| f.upload_time_iso_8601.clone())
+                        .min(),
+                    version,
+                    parsed,
+                })
+            })
+            .collect();
+        Ok((index, releases))
+    }
+}
+
+/// project page (per version when given); pypi.org serves pages and API from the same root
+fn project_page_url(index: &ApiEndpoint, project: &str, version: Option<&str>) -> String {
+    match version {
+        Some(v) => format!("{}/project/{}/{}/", index.base_url, project, v),
+        None => format!("{}/project/{}/", index.base_url, project),
+    }
+}
+
+fn compare_pep440(a: &str, b: &str) -> std::cmp::Ordering {
+    match (Pep440Version::parse(a), Pep440Version::parse(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+pub struct PyPiLatestProvider {
+    api: PyPiApi,
+}
+
+impl PyPiLatestProvider {
+    pub fn new() -> Self {
+        Self::with_index(ApiEndpoint::new(DEFAULT_PYPI_URL))
+    }
+
+    /// Use another index serving the PyPI JSON API (devpi, Artifactory, local stand-in).
+    pub fn with_index(index: ApiEndpoint) -> Self {
+        Self {
+            api: PyPiApi {
+                client: reqwest::Client::new(),
+                index,
+            },
+        }
+    }
+}
+
+impl Default for PyPiLatestProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KindProvider for PyPiLatestProvider {
+    const KIND: &'static str = WatchKind::PYPI_LATEST;
+}
+
+#[async_trait]
+impl WatchProvider for PyPiLatestProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let (project, include_prereleases) = match &target.kind {
+            WatchKind::PyPiLatest {
+                project,
+                include_prereleases,
+            } => (project, *include_prereleases),
+            _ => return Ok(None),
+        };
+
+        let (index, releases) = self.api.releases(target, project).await?;
+
+        let latest = releases
+            .into_iter()
+            .filter(|r| !r.yanked)
+            .filter(|r| include_prereleases || !r.parsed.is_prerelease())
+            .max_by(|a, b| a.parsed.cmp(&b.parsed));
+        let Some(latest) = latest else {
+            return Ok(None);
+        };
+
+        let subject = project.to_string();
+        let event_id = Event::make_event_id(&EventType::PyPiLatest, &subject, &latest.version);
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::PyPiLatest,
+            source: Source::PyPi,
+            subject,
+            old_value: None,
+            url: Some(project_page_url(&index, project, Some(&latest.version))),
+            new_value: latest.version,
+            occurred_at: latest.uploaded_at,
+            detected_at: now_string(),
+            meta: Default::default(),
+        }))
+    }
+}
+
+/// Reports changes to the set of yanked releases; the value is the yanked
+/// versions in PEP 440 order, and the event meta says which were (un)yanked.
+pub struct PyPiYankedProvider {
+    api: PyPiApi,
+}
+
+impl PyPiYankedProvider {
+    pub fn new() -> Self {
+        Self::with_index(ApiEndpoint::new(DEFAULT_PYPI_URL))
+    }
+
+    pub fn with_index(index: ApiEndpoint) -> Self {
+        Self {
+            api: PyPiApi {
+                client: reqwest::Client::new(),
+                index,
+            },
+        }
+    }
+}
+
+impl Default for PyPiYankedProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KindProvider for PyPiYankedProvider {
+    const KIND: &'static str = WatchKind::PYPI_YANKED;
+}
+
+#[async_trait]
+impl WatchProvider for PyPiYankedProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let project = match &target.kind {
+            WatchKind::PyPiYanked { project } => project,
+            _ => return Ok(None),
+        };
+
+        let (index, releases) = self.api.releases(target, project).await?;
+
+        let mut yanked: Vec<Release> = releases.into_iter().filter(|r| r.yanked).collect();
+        yanked.sort_by(|a, b| a.parsed.cmp(&b.parsed));
+        let value = yanked
+            .iter()
+            .map(|r| r.version.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let subject = project.to_string();
+        let event_id = Event::make_event_id(&EventType::PyPiYanked, &subject, &value);
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::PyPiYanked,
+            source: Source::PyPi,
+            subject,
+            old_value: None,
+            new_value: value,
+            occurred_at: None,
+            detected_at: now_string(),
+            url: Some(project_page_url(&index, project, None)),
+            meta: Default::default(),
+        }))
+    }
+
+    async fn enrich(&self, _target: &WatchTarget, event: &mut Event) -> AppResult<()> {
+        let (yanked, unyanked) = version_list_diff(
+            event.old_value.as_deref().unwrap_or(""),
+            &event.new_value,
+            compare_pep440,
+        );
+        if !yanked.is_empty() {
+            event.meta.insert(META_YANKED.into(), yanked.join(", "));
+        }
+        if !unyanked.is_empty() {
+            event.meta.insert(META_UNYANKED.into(), unyanked.join(", "));
+        }
+        Ok(())
+    }
+}
+
+fn now_string() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    format!("{}s_since_epoch", secs)
+}
@@ -1,7 +1,9 @@
 use async_trait::async_trait;
 use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
 
-use crate::application::{AppError, AppResult, EventStore};
+use crate::application::{
+    AppError, AppResult, EventStore, HttpCacheEntry, HttpCacheStore, ProviderStateStore,
+};
 use crate::domain::Event;
 
 pub struct SqliteEventStore {
@@ -53,7 +55,8 @@ impl SqliteEventStore {
               url TEXT,
               target_id TEXT,
               labels TEXT,
-              detected_at_epoch INTEGER NOT NULL DEFAULT 0
+              detected_at_epoch INTEGER NOT NULL DEFAULT 0,
+              meta TEXT
             );
           "#,
         )
@@ -76,6 +79,11 @@ impl SqliteEventStore {
         .execute(&self.pool)
         .await;
 
+        // add meta (JSON object, provider-specific details)
+        let _ = sqlx::query("ALTER TABLE events ADD COLUMN meta TEXT")
+            .execute(&self.pool)
+            .await;
+
         sqlx::query(
             r#"
             CREATE TABLE IF NOT EXISTS notify_log (
@@ -88,6 +96,56 @@ impl SqliteEventStore {
         .await
         .map_err(|e| AppError::Storage(e.to_string()))?;
 
+        // target_state: 每个 target 最近一次观察到的值
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS target_state (
+                target_id TEXT PRIMARY KEY,
+                last_value TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Storage(e.to_string()))?;
+
+        // add revision (每次 last_value 更新加 1)
+        let _ =
+            sqlx::query("ALTER TABLE target_state ADD COLUMN revision INTEGER NOT NULL DEFAULT 0")
+                .execute(&self.pool)
+                .await;
+
+        // http_cache: 条件请求用的 ETag / Last-Modified (+ 对应的响应体)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS http_cache (
+                cache_key TEXT PRIMARY KEY,
+                etag TEXT,
+                last_modified TEXT,
+                body TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Storage(e.to_string()))?;
+
+        // provider_state: provider 自己在两次检查之间要记住的东西 (如完整标签列表)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS provider_state (
+                target_id TEXT PRIMARY KEY,
+                state TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Storage(e.to_string()))?;
+
         Ok(())
     }
 }
@@ -164,8 +222,8 @@ impl EventStore for SqliteEventStore {
             INSERT OR IGNORE INTO events(
                 event_id, event_type, source, subject,
                 old_value, new_value, occurred_at, detected_at, url,
-                target_id, labels, detected_at_epoch
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                target_id, labels, detected_at_epoch, meta
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&e.event_id)
@@ -173,11 +231,14 @@ impl EventStore for SqliteEventStore {
         .bind(e.source.to_string())
         .bind(&e.subject)
         .bind(e.old_value.as_deref())
+        .bind(&e.new_value)
+        .bind(e.occurred_at.as_deref())
         .bind(&e.detected_at)
         .bind(e.url.as_deref())
         .bind(&record.target_id)
         .bind(labels_joined.as_deref())
         .bind(record.detected_at_epoch)
+        .bind(meta_to_json(&e.meta))
         .execute(&self.pool)
         .await
         .map_err(|e| AppError::Storage(e.to_string()))?;
@@ -212,6 +273,49 @@ impl EventStore for SqliteEventStore {
         Ok(())
     }
 
+    async fn get_last_value(&self, target_id: &str) -> AppResult<Option<String>> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT last_value FROM target_state WHERE target_id = ? LIMIT 1")
+                .bind(target_id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| AppError::Storage(e.to_string()))?;
+
+        Ok(row.map(|t| t.0))
+    }
+
+    async fn set_last_value(&self, target_id: &str, value: &str) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO target_state(target_id, last_value, updated_at) VALUES(?, ?, ?)
+            ON CONFLICT(target_id) DO UPDATE SET
+                last_value=excluded.last_value,
+                updated_at=excluded.updated_at,
+                revision=target_state.revision + 1
+            "#,
+        )
+        .bind(target_id)
+        .bind(value)
+        .bind(now_epoch())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Storage(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get_last_value_revision(&self, target_id: &str) -> AppResult<Option<(String, u64)>> {
+        let row: Option<(String, i64)> = sqlx::query_as(
+            "SELECT last_value, revision FROM target_state WHERE target_id = ? LIMIT 1",
+        )
+        .bind(target_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Storage(e.to_string()))?;
+
+        Ok(row.map(|(value, revision)| (value, revision.max(0) as u64)))
+    }
+
     async fn list_events(&self, limit: u32) -> AppResult<Vec<Event>> {
         // 用 rowid 倒序拉最新（不依赖 detected_at 的格式）
         let limit_i64 = i64::from(limit);
@@ -226,7 +330,8 @@ impl EventStore for SqliteEventStore {
               new_value,
               occurred_at,
               detected_at,
-              url
+              url,
+              meta
             FROM events
             ORDER BY rowid DESC
             LIMIT ?
@@ -239,20 +344,9 @@ impl EventStore for SqliteEventStore {
 
         let mut out = Vec::with_capacity(rows.len());
         for r in rows {
-            let event_type = match r.event_type.as_str() {
-                "GitHubRelease" => crate::domain::EventType::GitHubRelease,
-                "GitHubBranch" => crate::domain::EventType::GitHubBranch,
-                "NpmLatest" => crate::domain::EventType::NpmLatest,
-                "WhatsAppWebVersion" => crate::domain::EventType::WhatsAppWebVersion,
-                _ => crate::domain::EventType::GitHubRelease, // fallback（也可改成 Err）
-            };
+            let event_type = parse_event_type(&r.event_type);
 
-            let source = match r.source.as_str() {
-                "github" => crate::domain::Source::GitHub,
-                "npm" => crate::domain::Source::Npm,
-                "whatsapp-web" => crate::domain::Source::WhatsAppWeb,
-                _ => crate::domain::Source::GitHub,
-            };
+            let source = parse_source(&r.source);
 
             out.push(crate::domain::Event {
                 event_id: r.event_id.unwrap_or_default(),
@@ -264,6 +358,7 @@ impl EventStore for SqliteEventStore {
                 occurred_at: r.occurred_at,
                 detected_at: r.detected_at,
                 url: r.url,
+                meta: meta_from_json(r.meta),
             });
         }
 
@@ -289,7 +384,7 @@ impl EventStore for SqliteEventStore {
             r#"
             SELECT
                 event_id, event_type, source, subject, old_value, new_value,
-                occurred_at, detected_at, url
+                occurred_at, detected_at, url, meta
             FROM events
             WHERE 1=1
             "#,
@@ -315,6 +410,11 @@ impl EventStore for SqliteEventStore {
             qb.push_bind(subject);
         }
 
+        if let Some(bump) = query.bump {
+            qb.push(" AND json_extract(meta, '$.bump') = ");
+            qb.push_bind(bump.as_str());
+        }
+
         qb.push(" ORDER BY detected_at_epoch DESC, rowid DESC LIMIT ");
         qb.push_bind(query.limit.min(500) as i64);
 
@@ -356,21 +456,11 @@ impl EventStore for SqliteEventStore {
             let url = row
                 .try_get("url")
                 .map_err(|e| AppError::Storage(e.to_string()))?;
+            let meta: Option<String> = row.try_get("meta").ok().flatten();
 
-            let event_type = match event_type_s.as_str() {
-                "GitHubRelease" => crate::domain::EventType::GitHubRelease,
-                "GitHubBranch" => crate::domain::EventType::GitHubBranch,
-                "NpmLatest" => crate::domain::EventType::NpmLatest,
-                "WhatsAppWebVersion" => crate::domain::EventType::WhatsAppWebVersion,
-                _ => crate::domain::EventType::GitHubRelease,
-            };
+            let event_type = parse_event_type(&event_type_s);
 
-            let source = match source_s.as_str() {
-                "github" => crate::domain::Source::GitHub,
-                "npm" => crate::domain::Source::Npm,
-                "whatsapp-web" => crate::domain::Source::WhatsAppWeb,
-                _ => crate::domain::Source::GitHub,
-            };
+            let source = parse_source(&source_s);
 
             out.push(crate::domain::Event {
                 event_id,
@@ -382,6 +472,7 @@ impl EventStore for SqliteEventStore {
                 occurred_at,
                 detected_at,
                 url,
+                meta: meta_from_json(meta),
             });
         }
 
@@ -399,7 +490,7 @@ impl EventStore for SqliteEventStore {
             SELECT
                 event_id, event_type, source, subject, old_value, new_value,
                 occurred_at, detected_at, url,
-                target_id, labels, detected_at_epoch
+                target_id, labels, detected_at_epoch, meta
             FROM events
             WHERE 1=1
             "#,
@@ -422,6 +513,11 @@ impl EventStore for SqliteEventStore {
             qb.push_bind(subj);
         }
 
+        if let Some(bump) = query.bump {
+            qb.push(" AND json_extract(meta, '$.bump') = ");
+            qb.push_bind(bump.as_str());
+        }
+
         qb.push(" ORDER BY detected_at_epoch DESC, rowid DESC LIMIT ");
         qb.push_bind(query.limit.min(500) as i64);
 
@@ -460,25 +556,15 @@ impl EventStore for SqliteEventStore {
             let url: Option<String> = row
                 .try_get("url")
                 .map_err(|e| AppError::Storage(e.to_string()))?;
+            let meta: Option<String> = row.try_get("meta").ok().flatten();
 
             let target_id: Option<String> = row.try_get("target_id").ok();
             let labels: Option<String> = row.try_get("labels").ok();
             let detected_at_epoch: i64 = row.try_get("detected_at_epoch").unwrap_or(0);
 
-            let event_type = match event_type_s.as_str() {
-                "GitHubRelease" => crate::domain::EventType::GitHubRelease,
-                "GitHubBranch" => crate::domain::EventType::GitHubBranch,
-                "NpmLatest" => crate::domain::EventType::NpmLatest,
-                "WhatsAppWebVersion" => crate::domain::EventType::WhatsAppWebVersion,
-                _ => crate::domain::EventType::GitHubRelease,
-            };
+            let event_type = parse_event_type(&event_type_s);
 
-            let source = match source_s.as_str() {
-                "github" => crate::domain::Source::GitHub,
-                "npm" => crate::domain::Source::Npm,
-                "whatsapp-web" => crate::domain::Source::WhatsAppWeb,
-                _ => crate::domain::Source::GitHub,
-            };
+            let source = parse_source(&source_s);
 
             let labels_vec = labels
                 .unwrap_or_default()
@@ -498,6 +584,7 @@ impl EventStore for SqliteEventStore {
                     occurred_at,
                     detected_at,
                     url,
+                    meta: meta_from_json(meta),
                 },
                 target_id: target_id.unwrap_or_default(),
                 labels: labels_vec,
@@ -522,7 +609,7 @@ impl EventStore for SqliteEventStore {
                 rowid as rowid,
                 event_id, event_type, source, subject, old_value, new_value,
                 occurred_at, detected_at, url,
-                target_id, labels, detected_at_epoch
+                target_id, labels, detected_at_epoch, meta
             FROM events
             WHERE 1=1
             "#,
@@ -549,6 +636,11 @@ impl EventStore for SqliteEventStore {
             qb.push_bind(subj);
         }
 
+        if let Some(bump) = query.bump {
+            qb.push(" AND json_extract(meta, '$.bump') = ");
+            qb.push_bind(bump.as_str());
+        }
+
         // replay 要从旧到新，所以 ORDER BY rowid ASC
         qb.push(" ORDER BY rowid ASC LIMIT ");
         qb.push_bind(query.limit.min(500) as i64);
@@ -592,25 +684,15 @@ impl EventStore for SqliteEventStore {
             let url: Option<String> = row
                 .try_get("url")
                 .map_err(|e| AppError::Storage(e.to_string()))?;
+            let meta: Option<String> = row.try_get("meta").ok().flatten();
 
             let target_id: Option<String> = row.try_get("target_id").ok();
             let labels: Option<String> = row.try_get("labels").ok();
             let detected_at_epoch: i64 = row.try_get("detected_at_epoch").unwrap_or(0);
 
-            let event_type = match event_type_s.as_str() {
-                "GitHubRelease" => crate::domain::EventType::GitHubRelease,
-                "GitHubBranch" => crate::domain::EventType::GitHubBranch,
-                "NpmLatest" => crate::domain::EventType::NpmLatest,
-                "WhatsAppWebVersion" => crate::domain::EventType::WhatsAppWebVersion,
-                _ => crate::domain::EventType::GitHubRelease,
-            };
+            let event_type = parse_event_type(&event_type_s);
 
-            let source = match source_s.as_str() {
-                "github" => crate::domain::Source::GitHub,
-                "npm" => crate::domain::Source::Npm,
-                "whatsapp-web" => crate::domain::Source::WhatsAppWeb,
-                _ => crate::domain::Source::GitHub,
-            };
+            let source = parse_source(&source_s);
 
             let labels_vec = labels
                 .unwrap_or_default()
@@ -630,6 +712,7 @@ impl EventStore for SqliteEventStore {
                     occurred_at,
                     detected_at,
                     url,
+                    meta: meta_from_json(meta),
                 },
                 target_id: target_id.unwrap_or_default(),
                 labels: labels_vec,
@@ -659,8 +742,8 @@ impl EventStore for SqliteEventStore {
             INSERT OR IGNORE INTO events(
                 event_id, event_type, source, subject,
                 old_value, new_value, occurred_at, detected_at, url,
-                target_id, labels, detected_at_epoch
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                target_id, labels, detected_at_epoch, meta
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&e.event_id)
@@ -675,6 +758,7 @@ impl EventStore for SqliteEventStore {
         .bind(&record.target_id)
         .bind(labels_joined.as_deref())
         .bind(record.detected_at_epoch)
+        .bind(meta_to_json(&e.meta))
         .execute(&self.pool)
         .await
         .map_err(|e| AppError::Storage(e.to_string()))?;
@@ -690,6 +774,83 @@ impl EventStore for SqliteEventStore {
     }
 }
 
+#[async_trait]
+impl HttpCacheStore for SqliteEventStore {
+    async fn get_http_cache(&self, key: &str) -> AppResult<Option<HttpCacheEntry>> {
+        let row: Option<(Option<String>, Option<String>, String)> = sqlx::query_as(
+            "SELECT etag, last_modified, body FROM http_cache WHERE cache_key = ? LIMIT 1",
+        )
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Storage(e.to_string()))?;
+
+        Ok(row.map(|(etag, last_modified, body)| HttpCacheEntry {
+            etag,
+            last_modified,
+            body,
+        }))
+    }
+
+    async fn set_http_cache(&self, key: &str, entry: &HttpCacheEntry) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO http_cache(cache_key, etag, last_modified, body, updated_at)
+            VALUES(?, ?, ?, ?, ?)
+            ON CONFLICT(cache_key) DO UPDATE SET
+                etag=excluded.etag,
+                last_modified=excluded.last_modified,
+                body=excluded.body,
+                updated_at=excluded.updated_at
+            "#,
+        )
+        .bind(key)
+        .bind(entry.etag.as_deref())
+        .bind(entry.last_modified.as_deref())
+        .bind(&entry.body)
+        .bind(now_epoch())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Storage(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ProviderStateStore for SqliteEventStore {
+    async fn get_provider_state(&self, target_id: &str) -> AppResult<Option<String>> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT state FROM provider_state WHERE target_id = ? LIMIT 1")
+                .bind(target_id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| AppError::Storage(e.to_string()))?;
+
+        Ok(row.map(|r| r.0))
+    }
+
+    async fn set_provider_state(&self, target_id: &str, state: &str) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO provider_state(target_id, state, updated_at)
+            VALUES(?, ?, ?)
+            ON CONFLICT(target_id) DO UPDATE SET
+                state=excluded.state,
+                updated_at=excluded.updated_at
+            "#,
+        )
+        .bind(target_id)
+        .bind(state)
+        .bind(now_epoch())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Storage(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
 fn now_string() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
     let secs = SystemTime::now()
@@ -698,3 +859,81 @@ fn now_string() -> String {
         .as_secs();
     format!("{}s_since_epoch", secs)
 }
+
+fn now_epoch() -> i64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// events.event_type 存的是 `{:?}`; 认不出的旧值回退到 GitHubRelease
+fn parse_event_type(s: &str) -> crate::domain::EventType {
+    use crate::domain::EventType;
+    match s {
+        "GitHubRelease" => EventType::GitHubRelease,
+        "GitHubBranch" => EventType::GitHubBranch,
+        "GitHubTag" => EventType::GitHubTag,
+        "GitHubWorkflow" => EventType::GitHubWorkflow,
+        "GitHubSearchItem" => EventType::GitHubSearchItem,
+        "GitLabRelease" => EventType::GitLabRelease,
+        "GitLabBranch" => EventType::GitLabBranch,
+        "GiteaRelease" => EventType::GiteaRelease,
+        "GiteaBranch" => EventType::GiteaBranch,
+        "NpmLatest" => EventType::NpmLatest,
+        "NpmDistTag" => EventType::NpmDistTag,
+        "NpmDeprecated" => EventType::NpmDeprecated,
+        "NpmUnpublished" => EventType::NpmUnpublished,
+        "NpmModified" => EventType::NpmModified,
+        "CratesIoLatest" => EventType::CratesIoLatest,
+        "CratesIoYanked" => EventType::CratesIoYanked,
+        "PyPiLatest" => EventType::PyPiLatest,
+        "PyPiYanked" => EventType::PyPiYanked,
+        "MavenLatest" => EventType::MavenLatest,
+        "GoModule" => EventType::GoModule,
+        "OciDigest" => EventType::OciDigest,
+        "OciTags" => EventType::OciTags,
+        "WhatsAppWebVersion" => EventType::WhatsAppWebVersion,
+        "HttpJson" => EventType::HttpJson,
+        "HttpPage" => EventType::HttpPage,
+        "FeedEntry" => EventType::FeedEntry,
+        "Advisory" => EventType::Advisory,
+        "Custom" => EventType::Custom,
+        _ => EventType::GitHubRelease,
+    }
+}
+
+/// events.source 存的是 Source 的 Display
+fn parse_source(s: &str) -> crate::domain::Source {
+    use crate::domain::Source;
+    match s {
+        "github" => Source::GitHub,
+        "gitlab" => Source::GitLab,
+        "gitea" => Source::Gitea,
+        "npm" => Source::Npm,
+        "crates-io" => Source::CratesIo,
+        "pypi" => Source::PyPi,
+        "maven" => Source::Maven,
+        "go" => Source::Go,
+        "oci" => Source::Oci,
+        "whatsapp-web" => Source::WhatsAppWeb,
+        "http" => Source::Http,
+        "feed" => Source::Feed,
+        "osv" => Source::Osv,
+        "custom" => Source::Custom,
+        _ => Source::GitHub,
+    }
+}
+
+fn meta_to_json(meta: &std::collections::BTreeMap<String, String>) -> Option<String> {
+    if meta.is_empty() {
+        return None;
+    }
+    serde_json::to_string(meta).ok()
+}
+
+fn meta_from_json(raw: Option<String>) -> std::collections::BTreeMap<String, String> {
+    raw.and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
@@ -0,0 +1,107 @@
+use async_trait::async_trait;
+use reqwest::header::{ACCEPT, USER_AGENT};
+use serde::Deserialize;
+
+use crate::application::{AppError, AppResult, WatchProvider};
+use crate::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use crate::infrastructure::provider_registry::KindProvider;
+
+/// WhatsApp Web 自身的版本检查接口, 返回 `currentVersion`
+pub const DEFAULT_WHATSAPP_WEB_VERSION_URL: &str =
+    "https://web.whatsapp.com/check-update?version=2.3000.0&platform=web";
+
+pub struct WhatsAppWebVersionProvider {
+    client: reqwest::Client,
+    default_url: String,
+}
+
+impl WhatsAppWebVersionProvider {
+    pub fn new() -> Self {
+        Self::with_url(DEFAULT_WHATSAPP_WEB_VERSION_URL)
+    }
+
+    /// Use a different endpoint for targets that don't set their own `url`
+    /// (e.g. a mirror such as wppconnect's versions.json, or a local stand-in in tests).
+    pub fn with_url(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            default_url: url.into(),
+        }
+    }
+}
+
+impl Default for WhatsAppWebVersionProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionResp {
+    #[serde(
+        rename = "currentVersion",
+        alias = "current_version",
+        alias = "version"
+    )]
+    current_version: Option<String>,
+}
+
+impl KindProvider for WhatsAppWebVersionProvider {
+    const KIND: &'static str = WatchKind::WHATSAPP_WEB_VERSION;
+}
+
+#[async_trait]
+impl WatchProvider for WhatsAppWebVersionProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let url = match &target.kind {
+            WatchKind::WhatsAppWebVersion { url } => url.as_deref().unwrap_or(&self.default_url),
+            _ => return Ok(None),
+        };
+
+        let resp = self
+            .client
+            .get(url)
+            .header(USER_AGENT, "repopulse")
+            .header(ACCEPT, "application/json")
+            .send()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+
+        let body: VersionResp = resp
+            .json()
+            .await
+            .map_err(|e| AppError::Provider(e.to_string()))?;
+
+        let version = match body.current_version {
+            Some(v) if !v.trim().is_empty() => v.trim().to_string(),
+            _ => return Ok(None),
+        };
+
+        let subject = target.kind.subject();
+        let event_id = Event::make_event_id(&EventType::WhatsAppWebVersion, &subject, &version);
+
+        Ok(Some(Event {
+            event_id,
+            event_type: EventType::WhatsAppWebVersion,
+            source: Source::WhatsAppWeb,
+            subject,
+            old_value: None,
+            new_value: version,
+            occurred_at: None,
+            detected_at: now_string(),
+            url: Some(url.to_string()),
+            meta: Default::default(),
+        }))
+    }
+}
+
+fn now_string() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    format!("{}s_since_epoch", secs)
+}
@@ -1,13 +1,22 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
 use serde::Deserialize;
 
-use crate::domain::{RepoId, WatchKind, WatchTarget};
+use crate::domain::{
+    Endpoint, Forge, ImageRef, NameFilter, RepoId, Schedule, TagOrder, VersionBump, VersionPolicy,
+    VersionRange, WatchKind, WatchTarget,
+};
 
 #[derive(Debug, Deserialize)]
 pub struct Config {
     pub poll_interval_seconds: u64,
     pub cooldown_seconds: Option<u64>,
     pub sse: Option<SseCfg>,
+    pub concurrency: Option<ConcurrencyCfg>,
+    pub providers: Option<ProvidersCfg>,
     pub targets: Vec<TargetCfg>,
+    /// dependency manifests whose packages become targets as well
+    pub discovery: Option<Vec<DiscoveryCfg>>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -17,32 +26,258 @@ pub struct SseCfg {
     pub replay_max: Option<u32>,
 }
 
+/// e.g. path: "../my-app" (package.json / package-lock.json, Cargo.toml /
+/// Cargo.lock and requirements.txt files, searched recursively)
+#[derive(Debug, Deserialize, Clone)]
+pub struct DiscoveryCfg {
+    /// a manifest file or a directory; relative paths are relative to the working directory
+    pub path: String,
+    /// added to every discovered target, besides "manifest:<file>"
+    pub labels: Option<Vec<String>>,
+    /// also devDependencies / dev- and build-dependencies (default false)
+    pub dev_dependencies: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ConcurrencyCfg {
+    pub global: Option<usize>,
+    /// e.g. { github: 4, npm: 8 }
+    pub per_source: Option<HashMap<String, usize>>,
+    pub check_timeout_seconds: Option<u64>,
+}
+
+/// Provider-wide API roots / credentials (targets can override them).
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ProvidersCfg {
+    /// e.g. GitHub Enterprise: https://ghe.example.com/api/v3 (token defaults to $GITHUB_TOKEN)
+    pub github: Option<EndpointCfg>,
+    /// self-managed GitLab: https://gitlab.example.com/api/v4 (token defaults to $GITLAB_TOKEN)
+    pub gitlab: Option<EndpointCfg>,
+    /// Gitea / Forgejo API root (defaults to https://codeberg.org/api/v1; token defaults to $GITEA_TOKEN)
+    pub gitea: Option<EndpointCfg>,
+    /// e.g. Verdaccio / Artifactory npm mirror
+    pub npm: Option<EndpointCfg>,
+    /// crates.io-compatible API root (defaults to https://crates.io)
+    pub crates_io: Option<EndpointCfg>,
+    /// PyPI JSON API root, e.g. a devpi / Artifactory mirror (defaults to https://pypi.org)
+    pub pypi: Option<EndpointCfg>,
+    /// Maven repository root, e.g. Nexus (defaults to https://repo1.maven.org/maven2)
+    pub maven: Option<EndpointCfg>,
+    /// GOPROXY-compatible endpoint (defaults to https://proxy.golang.org)
+    pub goproxy: Option<EndpointCfg>,
+    /// OSV-compatible advisory API (defaults to https://api.osv.dev)
+    pub osv: Option<EndpointCfg>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct EndpointCfg {
+    pub base_url: Option<String>,
+    /// Bearer token, or Basic password when username is set
+    pub token: Option<String>,
+    pub username: Option<String>,
+}
+
+/// One entry under `targets:`. Fields shared by every target type live here;
+/// the type-specific ones come from `TargetKindCfg` (selected by `type`).
+#[derive(Debug, Deserialize)]
+pub struct TargetCfg {
+    #[serde(flatten)]
+    pub kind: TargetKindCfg,
+    pub id: Option<String>,
+    pub enabled: Option<bool>,
+    pub labels: Option<Vec<String>>,
+    /// e.g. 60 / "30s" / "5m" / "1d"; defaults to poll_interval_seconds
+    pub interval: Option<DurationCfg>,
+    /// cron expression in UTC, e.g. "0 9 * * 1-5" (mutually exclusive with interval)
+    pub cron: Option<String>,
+    /// random delay added to every scheduled check, e.g. "30s"
+    pub jitter: Option<DurationCfg>,
+    /// override the provider's API root for this target (GitHub Enterprise, private registry)
+    pub base_url: Option<String>,
+    pub token: Option<String>,
+    pub username: Option<String>,
+    /// only for version-valued kinds (releases, registry latest, ...)
+    pub version_policy: Option<VersionPolicyCfg>,
+    /// also watch the package's security advisories (OSV), as a target "<id>:advisories";
+    /// npm / crates.io / PyPI / Maven / Go targets only
+    pub advisories: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct VersionPolicyCfg {
+    pub ignore_prereleases: Option<bool>,
+    /// npm-style range, e.g. ">=2 <3" or "^1.4 || ^2"
+    pub range: Option<String>,
+    /// major | minor | patch | prerelease; smaller bumps are recorded but not notified
+    pub notify_on: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum DurationCfg {
+    Seconds(u64),
+    Text(String),
+}
+
+impl DurationCfg {
+    pub fn as_seconds(&self) -> anyhow::Result<u64> {
+        match self {
+            DurationCfg::Seconds(n) => Ok(*n),
+            DurationCfg::Text(s) => parse_duration_seconds(s)
+                .ok_or_else(|| anyhow::anyhow!("invalid duration: {s} (use 30s/5m/1h/1d)")),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type")]
-pub enum TargetCfg {
+pub enum TargetKindCfg {
     #[serde(rename = "github_release")]
-    GitHubRelease {
+    GitHubRelease { repo: String },
+
+    #[serde(rename = "github_branch")]
+    GitHubBranch { repo: String, branch: String },
+
+    /// Release on any forge; `forge`: github (default) | gitlab | gitea (also forgejo).
+    /// GitLab repos may be nested: "group/subgroup/project".
+    #[serde(rename = "release")]
+    Release { forge: Option<String>, repo: String },
+
+    #[serde(rename = "branch")]
+    Branch {
+        forge: Option<String>,
         repo: String,
-        id: Option<String>,
-        enabled: Option<bool>,
-        labels: Option<Vec<String>>,
+        branch: String,
     },
 
-    #[serde(rename = "github_branch")]
-    GitHubBranch {
+    #[serde(rename = "github_tag")]
+    GitHubTag {
+        repo: String,
+        /// glob, e.g. "v1.*"
+        pattern: Option<String>,
+        /// regex; mutually exclusive with `pattern`
+        regex: Option<String>,
+        /// "semver" (default) | "created"
+        order: Option<String>,
+    },
+
+    /// GitHub issue / PR search, e.g. query: "repo:owner/repo label:bug is:open"
+    #[serde(rename = "github_search")]
+    GitHubSearch { query: String },
+
+    /// e.g. workflow: "ci.yml" (file name or numeric id)
+    #[serde(rename = "github_workflow")]
+    GitHubWorkflow {
         repo: String,
+        workflow: String,
         branch: String,
-        id: Option<String>,
-        enabled: Option<bool>,
-        labels: Option<Vec<String>>,
     },
 
     #[serde(rename = "npm_latest")]
     NpmLatest {
         package: String,
-        id: Option<String>,
-        enabled: Option<bool>,
-        labels: Option<Vec<String>>,
+        /// also watch these dist-tags ("next", "beta", ...), each as a target "<id>:<tag>"
+        dist_tags: Option<Vec<String>>,
+        /// also watch deprecated versions, as a target "<id>:deprecated" (default false)
+        deprecations: Option<bool>,
+        /// also watch unpublished versions, as a target "<id>:unpublished" (default false)
+        unpublished: Option<bool>,
+        /// also watch `time.modified`, as a target "<id>:modified" (default false)
+        modified: Option<bool>,
+    },
+
+    #[serde(rename = "crates_io_latest")]
+    CratesIoLatest {
+        #[serde(rename = "crate")]
+        name: String,
+        /// also consider pre-release versions (default false)
+        prereleases: Option<bool>,
+        /// also watch yanks, as a second target "<id>:yanked" (default false)
+        yanks: Option<bool>,
+    },
+
+    #[serde(rename = "pypi_latest")]
+    PyPiLatest {
+        project: String,
+        /// also consider pre-releases / dev releases (default false)
+        prereleases: Option<bool>,
+        /// also watch yanked releases, as a second target "<id>:yanked" (default false)
+        yanks: Option<bool>,
+    },
+
+    /// e.g. artifact: "com.google.guava:guava"
+    #[serde(rename = "maven_latest")]
+    MavenLatest {
+        artifact: String,
+        /// also report -SNAPSHOT versions (default false)
+        snapshots: Option<bool>,
+    },
+
+    /// e.g. module: "github.com/spf13/cobra" or "github.com/go-redis/redis/v9"
+    #[serde(rename = "go_module")]
+    GoModule {
+        module: String,
+        /// also consider pre-release versions (default false)
+        prereleases: Option<bool>,
+    },
+
+    /// e.g. image: "node:20-alpine" (tag defaults to "latest")
+    #[serde(rename = "oci_digest")]
+    OciDigest { image: String },
+
+    #[serde(rename = "oci_tags")]
+    OciTags {
+        image: String,
+        /// glob, e.g. "20-*"
+        pattern: Option<String>,
+        /// regex; mutually exclusive with `pattern`
+        regex: Option<String>,
+    },
+
+    #[serde(rename = "whatsapp_web_version")]
+    WhatsAppWebVersion {
+        /// version endpoint; defaults to web.whatsapp.com check-update
+        url: Option<String>,
+    },
+
+    /// e.g. url: "https://status.example.com/api/v2/status.json", selector: "$.status.indicator"
+    #[serde(rename = "http_json")]
+    HttpJson {
+        url: String,
+        /// JSONPath (RFC 9535); the selected value becomes the watched value
+        selector: String,
+        headers: Option<BTreeMap<String, String>>,
+        /// default 200
+        expected_status: Option<u16>,
+        /// event link, `{value}` is replaced by the selected value (defaults to url)
+        link_template: Option<String>,
+    },
+
+    /// e.g. url: "https://example.com/changelog", selector: "main article"
+    #[serde(rename = "http_page")]
+    HttpPage {
+        url: String,
+        headers: Option<BTreeMap<String, String>>,
+        /// CSS selector narrowing the page (text of the matched elements)
+        selector: Option<String>,
+        /// regex over the (selected) HTML; group 1 if present, else the whole match
+        regex: Option<String>,
+    },
+
+    /// RSS 2.0 / Atom, e.g. url: "https://github.com/owner/repo/releases.atom"
+    #[serde(rename = "feed")]
+    Feed { url: String },
+
+    /// OSV advisories for any package, e.g. ecosystem: "npm", package: "lodash"
+    #[serde(rename = "advisories")]
+    Advisories { ecosystem: String, package: String },
+
+    /// Handled by a provider registered by the embedding application.
+    #[serde(rename = "custom")]
+    Custom {
+        provider: String,
+        subject: String,
+        params: Option<BTreeMap<String, String>>,
     },
 }
 
@@ -58,69 +293,503 @@ impl Config {
         let mut out = Vec::new();
 
         for t in &self.targets {
-            match t {
-                TargetCfg::GitHubRelease {
+            let (default_id, kind) = match &t.kind {
+                TargetKindCfg::GitHubRelease { repo } => (
+                    format!("github:{}:release", repo),
+                    WatchKind::GitHubRelease {
+                        repo: RepoId::parse(repo)?,
+                    },
+                ),
+                TargetKindCfg::GitHubBranch { repo, branch } => (
+                    format!("github:{}:branch:{}", repo, branch),
+                    WatchKind::GitHubBranch {
+                        repo: RepoId::parse(repo)?,
+                        branch: branch.clone(),
+                    },
+                ),
+                TargetKindCfg::Release { forge, repo } => {
+                    let forge = parse_forge(forge)?;
+                    (
+                        format!("{}:{}:release", forge, repo),
+                        WatchKind::release(forge, forge.parse_repo(repo)?),
+                    )
+                }
+                TargetKindCfg::Branch {
+                    forge,
                     repo,
-                    id,
-                    enabled,
-                    labels,
+                    branch,
                 } => {
-                    let repo_id = RepoId::parse(repo)?;
-                    let target_id = id
-                        .clone()
-                        .unwrap_or_else(|| format!("github:{}:release", repo));
-                    out.push(WatchTarget {
-                        id: target_id,
-                        enabled: enabled.unwrap_or(true),
-                        labels: labels.clone().unwrap_or_default(),
-                        kind: WatchKind::GitHubRelease { repo: repo_id },
-                    })
+                    let forge = parse_forge(forge)?;
+                    (
+                        format!("{}:{}:branch:{}", forge, repo, branch),
+                        WatchKind::branch(forge, forge.parse_repo(repo)?, branch.clone()),
+                    )
+                }
+                TargetKindCfg::GitHubTag {
+                    repo,
+                    pattern,
+                    regex,
+                    order,
+                } => (
+                    format!("github:{}:tag", repo),
+                    WatchKind::GitHubTag {
+                        repo: RepoId::parse(repo)?,
+                        filter: name_filter(pattern, regex)?,
+                        order: match order.as_deref() {
+                            None | Some("semver") => TagOrder::Semver,
+                            Some("created") => TagOrder::Created,
+                            Some(o) => anyhow::bail!("invalid tag order: {o} (semver/created)"),
+                        },
+                    },
+                ),
+                TargetKindCfg::GitHubSearch { query } => {
+                    if query.trim().is_empty() {
+                        anyhow::bail!("github_search targets need a query");
+                    }
+                    (
+                        format!("github:search:{}", query),
+                        WatchKind::GitHubSearch {
+                            query: query.clone(),
+                        },
+                    )
                 }
-                TargetCfg::GitHubBranch {
+                TargetKindCfg::GitHubWorkflow {
                     repo,
+                    workflow,
                     branch,
-                    id,
-                    enabled,
-                    labels,
                 } => {
-                    let repo_id = RepoId::parse(repo)?;
-                    let target_id = id
-                        .clone()
-                        .unwrap_or_else(|| format!("github:{}:branch", repo));
-                    out.push(WatchTarget {
-                        id: target_id,
-                        enabled: enabled.unwrap_or(true),
-                        labels: labels.clone().unwrap_or_default(),
-                        kind: WatchKind::GitHubBranch {
-                            repo: repo_id,
+                    if workflow.trim().is_empty() || branch.trim().is_empty() {
+                        anyhow::bail!("github_workflow targets need a workflow and a branch");
+                    }
+                    (
+                        format!("github:{}:workflow:{}:{}", repo, workflow, branch),
+                        WatchKind::GitHubWorkflow {
+                            repo: RepoId::parse(repo)?,
+                            workflow: workflow.clone(),
                             branch: branch.clone(),
                         },
-                    })
+                    )
                 }
-                TargetCfg::NpmLatest {
-                    package,
-                    id,
-                    enabled,
-                    labels,
+                TargetKindCfg::NpmLatest { package, .. } => (
+                    format!("npm:{}:latest", package),
+                    WatchKind::NpmLatest {
+                        package: package.clone(),
+                    },
+                ),
+                TargetKindCfg::CratesIoLatest {
+                    name, prereleases, ..
+                } => (
+                    format!("crates-io:{}:latest", name),
+                    WatchKind::CratesIoLatest {
+                        name: name.clone(),
+                        include_prereleases: prereleases.unwrap_or(false),
+                    },
+                ),
+                TargetKindCfg::PyPiLatest {
+                    project,
+                    prereleases,
+                    ..
+                } => (
+                    format!("pypi:{}:latest", project),
+                    WatchKind::PyPiLatest {
+                        project: project.clone(),
+                        include_prereleases: prereleases.unwrap_or(false),
+                    },
+                ),
+                TargetKindCfg::MavenLatest {
+                    artifact,
+                    snapshots,
                 } => {
-                    let target_id = id
-                        .clone()
-                        .unwrap_or_else(|| format!("npm:{}:latest", package));
-                    out.push(WatchTarget {
-                        id: target_id,
-                        enabled: enabled.unwrap_or(true),
-                        labels: labels.clone().unwrap_or_default(),
-                        kind: WatchKind::NpmLatest {
+                    let Some((group_id, artifact_id)) = artifact
+                        .split_once(':')
+                        .filter(|(g, a)| !g.is_empty() && !a.is_empty() && !a.contains(':'))
+                    else {
+                        anyhow::bail!(
+                            "invalid maven artifact: {artifact} (expected groupId:artifactId)"
+                        );
+                    };
+                    (
+                        format!("maven:{}:latest", artifact),
+                        WatchKind::MavenLatest {
+                            group_id: group_id.to_string(),
+                            artifact_id: artifact_id.to_string(),
+                            include_snapshots: snapshots.unwrap_or(false),
+                        },
+                    )
+                }
+                TargetKindCfg::GoModule {
+                    module,
+                    prereleases,
+                } => (
+                    format!("go:{}:latest", module),
+                    WatchKind::GoModule {
+                        module: module.clone(),
+                        include_prereleases: prereleases.unwrap_or(false),
+                    },
+                ),
+                TargetKindCfg::OciDigest { image } => {
+                    let (image_ref, tag) = ImageRef::parse(image)?;
+                    let tag = tag.unwrap_or_else(|| "latest".to_string());
+                    (
+                        format!("oci:{}:{}:digest", image_ref.as_str(), tag),
+                        WatchKind::OciDigest {
+                            image: image_ref,
+                            tag,
+                        },
+                    )
+                }
+                TargetKindCfg::OciTags {
+                    image,
+                    pattern,
+                    regex,
+                } => {
+                    let (image_ref, tag) = ImageRef::parse(image)?;
+                    if tag.is_some() {
+                        anyhow::bail!("oci_tags image must not include a tag: {image}");
+                    }
+                    (
+                        format!("oci:{}:tags", image_ref.as_str()),
+                        WatchKind::OciTags {
+                            image: image_ref,
+                            filter: name_filter(pattern, regex)?,
+                        },
+                    )
+                }
+                TargetKindCfg::WhatsAppWebVersion { url } => (
+                    "whatsapp-web:version".to_string(),
+                    WatchKind::WhatsAppWebVersion { url: url.clone() },
+                ),
+                TargetKindCfg::HttpJson {
+                    url,
+                    selector,
+                    headers,
+                    expected_status,
+                    link_template,
+                } => {
+                    reqwest::Url::parse(url)
+                        .map_err(|e| anyhow::anyhow!("invalid http_json url {url}: {e}"))?;
+                    serde_json_path::JsonPath::parse(selector)
+                        .map_err(|e| anyhow::anyhow!("invalid selector {selector}: {e}"))?;
+                    let expected_status = expected_status.unwrap_or(200);
+                    if !(100..=599).contains(&expected_status) {
+                        anyhow::bail!("invalid expected_status: {expected_status}");
+                    }
+                    (
+                        format!("http:{}:json", url),
+                        WatchKind::HttpJson {
+                            url: url.clone(),
+                            headers: headers.clone().unwrap_or_default(),
+                            selector: selector.clone(),
+                            expected_status,
+                            link_template: link_template.clone(),
+                        },
+                    )
+                }
+                TargetKindCfg::HttpPage {
+                    url,
+                    headers,
+                    selector,
+                    regex,
+                } => {
+                    reqwest::Url::parse(url)
+                        .map_err(|e| anyhow::anyhow!("invalid http_page url {url}: {e}"))?;
+                    if let Some(s) = selector {
+                        scraper::Selector::parse(s)
+                            .map_err(|e| anyhow::anyhow!("invalid css selector {s}: {e}"))?;
+                    }
+                    if let Some(r) = regex {
+                        regex::Regex::new(r)?;
+                    }
+                    (
+                        format!("http:{}:page", url),
+                        WatchKind::HttpPage {
+                            url: url.clone(),
+                            headers: headers.clone().unwrap_or_default(),
+                            selector: selector.clone(),
+                            regex: regex.clone(),
+                        },
+                    )
+                }
+                TargetKindCfg::Feed { url } => {
+                    reqwest::Url::parse(url)
+                        .map_err(|e| anyhow::anyhow!("invalid feed url {url}: {e}"))?;
+                    (
+                        format!("feed:{}", url),
+                        WatchKind::Feed { url: url.clone() },
+                    )
+                }
+                TargetKindCfg::Advisories { ecosystem, package } => {
+                    if ecosystem.trim().is_empty() || package.trim().is_empty() {
+                        anyhow::bail!("advisories targets need an ecosystem and a package");
+                    }
+                    (
+                        format!("osv:{}:{}", ecosystem, package),
+                        WatchKind::Advisories {
+                            ecosystem: ecosystem.clone(),
                             package: package.clone(),
                         },
+                    )
+                }
+                TargetKindCfg::Custom {
+                    provider,
+                    subject,
+                    params,
+                } => (
+                    format!("{}:{}", provider, subject),
+                    WatchKind::Custom {
+                        provider: provider.clone(),
+                        subject: subject.clone(),
+                        params: params.clone().unwrap_or_default(),
+                    },
+                ),
+            };
+
+            let target_id = t.id.clone().unwrap_or(default_id);
+            let schedule = t
+                .schedule(self.poll_interval_seconds)
+                .map_err(|e| anyhow::anyhow!("target {target_id}: {e}"))?;
+
+            let version_policy = t
+                .version_policy(&kind)
+                .map_err(|e| anyhow::anyhow!("target {target_id}: {e}"))?;
+
+            let target = WatchTarget {
+                id: target_id,
+                enabled: t.enabled.unwrap_or(true),
+                labels: t.labels.clone().unwrap_or_default(),
+                kind,
+                schedule,
+                endpoint: t.endpoint(),
+                version_policy,
+            };
+
+            // yanks / npm dist-tags 等: 同一个包再派生 target, 共用其它配置
+            let derived_id = |suffix: &str, default: String| match &t.id {
+                Some(id) => format!("{id}:{suffix}"),
+                None => default,
+            };
+            let derived: Vec<WatchTarget> = match &t.kind {
+                TargetKindCfg::CratesIoLatest {
+                    name,
+                    yanks: Some(true),
+                    ..
+                } => vec![WatchTarget {
+                    id: derived_id("yanked", format!("crates-io:{}:yanked", name)),
+                    kind: WatchKind::CratesIoYanked { name: name.clone() },
+                    version_policy: None,
+                    ..target.clone()
+                }],
+                TargetKindCfg::PyPiLatest {
+                    project,
+                    yanks: Some(true),
+                    ..
+                } => vec![WatchTarget {
+                    id: derived_id("yanked", format!("pypi:{}:yanked", project)),
+                    kind: WatchKind::PyPiYanked {
+                        project: project.clone(),
+                    },
+                    version_policy: None,
+                    ..target.clone()
+                }],
+                TargetKindCfg::NpmLatest {
+                    package,
+                    dist_tags,
+                    deprecations,
+                    unpublished,
+                    modified,
+                } => {
+                    let mut kinds = vec![];
+                    for tag in dist_tags.iter().flatten() {
+                        let tag = tag.trim();
+                        if tag.is_empty() || tag == "latest" {
+                            anyhow::bail!(
+                                "target {}: invalid dist-tag {tag:?} (latest is the target itself)",
+                                target.id
+                            );
+                        }
+                        kinds.push((
+                            tag.to_string(),
+                            WatchKind::NpmDistTag {
+                                package: package.clone(),
+                                tag: tag.to_string(),
+                            },
+                        ));
+                    }
+                    let package = package.clone();
+                    if *deprecations == Some(true) {
+                        kinds.push((
+                            "deprecated".into(),
+                            WatchKind::NpmDeprecated {
+                                package: package.clone(),
+                            },
+                        ));
+                    }
+                    if *unpublished == Some(true) {
+                        kinds.push((
+                            "unpublished".into(),
+                            WatchKind::NpmUnpublished {
+                                package: package.clone(),
+                            },
+                        ));
+                    }
+                    if *modified == Some(true) {
+                        kinds.push((
+                            "modified".into(),
+                            WatchKind::NpmModified {
+                                package: package.clone(),
+                            },
+                        ));
+                    }
+                    // version_policy 只属于 latest 本身
+                    kinds
+                        .into_iter()
+                        .map(|(suffix, kind)| WatchTarget {
+                            id: derived_id(&suffix, format!("npm:{}:{}", package, suffix)),
+                            kind,
+                            version_policy: None,
+                            ..target.clone()
+                        })
+                        .collect()
+                }
+                _ => vec![],
+            };
+            let advisories = match t.advisories {
+                Some(true) => {
+                    let Some((ecosystem, package)) = target.kind.osv_package() else {
+                        anyhow::bail!(
+                            "target {}: advisories are not supported for {} targets",
+                            target.id,
+                            target.kind.key()
+                        );
+                    };
+                    Some(WatchTarget {
+                        id: derived_id("advisories", format!("osv:{}:{}", ecosystem, package)),
+                        kind: WatchKind::Advisories {
+                            ecosystem: ecosystem.to_string(),
+                            package,
+                        },
+                        // 包仓库的凭据对 OSV 没意义
+                        endpoint: None,
+                        version_policy: None,
+                        ..target.clone()
                     })
                 }
+                _ => None,
+            };
+            out.push(target);
+            out.extend(derived);
+            out.extend(advisories);
+        }
+
+        // last value / ETag / schedule 状态都按 id 存, id 必须唯一
+        let mut ids = HashSet::new();
+        for t in &out {
+            if !ids.insert(t.id.as_str()) {
+                anyhow::bail!("duplicate target id: {} (set a distinct `id`)", t.id);
             }
         }
         Ok(out)
     }
 }
 
+fn name_filter(
+    pattern: &Option<String>,
+    regex: &Option<String>,
+) -> anyhow::Result<Option<NameFilter>> {
+    let filter = match (pattern, regex) {
+        (Some(_), Some(_)) => anyhow::bail!("set either pattern or regex, not both"),
+        (Some(p), None) => NameFilter::Glob { pattern: p.clone() },
+        (None, Some(r)) => NameFilter::Regex { pattern: r.clone() },
+        (None, None) => return Ok(None),
+    };
+    filter.validate()?;
+    Ok(Some(filter))
+}
+
+fn parse_forge(forge: &Option<String>) -> anyhow::Result<Forge> {
+    Ok(match forge {
+        Some(f) => Forge::parse(f)?,
+        None => Forge::GitHub,
+    })
+}
+
+impl TargetCfg {
+    fn endpoint(&self) -> Option<Endpoint> {
+        if self.base_url.is_none() && self.token.is_none() && self.username.is_none() {
+            return None;
+        }
+        Some(Endpoint {
+            base_url: self.base_url.clone(),
+            token: self.token.clone(),
+            username: self.username.clone(),
+        })
+    }
+
+    fn version_policy(&self, kind: &WatchKind) -> anyhow::Result<Option<VersionPolicy>> {
+        let Some(cfg) = &self.version_policy else {
+            return Ok(None);
+        };
+        if !kind.is_versioned() {
+            anyhow::bail!("version_policy is not supported for {} targets", kind.key());
+        }
+        if let Some(range) = &cfg.range {
+            VersionRange::parse(range)?;
+        }
+        let notify_on = match &cfg.notify_on {
+            Some(b) => Some(VersionBump::parse(b).ok_or_else(|| {
+                anyhow::anyhow!("invalid notify_on: {b} (major/minor/patch/prerelease)")
+            })?),
+            None => None,
+        };
+        Ok(Some(VersionPolicy {
+            ignore_prereleases: cfg.ignore_prereleases.unwrap_or(false),
+            range: cfg.range.clone(),
+            notify_on,
+        }))
+    }
+
+    fn schedule(&self, default_interval_seconds: u64) -> anyhow::Result<Option<Schedule>> {
+        let schedule = match (&self.interval, &self.cron) {
+            (Some(_), Some(_)) => anyhow::bail!("set either interval or cron, not both"),
+            (Some(i), None) => Some(Schedule::interval(i.as_seconds()?)),
+            (None, Some(c)) => Some(Schedule::cron(c)?),
+            (None, None) => None,
+        };
+        let jitter = match &self.jitter {
+            Some(j) => j.as_seconds()?,
+            None => 0,
+        };
+
+        Ok(match schedule {
+            Some(s) => Some(s.with_jitter(jitter)),
+            // 只配置了 jitter: 沿用全局间隔
+            None if jitter > 0 => {
+                Some(Schedule::interval(default_interval_seconds).with_jitter(jitter))
+            }
+            None => None,
+        })
+    }
+}
+
+/// "30s" / "5m" / "2h" / "1d" -> seconds
+fn parse_duration_seconds(s: &str) -> Option<u64> {
+    let s = s.trim();
+    if let Ok(n) = s.parse::<u64>() {
+        return Some(n);
+    }
+    // 单位按字符取, 不按字节切 ("5分" 之类的多字节结尾直接不认)
+    let (i, unit) = s.char_indices().last()?;
+    let n: u64 = s[..i].parse().ok()?;
+    let factor = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' => 60 * 60 * 24,
+        _ => return None,
+    };
+    n.checked_mul(factor)
+}
+
 /// very small ${VAR} expansion to keep config simple
 fn expand_env(s: &str) -> String {
     let mut out = s.to_string();
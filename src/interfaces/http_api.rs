@@ -17,7 +17,8 @@ use tokio_stream::StreamExt;
 use tokio_stream::wrappers::BroadcastStream;
 
 use crate::{
-    application::{EventStore, TargetRepository},
+    application::{EventStore, RateLimitReporter, TargetRepository},
+    domain::VersionBump,
     infrastructure::event_bus::EventBus,
 };
 
@@ -28,6 +29,7 @@ pub struct ApiState {
     pub api_token: Option<String>,
     pub event_bus: Option<EventBus>,
     pub sse_cfg: SseRuntimeCfg,
+    pub rate_limits: Vec<Arc<dyn RateLimitReporter>>,
 }
 
 #[derive(Clone)]
@@ -43,6 +45,7 @@ pub fn build_router(state: ApiState) -> Router {
         .route("/targets", get(list_targets))
         .route("/events", get(list_events))
         .route("/events/stream", get(stream_events))
+        .route("/rate_limits", get(list_rate_limits))
         .with_state(state)
 }
 
@@ -60,6 +63,18 @@ async fn list_targets(State(state): State<ApiState>, headers: HeaderMap) -> impl
     }
 }
 
+async fn list_rate_limits(State(state): State<ApiState>, headers: HeaderMap) -> impl IntoResponse {
+    if let Err((code, msg)) = check_auth(&headers, &state.api_token) {
+        return (code, msg).into_response();
+    }
+    let items: Vec<_> = state
+        .rate_limits
+        .iter()
+        .flat_map(|r| r.rate_limits())
+        .collect();
+    Json(items).into_response()
+}
+
 #[derive(Deserialize)]
 struct EventsQuery {
     limit: Option<u32>,
@@ -67,6 +82,7 @@ struct EventsQuery {
     label: Option<String>,
     r#type: Option<String>,
     subject: Option<String>,
+    bump: Option<String>, // "major" | "minor" | "patch" | "prerelease"
 }
 
 async fn list_events(
@@ -99,7 +115,21 @@ async fn list_events(
             None => {
                 return (
                     StatusCode::BAD_REQUEST,
-                    "invalid type (release/branch/npm/waweb)".to_string(),
+                    "invalid type (release/branch/tag/workflow/search/gitlab_release/gitlab_branch/gitea_release/gitea_branch/npm/npm_tag/npm_deprecated/npm_unpublished/npm_modified/crate/crate_yank/pypi/pypi_yank/maven/go/oci_digest/oci_tags/waweb/http_json/http_page/feed/advisory/custom)".to_string(),
+                )
+                    .into_response();
+            }
+        },
+        None => None,
+    };
+
+    let bump = match q.bump.as_deref() {
+        Some(b) => match VersionBump::parse(b) {
+            Some(b) => Some(b),
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    "invalid bump (major/minor/patch/prerelease)".to_string(),
                 )
                     .into_response();
             }
@@ -113,6 +143,7 @@ async fn list_events(
         label: q.label.clone(),
         event_type,
         subject: q.subject.clone(),
+        bump,
     };
 
     match state.store.list_events_filtered(query).await {
@@ -126,8 +157,9 @@ struct StreamQuery {
     replay: Option<u32>,   // e.g. 20
     since: Option<String>, // e.g. "24h" | "7d" | "3600s"
     label: Option<String>,
-    r#type: Option<String>, // e.g. "release" | "branch" | "npm" | "waweb"
+    r#type: Option<String>, // e.g. "release" | "branch" | "tag" | "workflow" | "search" | "gitlab_release" | "gitlab_branch" | "gitea_release" | "gitea_branch" | "npm" | "npm_tag" | "npm_deprecated" | "npm_unpublished" | "npm_modified" | "crate" | "crate_yank" | "pypi" | "pypi_yank" | "maven" | "go" | "oci_digest" | "oci_tags" | "waweb" | "http_json" | "http_page" | "feed" | "advisory" | "custom"
     subject: Option<String>,
+    bump: Option<String>, // "major" | "minor" | "patch" | "prerelease"
 }
 
 async fn stream_events(
@@ -176,7 +208,20 @@ async fn stream_events(
             None => {
                 return (
                     StatusCode::BAD_REQUEST,
-                    "invalid type (release/branch/npm/waweb)".to_string(),
+                    "invalid type (release/branch/tag/workflow/search/gitlab_release/gitlab_branch/gitea_release/gitea_branch/npm/npm_tag/npm_deprecated/npm_unpublished/npm_modified/crate/crate_yank/pypi/pypi_yank/maven/go/oci_digest/oci_tags/waweb/http_json/http_page/feed/advisory/custom)".to_string(),
+                )
+                    .into_response();
+            }
+        },
+        None => None,
+    };
+    let bump = match q.bump.as_deref() {
+        Some(b) => match VersionBump::parse(b) {
+            Some(b) => Some(b),
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    "invalid bump (major/minor/patch/prerelease)".to_string(),
                 )
                     .into_response();
             }
@@ -206,6 +251,7 @@ async fn stream_events(
         label: q.label.clone(),
         event_type,
         subject: q.subject.clone(),
+        bump,
     };
 
     let history = match state.store.list_event_records_cursor(history_query).await {
@@ -228,29 +274,33 @@ async fn stream_events(
         };
 
         // label filter
-        if let Some(label) = &label_filter {
-            if !record.labels.iter().any(|l| l == label) {
-                return None;
-            }
+        if let Some(label) = &label_filter
+            && !record.labels.iter().any(|l| l == label)
+        {
+            return None;
         }
         // subject filter
-        if let Some(subj) = &subject_filter {
-            if record.event.subject != *subj {
-                return None;
-            }
+        if let Some(subj) = &subject_filter
+            && record.event.subject != *subj
+        {
+            return None;
+        }
+        // bump filter
+        if let Some(b) = bump
+            && record
+                .event
+                .meta
+                .get(VersionBump::META_KEY)
+                .map(String::as_str)
+                != Some(b.as_str())
+        {
+            return None;
         }
         // type filter
-        if let Some(t) = &type_filter {
-            let ok = match (t.as_str(), &record.event.event_type) {
-                ("release", crate::domain::EventType::GitHubRelease) => true,
-                ("branch", crate::domain::EventType::GitHubBranch) => true,
-                ("npm", crate::domain::EventType::NpmLatest) => true,
-                ("waweb", crate::domain::EventType::WhatsAppWebVersion) => true,
-                _ => false,
-            };
-            if !ok {
-                return None;
-            }
+        if let Some(t) = &type_filter
+            && parse_type(t).as_ref() != Some(&record.event.event_type)
+        {
+            return None;
         }
 
         let data = serde_json::to_string(&record).ok()?;
@@ -301,7 +351,7 @@ async fn stream_events(
         // }
     };
 
-    return Sse::new(out_stream).into_response();
+    Sse::new(out_stream).into_response()
 }
 
 fn check_auth(headers: &HeaderMap, token: &Option<String>) -> Result<(), (StatusCode, String)> {
@@ -350,8 +400,32 @@ fn parse_type(t: &str) -> Option<crate::domain::EventType> {
     match t {
         "release" => Some(crate::domain::EventType::GitHubRelease),
         "branch" => Some(crate::domain::EventType::GitHubBranch),
+        "tag" => Some(crate::domain::EventType::GitHubTag),
+        "workflow" => Some(crate::domain::EventType::GitHubWorkflow),
+        "search" => Some(crate::domain::EventType::GitHubSearchItem),
+        "gitlab_release" => Some(crate::domain::EventType::GitLabRelease),
+        "gitlab_branch" => Some(crate::domain::EventType::GitLabBranch),
+        "gitea_release" => Some(crate::domain::EventType::GiteaRelease),
+        "gitea_branch" => Some(crate::domain::EventType::GiteaBranch),
+        "crate" => Some(crate::domain::EventType::CratesIoLatest),
+        "crate_yank" => Some(crate::domain::EventType::CratesIoYanked),
+        "pypi" => Some(crate::domain::EventType::PyPiLatest),
+        "pypi_yank" => Some(crate::domain::EventType::PyPiYanked),
+        "maven" => Some(crate::domain::EventType::MavenLatest),
+        "go" => Some(crate::domain::EventType::GoModule),
+        "oci_digest" => Some(crate::domain::EventType::OciDigest),
+        "oci_tags" => Some(crate::domain::EventType::OciTags),
         "npm" => Some(crate::domain::EventType::NpmLatest),
+        "npm_tag" => Some(crate::domain::EventType::NpmDistTag),
+        "npm_deprecated" => Some(crate::domain::EventType::NpmDeprecated),
+        "npm_unpublished" => Some(crate::domain::EventType::NpmUnpublished),
+        "npm_modified" => Some(crate::domain::EventType::NpmModified),
         "waweb" => Some(crate::domain::EventType::WhatsAppWebVersion),
+        "http_json" => Some(crate::domain::EventType::HttpJson),
+        "http_page" => Some(crate::domain::EventType::HttpPage),
+        "feed" => Some(crate::domain::EventType::FeedEntry),
+        "advisory" => Some(crate::domain::EventType::Advisory),
+        "custom" => Some(crate::domain::EventType::Custom),
         _ => None,
     }
 }
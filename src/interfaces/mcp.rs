@@ -4,7 +4,7 @@ use serde_json::{Value, json};
 use tokio::io::{self, AsyncBufReadExt, AsyncWriteExt};
 
 use crate::application::{EventQuery, EventStore, TargetRepository};
-use crate::domain::EventType;
+use crate::domain::{EventType, VersionBump};
 
 /// Minimal MCP-like server over stdio:
 /// - tools/list
@@ -97,8 +97,9 @@ impl McpServer {
                                         "token": { "type": "string", "description": "API token (required if API_TOKEN is set)"},
                                         "since": { "type": "string", "description": "The window: e.g. 24h, 7d, 3600s" },
                                         "label": { "type": "string", "description": "Filter by target label (e.g. whatsapp)" },
-                                        "type": { "type": "string", "enum": ["release", "branch", "npm", "waweb"], "description": "Event type filter" },
+                                        "type": { "type": "string", "enum": ["release", "branch", "tag", "workflow", "search", "gitlab_release", "gitlab_branch", "gitea_release", "gitea_branch", "npm", "npm_tag", "npm_deprecated", "npm_unpublished", "npm_modified", "crate", "crate_yank", "pypi", "pypi_yank", "maven", "go", "oci_digest", "oci_tags", "waweb", "http_json", "http_page", "feed", "advisory", "custom"], "description": "Event type filter" },
                                         "subject": { "type": "string", "description": "Exact subject filter (repo 'owner/repo' or package name)" },
+                                        "bump": { "type": "string", "enum": ["major", "minor", "patch", "prerelease"], "description": "Version bump filter (versioned targets only)" },
                                         "limit": { "type": "integer", "minimum": 1, "maximum": 500 }
                                       },
                                       "required": []
@@ -176,6 +177,10 @@ impl McpServer {
                                 .get("type")
                                 .and_then(|v| v.as_str())
                                 .and_then(parse_type);
+                            let bump = args
+                                .get("bump")
+                                .and_then(|v| v.as_str())
+                                .and_then(VersionBump::parse);
 
                             let q = EventQuery {
                                 since_epoch,
@@ -183,6 +188,7 @@ impl McpServer {
                                 label,
                                 event_type,
                                 subject,
+                                bump,
                             };
 
                             match self.store.list_events_filtered(q).await {
@@ -284,8 +290,32 @@ fn parse_type(t: &str) -> Option<EventType> {
     match t {
         "release" => Some(EventType::GitHubRelease),
         "branch" => Some(EventType::GitHubBranch),
+        "tag" => Some(EventType::GitHubTag),
+        "workflow" => Some(EventType::GitHubWorkflow),
+        "search" => Some(EventType::GitHubSearchItem),
+        "gitlab_release" => Some(EventType::GitLabRelease),
+        "gitlab_branch" => Some(EventType::GitLabBranch),
+        "gitea_release" => Some(EventType::GiteaRelease),
+        "gitea_branch" => Some(EventType::GiteaBranch),
+        "crate" => Some(EventType::CratesIoLatest),
+        "crate_yank" => Some(EventType::CratesIoYanked),
+        "pypi" => Some(EventType::PyPiLatest),
+        "pypi_yank" => Some(EventType::PyPiYanked),
+        "maven" => Some(EventType::MavenLatest),
+        "go" => Some(EventType::GoModule),
+        "oci_digest" => Some(EventType::OciDigest),
+        "oci_tags" => Some(EventType::OciTags),
         "npm" => Some(EventType::NpmLatest),
+        "npm_tag" => Some(EventType::NpmDistTag),
+        "npm_deprecated" => Some(EventType::NpmDeprecated),
+        "npm_unpublished" => Some(EventType::NpmUnpublished),
+        "npm_modified" => Some(EventType::NpmModified),
         "waweb" => Some(EventType::WhatsAppWebVersion),
+        "http_json" => Some(EventType::HttpJson),
+        "http_page" => Some(EventType::HttpPage),
+        "feed" => Some(EventType::FeedEntry),
+        "advisory" => Some(EventType::Advisory),
+        "custom" => Some(EventType::Custom),
         _ => None,
     }
 }
@@ -2,16 +2,45 @@ use clap::Parser;
 use std::sync::Arc;
 use tracing_subscriber::EnvFilter;
 
-use repopulse::application::usecases::{HandleEventUseCase, RunOnceUseCase};
+use repopulse::application::usecases::{ConcurrencyLimits, HandleEventUseCase, RunOnceUseCase};
+use repopulse::application::{HttpCacheStore, ProviderStateStore, Scheduler, TargetRepository};
+use repopulse::domain::{WatchKind, WatchTarget};
 use repopulse::infrastructure::{
-    broadcast_publisher, composite_provider::CompositeWatchProvider,
-    console_notifier::ConsoleNotifier, event_bus, feishu_notifier::FeishuNotifier,
-    github_branch_provider::GitHubBranchProvider, github_release_provider::GitHubReleaseProvider,
-    memory_store::InMemoryTargetRepository, multi_notifier::MultiNotifier,
-    npm_latest_provider::NpmLatestProvider, sqlite_store::SqliteEventStore,
+    api_endpoint::ApiEndpoint,
+    broadcast_publisher,
+    console_notifier::ConsoleNotifier,
+    crates_io_provider::{self, CratesIoLatestProvider, CratesIoYankedProvider},
+    event_bus,
+    feed_provider::FeedProvider,
+    feishu_notifier::FeishuNotifier,
+    gitea_provider::{self, GiteaBranchProvider, GiteaReleaseProvider},
+    github_branch_provider::GitHubBranchProvider,
+    github_client::GitHubClient,
+    github_release_provider::GitHubReleaseProvider,
+    github_search_provider::GitHubSearchProvider,
+    github_tag_provider::GitHubTagProvider,
+    github_workflow_provider::GitHubWorkflowProvider,
+    gitlab_provider::{self, GitLabBranchProvider, GitLabReleaseProvider},
+    go_module_provider::{self, GoModuleProvider},
+    http_json_provider::HttpJsonProvider,
+    http_page_provider::HttpPageProvider,
+    manifest_discovery::{ManifestSource, ManifestTargetRepository},
+    maven_latest_provider::{self, MavenLatestProvider},
+    memory_store::InMemoryTargetRepository,
+    multi_notifier::MultiNotifier,
+    npm_latest_provider::{
+        self, NpmDeprecatedProvider, NpmDistTagProvider, NpmLatestProvider, NpmModifiedProvider,
+        NpmRegistryClient, NpmUnpublishedProvider,
+    },
+    oci_registry_provider::{OciDigestProvider, OciRegistryClient, OciTagsProvider},
+    osv_provider::{self, OsvAdvisoryProvider},
+    provider_registry::ProviderRegistry,
+    pypi_provider::{self, PyPiLatestProvider, PyPiYankedProvider},
+    sqlite_store::SqliteEventStore,
+    whatsapp_web_version_provider::WhatsAppWebVersionProvider,
 };
 use repopulse::interfaces::{
-    config::Config,
+    config::{Config, EndpointCfg, ProvidersCfg},
     http_api::{ApiState, build_router},
 };
 
@@ -75,6 +104,22 @@ async fn main() {
         }
     };
 
+    let concurrency = {
+        let c = cfg.concurrency.clone();
+        let defaults = ConcurrencyLimits::default();
+        ConcurrencyLimits {
+            global: c.as_ref().and_then(|x| x.global).unwrap_or(defaults.global),
+            per_source: c
+                .as_ref()
+                .and_then(|x| x.per_source.clone())
+                .unwrap_or(defaults.per_source),
+            check_timeout_seconds: c
+                .as_ref()
+                .and_then(|x| x.check_timeout_seconds)
+                .unwrap_or(defaults.check_timeout_seconds),
+        }
+    };
+
     let targets = match cfg.to_watch_targets() {
         Ok(t) => t,
         Err(e) => {
@@ -86,16 +131,52 @@ async fn main() {
     let poll_interval = cfg.poll_interval_seconds;
 
     // 2) build infra
-    let target_repo = InMemoryTargetRepository::new(targets);
-    let token = std::env::var("GITHUB_TOKEN").ok();
-    let provider = CompositeWatchProvider::new(
-        Box::new(GitHubReleaseProvider::new(token.clone())),
-        Box::new(GitHubBranchProvider::new(token.clone())),
-        Box::new(NpmLatestProvider::new()),
-    );
     let db_url =
         std::env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite:/data/state.db".to_string());
-    let store = SqliteEventStore::new(&db_url).await.expect("sqlite store");
+    let store = Arc::new(SqliteEventStore::new(&db_url).await.expect("sqlite store"));
+    let providers_cfg = cfg.providers.clone().unwrap_or_default();
+    let github = {
+        let c = providers_cfg.github.clone().unwrap_or_default();
+        let token = c.token.or_else(|| std::env::var("GITHUB_TOKEN").ok());
+        let mut client = GitHubClient::new(token).with_cache(store.clone());
+        if let Some(base_url) = c.base_url {
+            client = client.with_base_url(base_url);
+        }
+        client
+    };
+    let sources: Vec<ManifestSource> = cfg
+        .discovery
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|d| {
+            if !std::path::Path::new(&d.path).exists() {
+                tracing::warn!(path = %d.path, "discovery path does not exist (yet)");
+            }
+            ManifestSource::new(d.path)
+                .with_labels(d.labels.unwrap_or_default())
+                .with_dev_dependencies(d.dev_dependencies.unwrap_or(false))
+        })
+        .collect();
+    // 发现的 target 随 manifest 变化, 对应的 provider 先都注册上
+    let discovered_kinds = if sources.is_empty() {
+        &[][..]
+    } else {
+        ManifestTargetRepository::KINDS
+    };
+    let provider = build_providers(
+        &targets,
+        discovered_kinds,
+        &github,
+        store.clone(),
+        store.clone(),
+        &providers_cfg,
+    );
+    let target_repo: Arc<dyn TargetRepository> = if sources.is_empty() {
+        Arc::new(InMemoryTargetRepository::new(targets))
+    } else {
+        Arc::new(ManifestTargetRepository::new(targets, sources))
+    };
 
     let event_bus = event_bus::EventBus::new(1024);
     let publisher = broadcast_publisher::BroadcastPublisher::new(event_bus.clone());
@@ -117,9 +198,6 @@ async fn main() {
     let notifier = MultiNotifier::new(notifiers);
     let cooldown = cfg.cooldown_seconds.unwrap_or(0);
 
-    let target_repo = Arc::new(target_repo);
-    let store = Arc::new(store);
-
     // 3) usecases
     let handle_event = HandleEventUseCase {
         store: store.as_ref(),
@@ -131,6 +209,7 @@ async fn main() {
         targets: target_repo.as_ref(),
         provider: &provider,
         handle_event,
+        concurrency,
     };
 
     if let Some(addr) = args.http_addr.clone() {
@@ -141,6 +220,7 @@ async fn main() {
             api_token,
             event_bus: Some(event_bus.clone()),
             sse_cfg,
+            rate_limits: vec![Arc::new(github.clone())],
         };
         let app = build_router(state);
 
@@ -186,10 +266,211 @@ async fn main() {
     }
 
     tracing::info!(poll_interval = poll_interval, "polling started");
+    let mut scheduler = Scheduler::new(poll_interval);
     loop {
-        if let Err(e) = run_once.execute().await {
-            tracing::error!("RunOnce failed: {e}");
+        let targets = match target_repo.list_enabled_targets().await {
+            Ok(t) => t,
+            Err(e) => {
+                tracing::error!("list targets failed: {e}");
+                vec![]
+            }
+        };
+
+        let now = epoch_seconds();
+        let due = scheduler.due(&targets, now);
+        if !due.is_empty() {
+            if let Err(e) = run_once.execute_targets(&due).await {
+                tracing::error!("RunOnce failed: {e}");
+            }
+            scheduler.mark_checked(&due, now);
+        }
+
+        // 睡到下一个 target 到期 (至少 1s, 最多一个全局间隔)
+        let now = epoch_seconds();
+        let wait = (scheduler.next_wake(&targets, now) - now).clamp(1, poll_interval.max(1) as i64);
+        tokio::time::sleep(std::time::Duration::from_secs(wait as u64)).await;
+    }
+}
+
+fn epoch_seconds() -> i64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// Provider-wide API root from `providers.<name>`, falling back to `default_url`.
+fn endpoint_from_cfg(cfg: &Option<EndpointCfg>, default_url: &str) -> ApiEndpoint {
+    let c = cfg.clone().unwrap_or_default();
+    ApiEndpoint::new(c.base_url.unwrap_or_else(|| default_url.into()))
+        .with_token(c.token)
+        .with_username(c.username)
+}
+
+/// Like `endpoint_from_cfg`, with the token falling back to `$<token_env>` (as for GitHub).
+fn forge_endpoint(cfg: &Option<EndpointCfg>, default_url: &str, token_env: &str) -> ApiEndpoint {
+    let api = endpoint_from_cfg(cfg, default_url);
+    match api.token {
+        Some(_) => api,
+        None => api.with_token(std::env::var(token_env).ok()),
+    }
+}
+
+/// Register the built-in providers needed by the configured targets (and by
+/// `extra_kinds`, e.g. the kinds of discovered targets).
+fn build_providers(
+    targets: &[WatchTarget],
+    extra_kinds: &[&str],
+    github: &GitHubClient,
+    store: Arc<dyn HttpCacheStore>,
+    state: Arc<dyn ProviderStateStore>,
+    providers_cfg: &ProvidersCfg,
+) -> ProviderRegistry {
+    let mut registry = ProviderRegistry::new();
+    // 两个 OCI provider 共用 token 缓存
+    let oci = OciRegistryClient::new();
+    // npm 的几个 provider 共用 packument 拉取
+    let npm = NpmRegistryClient::new(endpoint_from_cfg(
+        &providers_cfg.npm,
+        npm_latest_provider::DEFAULT_NPM_REGISTRY_URL,
+    ))
+    .with_cache(store.clone());
+
+    let kinds = targets
+        .iter()
+        .map(|t| t.kind.key())
+        .chain(extra_kinds.iter().copied());
+    for kind in kinds {
+        if registry.contains(kind) {
+            continue;
+        }
+        match kind {
+            WatchKind::GITHUB_RELEASE => {
+                registry.register_provider(GitHubReleaseProvider::new(github.clone()));
+            }
+            WatchKind::GITHUB_BRANCH => {
+                registry.register_provider(GitHubBranchProvider::new(github.clone()));
+            }
+            WatchKind::GITHUB_TAG => {
+                registry.register_provider(GitHubTagProvider::new(github.clone()));
+            }
+            WatchKind::GITHUB_SEARCH => {
+                registry
+                    .register_provider(GitHubSearchProvider::new(github.clone(), state.clone()));
+            }
+            WatchKind::GITHUB_WORKFLOW => {
+                registry.register_provider(GitHubWorkflowProvider::new(github.clone()));
+            }
+            WatchKind::GITLAB_RELEASE => {
+                let api = forge_endpoint(
+                    &providers_cfg.gitlab,
+                    gitlab_provider::DEFAULT_GITLAB_API_URL,
+                    "GITLAB_TOKEN",
+                );
+                registry.register_provider(GitLabReleaseProvider::with_endpoint(api));
+            }
+            WatchKind::GITLAB_BRANCH => {
+                let api = forge_endpoint(
+                    &providers_cfg.gitlab,
+                    gitlab_provider::DEFAULT_GITLAB_API_URL,
+                    "GITLAB_TOKEN",
+                );
+                registry.register_provider(GitLabBranchProvider::with_endpoint(api));
+            }
+            WatchKind::GITEA_RELEASE => {
+                let api = forge_endpoint(
+                    &providers_cfg.gitea,
+                    gitea_provider::DEFAULT_GITEA_API_URL,
+                    "GITEA_TOKEN",
+                );
+                registry.register_provider(GiteaReleaseProvider::with_endpoint(api));
+            }
+            WatchKind::GITEA_BRANCH => {
+                let api = forge_endpoint(
+                    &providers_cfg.gitea,
+                    gitea_provider::DEFAULT_GITEA_API_URL,
+                    "GITEA_TOKEN",
+                );
+                registry.register_provider(GiteaBranchProvider::with_endpoint(api));
+            }
+            WatchKind::NPM_LATEST => {
+                registry.register_provider(NpmLatestProvider::with_client(npm.clone()));
+            }
+            WatchKind::NPM_DIST_TAG => {
+                registry.register_provider(NpmDistTagProvider::with_client(npm.clone()));
+            }
+            WatchKind::NPM_DEPRECATED => {
+                registry.register_provider(NpmDeprecatedProvider::with_client(npm.clone()));
+            }
+            WatchKind::NPM_UNPUBLISHED => {
+                registry.register_provider(NpmUnpublishedProvider::with_client(npm.clone()));
+            }
+            WatchKind::NPM_MODIFIED => {
+                registry.register_provider(NpmModifiedProvider::with_client(npm.clone()));
+            }
+            WatchKind::CRATES_IO_LATEST => {
+                let api = endpoint_from_cfg(
+                    &providers_cfg.crates_io,
+                    crates_io_provider::DEFAULT_CRATES_IO_URL,
+                );
+                registry.register_provider(CratesIoLatestProvider::with_registry(api));
+            }
+            WatchKind::CRATES_IO_YANKED => {
+                let api = endpoint_from_cfg(
+                    &providers_cfg.crates_io,
+                    crates_io_provider::DEFAULT_CRATES_IO_URL,
+                );
+                registry.register_provider(CratesIoYankedProvider::with_registry(api));
+            }
+            WatchKind::PYPI_LATEST => {
+                let api = endpoint_from_cfg(&providers_cfg.pypi, pypi_provider::DEFAULT_PYPI_URL);
+                registry.register_provider(PyPiLatestProvider::with_index(api));
+            }
+            WatchKind::PYPI_YANKED => {
+                let api = endpoint_from_cfg(&providers_cfg.pypi, pypi_provider::DEFAULT_PYPI_URL);
+                registry.register_provider(PyPiYankedProvider::with_index(api));
+            }
+            WatchKind::MAVEN_LATEST => {
+                let repo = endpoint_from_cfg(
+                    &providers_cfg.maven,
+                    maven_latest_provider::DEFAULT_MAVEN_REPOSITORY_URL,
+                );
+                registry.register_provider(MavenLatestProvider::with_repository(repo));
+            }
+            WatchKind::GO_MODULE => {
+                let proxy = endpoint_from_cfg(
+                    &providers_cfg.goproxy,
+                    go_module_provider::DEFAULT_GOPROXY_URL,
+                );
+                registry.register_provider(GoModuleProvider::with_proxy(proxy));
+            }
+            WatchKind::OCI_DIGEST => {
+                registry.register_provider(OciDigestProvider::new(oci.clone()));
+            }
+            WatchKind::OCI_TAGS => {
+                registry.register_provider(OciTagsProvider::new(oci.clone(), state.clone()));
+            }
+            WatchKind::WHATSAPP_WEB_VERSION => {
+                registry.register_provider(WhatsAppWebVersionProvider::new());
+            }
+            WatchKind::HTTP_JSON => {
+                registry.register_provider(HttpJsonProvider::new());
+            }
+            WatchKind::HTTP_PAGE => {
+                registry.register_provider(HttpPageProvider::new(store.clone(), state.clone()));
+            }
+            WatchKind::FEED => {
+                registry.register_provider(FeedProvider::new().with_cache(store.clone()));
+            }
+            WatchKind::ADVISORIES => {
+                let api = endpoint_from_cfg(&providers_cfg.osv, osv_provider::DEFAULT_OSV_API_URL);
+                registry.register_provider(OsvAdvisoryProvider::with_endpoint(api));
+            }
+            _ => {
+                tracing::warn!(kind, "no built-in provider for target kind");
+            }
         }
-        tokio::time::sleep(std::time::Duration::from_secs(poll_interval)).await;
     }
+    registry
 }
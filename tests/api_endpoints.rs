@@ -0,0 +1,105 @@
+use axum::{Json, Router, http::HeaderMap, http::StatusCode, response::IntoResponse, routing::get};
+use serde_json::json;
+
+use repopulse::application::WatchProvider;
+use repopulse::domain::{Endpoint, RepoId, WatchKind, WatchTarget};
+use repopulse::infrastructure::api_endpoint::ApiEndpoint;
+use repopulse::infrastructure::github_client::GitHubClient;
+use repopulse::infrastructure::github_release_provider::GitHubReleaseProvider;
+use repopulse::infrastructure::npm_latest_provider::NpmLatestProvider;
+
+fn authorization(headers: &HeaderMap) -> String {
+    headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string()
+}
+
+/// 同时模拟 GitHub Enterprise (/api/v3) 和私有 npm registry
+async fn spawn_stand_in() -> String {
+    let app = Router::new()
+        .route(
+            "/api/v3/repos/corp/tool/releases/latest",
+            get(|headers: HeaderMap| async move {
+                if authorization(&headers) != "Bearer ghe-token" {
+                    return StatusCode::UNAUTHORIZED.into_response();
+                }
+                Json(json!({
+                    "tag_name": "v2.0.0",
+                    "html_url": "https://ghe.example.com/corp/tool/releases/tag/v2.0.0",
+                }))
+                .into_response()
+            }),
+        )
+        .route(
+            "/npm/{pkg}",
+            get(|headers: HeaderMap| async move {
+                // Basic ci:secret
+                if authorization(&headers) != "Basic Y2k6c2VjcmV0" {
+                    return StatusCode::UNAUTHORIZED.into_response();
+                }
+                Json(json!({ "dist-tags": { "latest": "4.5.6" } })).into_response()
+            }),
+        );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn target_endpoint_points_github_provider_at_enterprise_server() {
+    let base = spawn_stand_in().await;
+    // 默认指向 github.com, 由 target 覆盖
+    let provider = GitHubReleaseProvider::new(GitHubClient::new(Some("public-token".into())));
+    let target = WatchTarget {
+        id: "ghe:corp/tool:release".to_string(),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::GitHubRelease {
+            repo: RepoId::parse("corp/tool").unwrap(),
+        },
+        schedule: None,
+        endpoint: Some(Endpoint {
+            base_url: Some(format!("{base}/api/v3")),
+            token: Some("ghe-token".to_string()),
+            username: None,
+        }),
+        version_policy: None,
+    };
+
+    let event = provider.check(&target).await.unwrap().unwrap();
+    assert_eq!(event.new_value, "v2.0.0");
+
+    // token 不能出现在 /targets 的输出里
+    let json = serde_json::to_string(&target).unwrap();
+    assert!(!json.contains("ghe-token"));
+}
+
+#[tokio::test]
+async fn npm_provider_uses_private_registry_with_basic_auth() {
+    let base = spawn_stand_in().await;
+    let provider = NpmLatestProvider::with_registry(
+        ApiEndpoint::new(format!("{base}/npm"))
+            .with_token(Some("secret".into()))
+            .with_username(Some("ci".into())),
+    );
+    let target = WatchTarget {
+        id: "npm:@corp/lib:latest".to_string(),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::NpmLatest {
+            package: "@corp/lib".to_string(),
+        },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    };
+
+    let event = provider.check(&target).await.unwrap().unwrap();
+    assert_eq!(event.new_value, "4.5.6");
+    assert_eq!(event.url, Some(format!("{base}/npm/@corp/lib")));
+}
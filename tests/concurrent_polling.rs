@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use repopulse::application::usecases::{ConcurrencyLimits, HandleEventUseCase, RunOnceUseCase};
+use repopulse::application::{AppResult, EventStore, Notifier, WatchProvider};
+use repopulse::domain::{Event, EventType, WatchKind, WatchTarget};
+use repopulse::infrastructure::memory_store::{InMemoryEventStore, InMemoryTargetRepository};
+
+/// 记录同时在跑的 check 数; 包名为 "hang" 的 target 永远不返回
+#[derive(Default)]
+struct SlowProvider {
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+#[async_trait]
+impl WatchProvider for SlowProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let subject = target.kind.subject();
+        if subject == "hang" {
+            std::future::pending::<()>().await;
+        }
+
+        let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+        // 越靠前的 target 越慢, 验证结果仍按 target 顺序处理
+        let delay = 60 - subject.trim_start_matches("pkg").parse::<u64>().unwrap() * 10;
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        Ok(Some(Event {
+            event_id: Event::make_event_id(&EventType::NpmLatest, &subject, "2.0.0"),
+            event_type: EventType::NpmLatest,
+            source: target.kind.source(),
+            subject,
+            old_value: None,
+            new_value: "2.0.0".to_string(),
+            occurred_at: None,
+            detected_at: "0s_since_epoch".to_string(),
+            url: None,
+            meta: Default::default(),
+        }))
+    }
+}
+
+#[derive(Clone, Default)]
+struct RecordingNotifier {
+    subjects: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    async fn notify(&self, event: &Event) -> AppResult<()> {
+        self.subjects.lock().unwrap().push(event.subject.clone());
+        Ok(())
+    }
+}
+
+fn npm_target(package: &str) -> WatchTarget {
+    WatchTarget {
+        id: format!("npm:{package}:latest"),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::NpmLatest {
+            package: package.to_string(),
+        },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+#[tokio::test]
+async fn checks_run_concurrently_within_limits_and_are_handled_in_order() {
+    let mut targets = vec![npm_target("hang")];
+    targets.extend((0..5).map(|i| npm_target(&format!("pkg{i}"))));
+
+    let store = InMemoryEventStore::new();
+    for t in &targets {
+        store.set_last_value(&t.id, "1.0.0").await.unwrap();
+    }
+    let target_repo = InMemoryTargetRepository::new(targets);
+    let provider = SlowProvider::default();
+    let notifier = RecordingNotifier::default();
+
+    let run_once = RunOnceUseCase {
+        targets: &target_repo,
+        provider: &provider,
+        handle_event: HandleEventUseCase {
+            store: &store,
+            notifier: &notifier,
+            publisher: None,
+            cooldown_seconds: 0,
+        },
+        concurrency: ConcurrencyLimits {
+            global: 8,
+            per_source: HashMap::from([("npm".to_string(), 3)]),
+            check_timeout_seconds: 1,
+        },
+    };
+
+    run_once.execute().await.unwrap();
+
+    // "hang" 占着一个 npm permit 直到超时, 其余最多 2 个同时在跑
+    let max = provider.max_in_flight.load(Ordering::SeqCst);
+    assert!((1..=2).contains(&max), "max in flight = {max}");
+    assert_eq!(
+        *notifier.subjects.lock().unwrap(),
+        vec!["pkg0", "pkg1", "pkg2", "pkg3", "pkg4"]
+    );
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use axum::{Router, http::HeaderMap, http::StatusCode, response::IntoResponse, routing::get};
+
+use repopulse::application::HttpCacheStore;
+use repopulse::infrastructure::conditional_get::send_conditional;
+use repopulse::infrastructure::memory_store::InMemoryEventStore;
+
+/// 类 GitHub: 带 ETag, If-None-Match 命中时返回 304
+async fn spawn_stand_in(full_responses: Arc<AtomicUsize>) -> String {
+    let app = Router::new().route(
+        "/repos/o/r/releases/latest",
+        get(move |headers: HeaderMap| {
+            let full_responses = full_responses.clone();
+            async move {
+                if headers.get("if-none-match").and_then(|v| v.to_str().ok()) == Some("\"v1\"") {
+                    return StatusCode::NOT_MODIFIED.into_response();
+                }
+                full_responses.fetch_add(1, Ordering::SeqCst);
+                ([("etag", "\"v1\"")], r#"{"tag_name":"v1.0.0"}"#).into_response()
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}/repos/o/r/releases/latest", addr)
+}
+
+#[tokio::test]
+async fn second_request_is_conditional_and_served_from_cache() {
+    let full_responses = Arc::new(AtomicUsize::new(0));
+    let url = spawn_stand_in(full_responses.clone()).await;
+    let cache = InMemoryEventStore::new();
+    let client = reqwest::Client::new();
+
+    let first = send_conditional(Some(&cache), "github:o/r:release", client.get(&url))
+        .await
+        .unwrap();
+    assert!(!first.not_modified);
+    assert_eq!(first.body, r#"{"tag_name":"v1.0.0"}"#);
+    let entry = cache
+        .get_http_cache("github:o/r:release")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(entry.etag.as_deref(), Some("\"v1\""));
+
+    let second = send_conditional(Some(&cache), "github:o/r:release", client.get(&url))
+        .await
+        .unwrap();
+    assert!(second.not_modified);
+    assert_eq!(second.body, first.body);
+    assert_eq!(full_responses.load(Ordering::SeqCst), 1);
+}
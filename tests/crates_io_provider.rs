@@ -0,0 +1,173 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::{Json, Router, routing::get};
+use serde_json::json;
+
+use repopulse::application::usecases::{ConcurrencyLimits, HandleEventUseCase, RunOnceUseCase};
+use repopulse::application::{AppResult, EventStore, Notifier, WatchProvider};
+use repopulse::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use repopulse::infrastructure::api_endpoint::ApiEndpoint;
+use repopulse::infrastructure::crates_io_provider::{
+    CratesIoLatestProvider, CratesIoYankedProvider, META_UNYANKED, META_YANKED,
+};
+use repopulse::infrastructure::memory_store::{InMemoryEventStore, InMemoryTargetRepository};
+use repopulse::interfaces::config::Config;
+
+/// 1.10.0 被 yank, 2.0.0-beta.1 是 pre-release
+async fn spawn_stand_in() -> String {
+    let app = Router::new().route(
+        "/api/v1/crates/demo",
+        get(|| async {
+            Json(json!({
+                "crate": { "name": "demo", "max_stable_version": "1.9.0" },
+                "versions": [
+                    { "num": "2.0.0-beta.1", "yanked": false, "created_at": "2024-03-01T00:00:00Z" },
+                    { "num": "1.10.0", "yanked": true, "created_at": "2024-02-01T00:00:00Z" },
+                    { "num": "1.9.0", "yanked": false, "created_at": "2024-01-01T00:00:00Z" },
+                    { "num": "1.2.0", "yanked": true, "created_at": "2023-01-01T00:00:00Z" },
+                ],
+            }))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+fn target(kind: WatchKind) -> WatchTarget {
+    WatchTarget {
+        id: "crates-io:demo".to_string(),
+        enabled: true,
+        labels: vec![],
+        kind,
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+#[tokio::test]
+async fn reports_max_stable_version_skipping_yanked() {
+    let base = spawn_stand_in().await;
+    let p = CratesIoLatestProvider::with_registry(ApiEndpoint::new(base.clone()));
+
+    let event = p
+        .check(&target(WatchKind::CratesIoLatest {
+            name: "demo".into(),
+            include_prereleases: false,
+        }))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(event.event_type, EventType::CratesIoLatest);
+    assert_eq!(event.source, Source::CratesIo);
+    assert_eq!(event.new_value, "1.9.0");
+    assert_eq!(event.occurred_at.as_deref(), Some("2024-01-01T00:00:00Z"));
+    assert_eq!(event.url, Some(format!("{base}/crates/demo/1.9.0")));
+
+    let event = p
+        .check(&target(WatchKind::CratesIoLatest {
+            name: "demo".into(),
+            include_prereleases: true,
+        }))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.new_value, "2.0.0-beta.1");
+}
+
+#[tokio::test]
+async fn yank_events_say_which_versions_changed() {
+    let base = spawn_stand_in().await;
+    let p = CratesIoYankedProvider::with_registry(ApiEndpoint::new(base));
+    let t = target(WatchKind::CratesIoYanked {
+        name: "demo".into(),
+    });
+
+    let mut event = p.check(&t).await.unwrap().unwrap();
+    assert_eq!(event.event_type, EventType::CratesIoYanked);
+    assert_eq!(event.new_value, "1.2.0, 1.10.0");
+
+    event.old_value = Some("1.2.0, 1.9.1".into());
+    p.enrich(&t, &mut event).await.unwrap();
+    assert_eq!(event.meta[META_YANKED], "1.10.0");
+    assert_eq!(event.meta[META_UNYANKED], "1.9.1");
+}
+
+#[derive(Clone, Default)]
+struct RecordingNotifier {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    async fn notify(&self, event: &Event) -> AppResult<()> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn re_yank_after_unyank_is_reported_again() {
+    let base = spawn_stand_in().await;
+    let t = target(WatchKind::CratesIoYanked {
+        name: "demo".into(),
+    });
+    let target_repo = InMemoryTargetRepository::new(vec![t.clone()]);
+    let store = InMemoryEventStore::new();
+    let provider = CratesIoYankedProvider::with_registry(ApiEndpoint::new(base));
+    let notifier = RecordingNotifier::default();
+    let run_once = RunOnceUseCase {
+        targets: &target_repo,
+        provider: &provider,
+        handle_event: HandleEventUseCase {
+            store: &store,
+            notifier: &notifier,
+            publisher: None,
+            cooldown_seconds: 0,
+        },
+        concurrency: ConcurrencyLimits::default(),
+    };
+
+    // "1.2.0" -> "1.2.0, 1.10.0" 发生两次 (中间 1.10.0 被 unyank 过)
+    for _ in 0..2 {
+        store.set_last_value(&t.id, "1.2.0").await.unwrap();
+        run_once.execute().await.unwrap();
+    }
+
+    let events = notifier.events.lock().unwrap().clone();
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|e| e.meta[META_YANKED] == "1.10.0"));
+    assert_ne!(events[0].event_id, events[1].event_id);
+}
+
+#[test]
+fn yanks_option_adds_a_second_target() {
+    let cfg: Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 60
+targets:
+  - type: crates_io_latest
+    crate: serde
+    yanks: true
+    labels: ["rust"]
+"#,
+    )
+    .unwrap();
+
+    let targets = cfg.to_watch_targets().unwrap();
+    let ids: Vec<&str> = targets.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(ids, ["crates-io:serde:latest", "crates-io:serde:yanked"]);
+    assert_eq!(
+        targets[1].kind,
+        WatchKind::CratesIoYanked {
+            name: "serde".into()
+        }
+    );
+    assert_eq!(targets[1].labels, ["rust"]);
+}
@@ -1,6 +1,6 @@
-use repopulse::application::usecases::{HandleEventUseCase, RunOnceUseCase};
-use repopulse::application::{AppResult, Notifier};
-use repopulse::domain::Event;
+use repopulse::application::usecases::{ConcurrencyLimits, HandleEventUseCase, RunOnceUseCase};
+use repopulse::application::{AppResult, EventStore, Notifier};
+use repopulse::domain::{Event, EventType, Source};
 use repopulse::domain::{RepoId, WatchKind, WatchTarget};
 use repopulse::infrastructure::{
     fake_provider::FakeWatchProvider,
@@ -41,6 +41,9 @@ async fn should_notify_only_once_for_same_event() {
         enabled: true,
         labels: vec![],
         kind: WatchKind::GitHubRelease { repo },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
     }];
 
     let target_repo = InMemoryTargetRepository::new(targets);
@@ -48,21 +51,78 @@ async fn should_notify_only_once_for_same_event() {
     let store = InMemoryEventStore::new();
     let notifier = CountingNotifier::new();
 
+    // 已有 baseline, 否则首次观察只会记录 baseline 不通知
+    store
+        .set_last_value("github:pedroslopez/whatsapp-web.js:release", "v0.9.0")
+        .await
+        .unwrap();
+
     let handle_event = HandleEventUseCase {
         store: &store,
         notifier: &notifier,
+        publisher: None,
         cooldown_seconds: 0,
     };
     let run_once = RunOnceUseCase {
         targets: &target_repo,
         provider: &provider,
         handle_event,
+        concurrency: ConcurrencyLimits::default(),
     };
 
     // 第一次执行 通知 1 次
     run_once.execute().await.unwrap();
-    // 第二次执行 同一个 fake event（event_id 相同） 不通知
+    // 第二次执行 值没变, 在 last value 比较处就停下 (has_seen 去重见下一个测试)
     run_once.execute().await.unwrap();
 
     assert_eq!(notifier.get(), 1);
 }
+
+#[tokio::test]
+async fn handle_event_dedups_by_event_id() {
+    let target = WatchTarget {
+        id: "github:o/r:release".to_string(),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::GitHubRelease {
+            repo: RepoId::parse("o/r").unwrap(),
+        },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    };
+    let store = InMemoryEventStore::new();
+    let notifier = CountingNotifier::new();
+    let handle_event = HandleEventUseCase {
+        store: &store,
+        notifier: &notifier,
+        publisher: None,
+        cooldown_seconds: 0,
+    };
+
+    let event = |old_value: &str| Event {
+        event_id: Event::make_event_id(&EventType::GitHubRelease, "o/r", "v1.0.0"),
+        event_type: EventType::GitHubRelease,
+        source: Source::GitHub,
+        subject: "o/r".to_string(),
+        old_value: Some(old_value.to_string()),
+        new_value: "v1.0.0".to_string(),
+        occurred_at: None,
+        detected_at: "0s_since_epoch".to_string(),
+        url: None,
+        meta: Default::default(),
+    };
+
+    // 不经过 run_once 的 baseline / last value: 同一个 event_id 只处理一次
+    handle_event
+        .execute(&event("v0.9.0"), &target)
+        .await
+        .unwrap();
+    handle_event
+        .execute(&event("v0.9.1"), &target)
+        .await
+        .unwrap();
+
+    assert_eq!(notifier.get(), 1);
+    assert!(store.has_seen(&event("v0.9.0").event_id).await.unwrap());
+}
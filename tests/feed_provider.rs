@@ -0,0 +1,242 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::{Router, extract::State, http::header, response::IntoResponse, routing::get};
+
+use repopulse::application::usecases::{ConcurrencyLimits, HandleEventUseCase, RunOnceUseCase};
+use repopulse::application::{AppResult, EventStore, Notifier, WatchProvider};
+use repopulse::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use repopulse::infrastructure::feed_provider::{FeedProvider, META_ENTRY_ID};
+use repopulse::infrastructure::memory_store::{InMemoryEventStore, InMemoryTargetRepository};
+
+type Body = Arc<Mutex<String>>;
+
+async fn feed(State(body): State<Body>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "application/xml")],
+        body.lock().unwrap().clone(),
+    )
+}
+
+async fn spawn_stand_in(initial: String) -> (String, Body) {
+    let body: Body = Arc::new(Mutex::new(initial));
+    let app = Router::new()
+        .route("/feed.xml", get(feed))
+        .with_state(body.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{}/feed.xml", addr), body)
+}
+
+/// GitHub releases.atom 的形状: 最新在前, 链接是相对地址也要能处理
+fn atom(entries: &[(&str, &str)]) -> String {
+    let entries: String = entries
+        .iter()
+        .map(|(tag, updated)| {
+            format!(
+                r#"<entry>
+  <id>tag:github.com,2008:Repository/1/{tag}</id>
+  <updated>{updated}</updated>
+  <link rel="alternate" type="text/html" href="/owner/repo/releases/tag/{tag}"/>
+  <title>Release {tag}</title>
+</entry>"#
+            )
+        })
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en-US">
+  <id>tag:github.com,2008:https://github.com/owner/repo/releases</id>
+  <title>Release notes from repo</title>
+  {entries}
+</feed>"#
+    )
+}
+
+const RSS: &str = r#"<?xml version="1.0"?>
+<rss version="2.0">
+  <channel>
+    <title>Security bulletins</title>
+    <item>
+      <title><![CDATA[ DSA-2 openssl ]]></title>
+      <link>https://example.com/dsa-2</link>
+      <guid isPermaLink="false">dsa-2</guid>
+      <pubDate>Tue, 02 Jan 2024 10:00:00 +0100</pubDate>
+    </item>
+    <item>
+      <title>DSA-1 curl</title>
+      <link>https://example.com/dsa-1</link>
+      <pubDate>Mon, 01 Jan 2024 10:00:00 GMT</pubDate>
+    </item>
+  </channel>
+</rss>"#;
+
+fn target(url: &str) -> WatchTarget {
+    WatchTarget {
+        id: format!("feed:{url}"),
+        enabled: true,
+        labels: vec!["feeds".into()],
+        kind: WatchKind::Feed { url: url.into() },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+#[tokio::test]
+async fn parses_rss_and_atom_oldest_first() {
+    let (url, body) = spawn_stand_in(RSS.to_string()).await;
+    let p = FeedProvider::new();
+
+    let items = p.check_items(&target(&url)).await.unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].event_type, EventType::FeedEntry);
+    assert_eq!(items[0].source, Source::Feed);
+    assert_eq!(items[0].new_value, "DSA-1 curl");
+    // 没有 guid 时用 link 作为 id
+    assert_eq!(items[0].meta[META_ENTRY_ID], "https://example.com/dsa-1");
+    assert_eq!(items[1].new_value, "DSA-2 openssl");
+    assert_eq!(items[1].url.as_deref(), Some("https://example.com/dsa-2"));
+    assert_eq!(
+        items[1].occurred_at.as_deref(),
+        Some("2024-01-02T09:00:00Z")
+    );
+
+    *body.lock().unwrap() = atom(&[
+        ("v1.1.0", "2024-02-01T00:00:00Z"),
+        ("v1.0.0", "2024-01-01T00:00:00Z"),
+    ]);
+    let items = p.check_items(&target(&url)).await.unwrap();
+    let titles: Vec<&str> = items.iter().map(|e| e.new_value.as_str()).collect();
+    assert_eq!(titles, ["Release v1.0.0", "Release v1.1.0"]);
+    let host = url.trim_end_matches("/feed.xml");
+    assert_eq!(
+        items[1].url,
+        Some(format!("{host}/owner/repo/releases/tag/v1.1.0"))
+    );
+    // check 只给最新一条
+    let newest = p.check(&target(&url)).await.unwrap().unwrap();
+    assert_eq!(newest.new_value, "Release v1.1.0");
+
+    *body.lock().unwrap() = "<html><body>not a feed</body></html>".into();
+    assert!(p.check_items(&target(&url)).await.is_err());
+}
+
+fn rss(items: &[(&str, &str)]) -> String {
+    let items: String = items
+        .iter()
+        .map(|(guid, date)| format!("<item><guid>{guid}</guid><pubDate>{date}</pubDate></item>"))
+        .collect();
+    format!(r#"<?xml version="1.0"?><rss version="2.0"><channel>{items}</channel></rss>"#)
+}
+
+#[tokio::test]
+async fn unparsed_dates_keep_document_order() {
+    // 最新在前; 日期解析不了时按字符串排会把 "Mon" 排到 "Sun" 前面
+    let (url, body) = spawn_stand_in(rss(&[
+        ("c", "Mon, 8th of January"),
+        ("b", "Sun, 7th of January"),
+        ("a", "Sat, 6th of January"),
+    ]))
+    .await;
+    let p = FeedProvider::new();
+    let ids = |items: Vec<Event>| -> Vec<String> {
+        items
+            .into_iter()
+            .map(|e| e.meta[META_ENTRY_ID].clone())
+            .collect()
+    };
+
+    let items = p.check_items(&target(&url)).await.unwrap();
+    assert_eq!(items[2].occurred_at.as_deref(), Some("Mon, 8th of January"));
+    assert_eq!(ids(items), ["a", "b", "c"]);
+
+    // 只有部分能解析: 也按文档顺序
+    *body.lock().unwrap() = rss(&[
+        ("c", "Mon, 01 Jan 2024 10:00:00 GMT"),
+        ("b", "yesterday"),
+        ("a", "Tue, 02 Jan 2024 10:00:00 GMT"),
+    ]);
+    let items = p.check_items(&target(&url)).await.unwrap();
+    assert_eq!(ids(items), ["a", "b", "c"]);
+}
+
+#[tokio::test]
+async fn feeds_with_a_dtd_are_rejected() {
+    let (url, _) = spawn_stand_in(
+        r#"<?xml version="1.0"?>
+<!DOCTYPE rss [
+  <!ENTITY lol "lol">
+  <!ENTITY lol1 "&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;">
+]>
+<rss version="2.0"><channel><item><guid>&lol1;</guid></item></channel></rss>"#
+            .to_string(),
+    )
+    .await;
+    let err = FeedProvider::new()
+        .check_items(&target(&url))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid feed"), "{err}");
+}
+
+#[derive(Clone, Default)]
+struct RecordingNotifier {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    async fn notify(&self, event: &Event) -> AppResult<()> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn one_event_per_new_entry_after_baseline() {
+    let (url, body) = spawn_stand_in(atom(&[("v1.0.0", "2024-01-01T00:00:00Z")])).await;
+    let target_repo = InMemoryTargetRepository::new(vec![target(&url)]);
+    let provider = FeedProvider::new();
+    let store = InMemoryEventStore::new();
+    let notifier = RecordingNotifier::default();
+    let run_once = RunOnceUseCase {
+        targets: &target_repo,
+        provider: &provider,
+        handle_event: HandleEventUseCase {
+            store: &store,
+            notifier: &notifier,
+            publisher: None,
+            cooldown_seconds: 0,
+        },
+        concurrency: ConcurrencyLimits::default(),
+    };
+
+    // baseline: 已有条目不通知
+    run_once.execute().await.unwrap();
+    assert!(notifier.events.lock().unwrap().is_empty());
+
+    *body.lock().unwrap() = atom(&[
+        ("v1.2.0", "2024-03-01T00:00:00Z"),
+        ("v1.1.0", "2024-02-01T00:00:00Z"),
+        ("v1.0.0", "2024-01-01T00:00:00Z"),
+    ]);
+    run_once.execute().await.unwrap();
+    // 再跑一次不会重复
+    run_once.execute().await.unwrap();
+
+    let events = notifier.events.lock().unwrap().clone();
+    let titles: Vec<&str> = events.iter().map(|e| e.new_value.as_str()).collect();
+    assert_eq!(titles, ["Release v1.1.0", "Release v1.2.0"]);
+    assert_eq!(
+        store
+            .get_last_value(&format!("feed:{url}"))
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("Release v1.2.0")
+    );
+}
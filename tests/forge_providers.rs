@@ -0,0 +1,253 @@
+use axum::{Router, http::StatusCode, http::Uri, response::IntoResponse};
+
+use repopulse::application::WatchProvider;
+use repopulse::domain::{EventType, Forge, RepoId, Source, WatchKind, WatchTarget};
+use repopulse::infrastructure::api_endpoint::ApiEndpoint;
+use repopulse::infrastructure::gitea_provider::{GiteaBranchProvider, GiteaReleaseProvider};
+use repopulse::infrastructure::gitlab_provider::{GitLabBranchProvider, GitLabReleaseProvider};
+use repopulse::interfaces::config::Config;
+
+/// 本地 GitLab (/api/v4) + Gitea (/api/v1); GitLab 的项目路径是 url-encoded 的一整段
+async fn forge(uri: Uri) -> impl IntoResponse {
+    match uri.path() {
+        "/api/v4/projects/acme%2Ftools%2Fcli/releases" => r#"[
+            {"tag_name":"v3.0.0","released_at":"2030-01-01T00:00:00Z","upcoming_release":true},
+            {"tag_name":"v2.4.0","released_at":"2024-05-01T00:00:00Z","upcoming_release":false,
+             "_links":{"self":"https://gitlab.example.com/acme/tools/cli/-/releases/v2.4.0"}}
+        ]"#
+        .into_response(),
+        "/api/v4/projects/acme%2Fempty/releases" => "[]".into_response(),
+        "/api/v4/projects/acme%2Ftools%2Fcli/repository/branches/feature%2Fx" => r#"{
+            "name":"feature/x",
+            "commit":{"id":"0a1b2c3d","committed_date":"2024-05-02T00:00:00Z"},
+            "web_url":"https://gitlab.example.com/acme/tools/cli/-/tree/feature/x"
+        }"#
+        .into_response(),
+        "/api/v1/repos/owner/app/releases/latest" => r#"{
+            "tag_name":"v1.2.0","published_at":"2024-06-01T00:00:00Z",
+            "html_url":"https://gitea.example.com/owner/app/releases/tag/v1.2.0"
+        }"#
+        .into_response(),
+        "/api/v1/repos/owner/app/branches/main" => {
+            r#"{"name":"main","commit":{"id":"deadbeef","timestamp":"2024-06-02T00:00:00Z"}}"#
+                .into_response()
+        }
+        _ => (StatusCode::NOT_FOUND, "not found").into_response(),
+    }
+}
+
+async fn spawn_stand_in() -> String {
+    let app = Router::new().fallback(forge);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+fn target(kind: WatchKind) -> WatchTarget {
+    WatchTarget {
+        id: format!("{}:{}", kind.key(), kind.subject()),
+        enabled: true,
+        labels: vec![],
+        kind,
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+#[tokio::test]
+async fn gitlab_nested_project_release_and_branch() {
+    let base = spawn_stand_in().await;
+    let api = ApiEndpoint::new(format!("{base}/api/v4"));
+    let repo = RepoId::parse_nested("acme/tools/cli").unwrap();
+
+    // upcoming release 被跳过
+    let event = GitLabReleaseProvider::with_endpoint(api.clone())
+        .check(&target(WatchKind::release(Forge::GitLab, repo.clone())))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.event_type, EventType::GitLabRelease);
+    assert_eq!(event.source, Source::GitLab);
+    assert_eq!(event.subject, "acme/tools/cli");
+    assert_eq!(event.new_value, "v2.4.0");
+    assert_eq!(
+        event.url.as_deref(),
+        Some("https://gitlab.example.com/acme/tools/cli/-/releases/v2.4.0")
+    );
+
+    let event = GitLabBranchProvider::with_endpoint(api.clone())
+        .check(&target(WatchKind::branch(
+            Forge::GitLab,
+            repo,
+            "feature/x".into(),
+        )))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.event_type, EventType::GitLabBranch);
+    assert_eq!(event.subject, "acme/tools/cli#feature/x");
+    assert_eq!(event.new_value, "0a1b2c3d");
+    assert_eq!(event.occurred_at.as_deref(), Some("2024-05-02T00:00:00Z"));
+
+    // 没有 release 不是错误
+    let empty = RepoId::parse("acme/empty").unwrap();
+    let none = GitLabReleaseProvider::with_endpoint(api)
+        .check(&target(WatchKind::release(Forge::GitLab, empty)))
+        .await
+        .unwrap();
+    assert!(none.is_none());
+}
+
+#[tokio::test]
+async fn gitea_release_and_branch() {
+    let base = spawn_stand_in().await;
+    let api = ApiEndpoint::new(format!("{base}/api/v1"));
+    let repo = RepoId::parse("owner/app").unwrap();
+
+    let event = GiteaReleaseProvider::with_endpoint(api.clone())
+        .check(&target(WatchKind::release(Forge::Gitea, repo.clone())))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.event_type, EventType::GiteaRelease);
+    assert_eq!(event.source, Source::Gitea);
+    assert_eq!(event.new_value, "v1.2.0");
+    assert_eq!(event.occurred_at.as_deref(), Some("2024-06-01T00:00:00Z"));
+
+    let event = GiteaBranchProvider::with_endpoint(api.clone())
+        .check(&target(WatchKind::branch(
+            Forge::Gitea,
+            repo,
+            "main".into(),
+        )))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.event_type, EventType::GiteaBranch);
+    assert_eq!(event.subject, "owner/app#main");
+    assert_eq!(event.new_value, "deadbeef");
+    assert_eq!(event.url, Some(format!("{base}/owner/app/src/branch/main")));
+
+    // 仓库没有 release (404) -> None; 分支不存在 -> 报错
+    let other = RepoId::parse("owner/other").unwrap();
+    let none = GiteaReleaseProvider::with_endpoint(api.clone())
+        .check(&target(WatchKind::release(Forge::Gitea, other.clone())))
+        .await
+        .unwrap();
+    assert!(none.is_none());
+    let err = GiteaBranchProvider::with_endpoint(api)
+        .check(&target(WatchKind::branch(
+            Forge::Gitea,
+            other,
+            "main".into(),
+        )))
+        .await;
+    assert!(err.is_err());
+}
+
+#[test]
+fn forge_field_selects_kind_and_github_stays_compatible() {
+    let cfg: Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 60
+targets:
+  - type: release
+    forge: gitlab
+    repo: acme/tools/cli
+  - type: branch
+    forge: forgejo
+    repo: owner/app
+    branch: main
+  - type: release
+    repo: owner/repo
+"#,
+    )
+    .unwrap();
+
+    let targets = cfg.to_watch_targets().unwrap();
+    let ids: Vec<&str> = targets.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        [
+            "gitlab:acme/tools/cli:release",
+            "gitea:owner/app:branch:main",
+            "github:owner/repo:release",
+        ]
+    );
+    assert_eq!(targets[0].kind.key(), WatchKind::GITLAB_RELEASE);
+    assert_eq!(targets[1].kind.key(), WatchKind::GITEA_BRANCH);
+    // forge 默认 github, 与 github_release 完全一样
+    assert_eq!(
+        targets[2].kind,
+        WatchKind::GitHubRelease {
+            repo: RepoId::parse("owner/repo").unwrap()
+        }
+    );
+
+    // 只有 GitLab 允许嵌套 namespace
+    let cfg: Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 60
+targets:
+  - type: release
+    forge: gitea
+    repo: a/b/c
+"#,
+    )
+    .unwrap();
+    assert!(cfg.to_watch_targets().is_err());
+}
+
+#[test]
+fn forge_branch_ids_include_the_branch() {
+    let cfg: Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 60
+targets:
+  - type: branch
+    forge: gitlab
+    repo: acme/cli
+    branch: main
+  - type: branch
+    forge: gitlab
+    repo: acme/cli
+    branch: stable
+"#,
+    )
+    .unwrap();
+    let targets = cfg.to_watch_targets().unwrap();
+    let ids: Vec<&str> = targets.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        [
+            "gitlab:acme/cli:branch:main",
+            "gitlab:acme/cli:branch:stable"
+        ]
+    );
+
+    // 同一分支配置两次: id 重复, 报错
+    let dup: Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 60
+targets:
+  - type: branch
+    forge: gitea
+    repo: owner/app
+    branch: main
+  - type: branch
+    forge: forgejo
+    repo: owner/app
+    branch: main
+"#,
+    )
+    .unwrap();
+    let err = dup.to_watch_targets().unwrap_err().to_string();
+    assert!(
+        err.contains("duplicate target id: gitea:owner/app:branch:main"),
+        "{err}"
+    );
+}
@@ -0,0 +1,201 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+};
+use serde_json::json;
+
+use repopulse::application::usecases::{ConcurrencyLimits, HandleEventUseCase, RunOnceUseCase};
+use repopulse::application::{AppResult, Notifier, WatchProvider};
+use repopulse::domain::{Event, EventType, RepoId, Source, WatchKind, WatchTarget};
+use repopulse::infrastructure::github_branch_provider::{
+    GitHubBranchProvider, META_COMMIT_COUNT, META_COMMITS, META_COMPARE_URL, META_FORCE_PUSHED,
+};
+use repopulse::infrastructure::github_client::GitHubClient;
+use repopulse::infrastructure::memory_store::{InMemoryEventStore, InMemoryTargetRepository};
+
+type Head = Arc<Mutex<&'static str>>;
+
+/// old1...new1: 正常前进两个提交; old2...new2: 分叉 (force-push); 其它: 404
+async fn spawn_stand_in() -> String {
+    spawn_stand_in_with_head(Arc::new(Mutex::new("new1"))).await
+}
+
+async fn spawn_stand_in_with_head(head: Head) -> String {
+    let branch = get(|State(head): State<Head>| async move {
+        Json(json!({
+            "commit": { "sha": *head.lock().unwrap() },
+            "_links": { "html": "https://github.com/o/r/tree/main" },
+        }))
+    });
+    let app = Router::new()
+        .route("/repos/o/r/branches/main", branch)
+        .with_state(head)
+        .route(
+        "/repos/o/r/compare/{range}",
+        get(|Path(range): Path<String>| async move {
+            match range.as_str() {
+                "old1...new1" => Json(json!({
+                    "status": "ahead",
+                    "ahead_by": 2,
+                    "behind_by": 0,
+                    "html_url": "https://github.com/o/r/compare/old1...new1",
+                    "commits": [
+                        { "sha": "aaaaaaa1111", "commit": { "message": "fix: first\n\nbody", "author": { "name": "Alice" } } },
+                        { "sha": "bbbbbbb2222", "commit": { "message": "feat: second", "author": { "name": "Bob" } } },
+                    ],
+                }))
+                .into_response(),
+                "old2...new2" => Json(json!({
+                    "status": "diverged",
+                    "ahead_by": 1,
+                    "behind_by": 3,
+                    "commits": [
+                        { "sha": "ccccccc3333", "commit": { "message": "rewrite", "author": null }, "author": { "login": "carol" } },
+                    ],
+                }))
+                .into_response(),
+                _ => StatusCode::NOT_FOUND.into_response(),
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+fn target() -> WatchTarget {
+    WatchTarget {
+        id: "github:o/r:branch".to_string(),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::GitHubBranch {
+            repo: RepoId::parse("o/r").unwrap(),
+            branch: "main".into(),
+        },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+fn moved(old: &str, new: &str) -> Event {
+    Event {
+        event_id: Event::make_event_id(&EventType::GitHubBranch, "o/r#main", new),
+        event_type: EventType::GitHubBranch,
+        source: Source::GitHub,
+        subject: "o/r#main".into(),
+        old_value: Some(old.into()),
+        new_value: new.into(),
+        occurred_at: None,
+        detected_at: "0s_since_epoch".into(),
+        url: None,
+        meta: Default::default(),
+    }
+}
+
+async fn provider() -> GitHubBranchProvider {
+    let base = spawn_stand_in().await;
+    GitHubBranchProvider::new(GitHubClient::new(None).with_base_url(base))
+}
+
+#[tokio::test]
+async fn fast_forward_lists_commits_newest_first() {
+    let p = provider().await;
+    let mut event = moved("old1", "new1");
+
+    p.enrich(&target(), &mut event).await.unwrap();
+
+    assert_eq!(event.meta[META_COMMIT_COUNT], "2");
+    assert_eq!(
+        event.meta[META_COMMITS],
+        "bbbbbbb Bob: feat: second\naaaaaaa Alice: fix: first"
+    );
+    assert_eq!(
+        event.meta[META_COMPARE_URL],
+        "https://github.com/o/r/compare/old1...new1"
+    );
+    assert_eq!(event.meta[META_FORCE_PUSHED], "false");
+}
+
+#[tokio::test]
+async fn diverged_history_is_flagged_as_force_push() {
+    let p = provider().await;
+    let mut event = moved("old2", "new2");
+
+    p.enrich(&target(), &mut event).await.unwrap();
+
+    assert_eq!(event.meta[META_FORCE_PUSHED], "true");
+    assert_eq!(event.meta[META_COMMIT_COUNT], "1");
+    assert_eq!(event.meta[META_COMMITS], "ccccccc carol: rewrite");
+}
+
+#[tokio::test]
+async fn missing_old_commit_is_flagged_as_force_push() {
+    let p = provider().await;
+    let mut event = moved("gone", "new3");
+
+    p.enrich(&target(), &mut event).await.unwrap();
+
+    assert_eq!(event.meta[META_FORCE_PUSHED], "true");
+    assert!(event.meta[META_COMPARE_URL].ends_with("/o/r/compare/gone...new3"));
+    assert!(!event.meta.contains_key(META_COMMITS));
+}
+
+#[derive(Clone, Default)]
+struct RecordingNotifier {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    async fn notify(&self, event: &Event) -> AppResult<()> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn force_push_back_to_an_earlier_head_is_reported() {
+    let head: Head = Arc::new(Mutex::new("new2"));
+    let base = spawn_stand_in_with_head(head.clone()).await;
+    let provider = GitHubBranchProvider::new(GitHubClient::new(None).with_base_url(base));
+    let target_repo = InMemoryTargetRepository::new(vec![target()]);
+    let store = InMemoryEventStore::new();
+    let notifier = RecordingNotifier::default();
+    let run_once = RunOnceUseCase {
+        targets: &target_repo,
+        provider: &provider,
+        handle_event: HandleEventUseCase {
+            store: &store,
+            notifier: &notifier,
+            publisher: None,
+            cooldown_seconds: 0,
+        },
+        concurrency: ConcurrencyLimits::default(),
+    };
+
+    // baseline new2, 之后 force-push 来回: old2 -> new2 -> old2 (最后一次回到见过的 head)
+    for sha in ["new2", "old2", "new2", "old2"] {
+        *head.lock().unwrap() = sha;
+        run_once.execute().await.unwrap();
+    }
+
+    let events = notifier.events.lock().unwrap().clone();
+    let heads: Vec<(&str, &str)> = events
+        .iter()
+        .map(|e| (e.new_value.as_str(), e.meta[META_FORCE_PUSHED].as_str()))
+        .collect();
+    assert_eq!(
+        heads,
+        [("old2", "true"), ("new2", "true"), ("old2", "true")]
+    );
+}
@@ -0,0 +1,160 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use axum::{Router, http::StatusCode, response::IntoResponse, routing::get};
+
+use repopulse::application::{AppError, RateLimitReporter};
+use repopulse::infrastructure::github_client::GitHubClient;
+
+/// 第一次正常返回, 之后一律 403 + 额度耗尽
+async fn spawn_stand_in(hits: Arc<AtomicUsize>) -> String {
+    let app = Router::new().route(
+        "/repos/o/r/releases/latest",
+        get(move || {
+            let hits = hits.clone();
+            async move {
+                let n = hits.fetch_add(1, Ordering::SeqCst);
+                let reset = "4102444800"; // 2100-01-01
+                if n == 0 {
+                    return (
+                        [
+                            ("x-ratelimit-limit", "60"),
+                            ("x-ratelimit-remaining", "1"),
+                            ("x-ratelimit-reset", reset),
+                        ],
+                        r#"{"tag_name":"v1.0.0"}"#,
+                    )
+                        .into_response();
+                }
+                (
+                    StatusCode::FORBIDDEN,
+                    [
+                        ("x-ratelimit-limit", "60"),
+                        ("x-ratelimit-remaining", "0"),
+                        ("x-ratelimit-reset", reset),
+                    ],
+                    "API rate limit exceeded",
+                )
+                    .into_response()
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn pauses_until_reset_after_rate_limit_rejection() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let base = spawn_stand_in(hits.clone()).await;
+    let github = GitHubClient::new(None).with_base_url(base);
+    let path = "/repos/o/r/releases/latest";
+
+    let ok = github
+        .get(None, path, "application/json", None)
+        .await
+        .unwrap();
+    assert!(ok.status.is_success());
+    assert_eq!(github.rate_limits()[0].remaining, Some(1));
+
+    let err = github.get(None, path, "application/json", None).await.err();
+    assert!(matches!(err, Some(AppError::RateLimited(_))));
+
+    // 暂停期间不再请求上游
+    let err = github.get(None, path, "application/json", None).await.err();
+    assert!(matches!(err, Some(AppError::RateLimited(_))));
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+    let snapshot = &github.rate_limits()[0];
+    assert_eq!(snapshot.remaining, Some(0));
+    assert_eq!(snapshot.paused_until_epoch, Some(4102444800));
+}
+
+/// search 额度耗尽, core 正常
+async fn spawn_search_exhausted(hits: Arc<AtomicUsize>) -> String {
+    let reset = "4102444800";
+    let app = Router::new()
+        .route(
+            "/search/issues",
+            get(move || {
+                let hits = hits.clone();
+                async move {
+                    hits.fetch_add(1, Ordering::SeqCst);
+                    (
+                        StatusCode::FORBIDDEN,
+                        [
+                            ("x-ratelimit-limit", "30"),
+                            ("x-ratelimit-remaining", "0"),
+                            ("x-ratelimit-reset", reset),
+                            ("x-ratelimit-resource", "search"),
+                        ],
+                        "API rate limit exceeded",
+                    )
+                }
+            }),
+        )
+        .route(
+            "/repos/o/r/releases/latest",
+            get(|| async {
+                (
+                    [
+                        ("x-ratelimit-limit", "5000"),
+                        ("x-ratelimit-remaining", "4999"),
+                        ("x-ratelimit-reset", reset),
+                        ("x-ratelimit-resource", "core"),
+                    ],
+                    r#"{"tag_name":"v1.0.0"}"#,
+                )
+            }),
+        );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn search_quota_does_not_pause_core_requests() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let base = spawn_search_exhausted(hits.clone()).await;
+    let github = GitHubClient::new(None).with_base_url(base);
+    let search = "/search/issues?q=repo:o/r";
+    let core = "/repos/o/r/releases/latest";
+
+    let err = github
+        .get(None, search, "application/json", None)
+        .await
+        .err();
+    assert!(matches!(err, Some(AppError::RateLimited(_))));
+    let err = github
+        .get(None, search, "application/json", None)
+        .await
+        .err();
+    assert!(matches!(err, Some(AppError::RateLimited(_))));
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+    let ok = github
+        .get(None, core, "application/json", None)
+        .await
+        .unwrap();
+    assert!(ok.status.is_success());
+
+    let limits = github.rate_limits();
+    let summary: Vec<(Option<&str>, Option<u64>, Option<i64>)> = limits
+        .iter()
+        .map(|r| (r.resource.as_deref(), r.remaining, r.paused_until_epoch))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (Some("core"), Some(4999), None),
+            (Some("search"), Some(0), Some(4102444800)),
+        ]
+    );
+}
@@ -0,0 +1,320 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+};
+use serde_json::{Value, json};
+
+use repopulse::application::usecases::{ConcurrencyLimits, HandleEventUseCase, RunOnceUseCase};
+use repopulse::application::{AppResult, Notifier, WatchProvider};
+use repopulse::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use repopulse::infrastructure::github_client::GitHubClient;
+use repopulse::infrastructure::github_search_provider::{
+    GitHubSearchProvider, META_ACTION, META_ITEM_KIND, META_LABEL, META_LABELS, META_NUMBER,
+    META_REPO,
+};
+use repopulse::infrastructure::memory_store::{InMemoryEventStore, InMemoryTargetRepository};
+use repopulse::interfaces::config::Config;
+
+const QUERY: &str = "repo:o/r label:bug is:open";
+
+type Issues = Arc<Mutex<Vec<Value>>>;
+
+/// 模拟 `is:open`: 只返回 open 的条目, 最近更新的在前
+async fn search(
+    State(issues): State<Issues>,
+    Query(q): Query<std::collections::HashMap<String, String>>,
+) -> Json<Value> {
+    assert_eq!(q.get("q").map(String::as_str), Some(QUERY));
+    let mut items: Vec<Value> = issues
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|i| i["state"] == "open")
+        .cloned()
+        .collect();
+    items.sort_by(|a, b| b["updated_at"].as_str().cmp(&a["updated_at"].as_str()));
+    Json(json!({ "total_count": items.len(), "items": items }))
+}
+
+/// `"fail": true` 的条目单独查询时返回 500
+async fn issue(
+    State(issues): State<Issues>,
+    Path(number): Path<u64>,
+) -> Result<Json<Value>, StatusCode> {
+    let issue = issues
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|i| i["number"] == number)
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if issue["fail"] == true {
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    Ok(Json(issue))
+}
+
+async fn spawn_stand_in(initial: Vec<Value>) -> (String, Issues) {
+    let issues: Issues = Arc::new(Mutex::new(initial));
+    let app = Router::new()
+        .route("/search/issues", get(search))
+        .route("/repos/o/r/issues/{number}", get(issue))
+        .with_state(issues.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{}", addr), issues)
+}
+
+fn item(number: u64, pull_request: bool, labels: &[&str], updated_at: &str) -> Value {
+    let mut v = json!({
+        "number": number,
+        "title": format!("Bug {number}"),
+        "html_url": format!("https://github.com/o/r/issues/{number}"),
+        "state": "open",
+        "repository_url": "https://api.github.com/repos/o/r",
+        "labels": labels.iter().map(|l| json!({ "name": l })).collect::<Vec<_>>(),
+        "updated_at": updated_at,
+    });
+    if pull_request {
+        v["pull_request"] = json!({ "merged_at": null });
+    }
+    v
+}
+
+fn target() -> WatchTarget {
+    WatchTarget {
+        id: "github:search:bugs".to_string(),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::GitHubSearch {
+            query: QUERY.into(),
+        },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+#[tokio::test]
+async fn reports_matching_items_oldest_first() {
+    let (base, _) = spawn_stand_in(vec![
+        item(1, false, &["bug"], "2024-01-01T00:00:00Z"),
+        item(2, true, &["bug", "ui"], "2024-02-01T00:00:00Z"),
+    ])
+    .await;
+    let p = GitHubSearchProvider::new(
+        GitHubClient::new(None).with_base_url(base),
+        Arc::new(InMemoryEventStore::new()),
+    );
+
+    let items = p.check_items(&target()).await.unwrap();
+    let values: Vec<&str> = items.iter().map(|e| e.new_value.as_str()).collect();
+    assert_eq!(values, ["o/r#1 Bug 1", "o/r#2 Bug 2"]);
+
+    let e = &items[1];
+    assert_eq!(e.event_type, EventType::GitHubSearchItem);
+    assert_eq!(e.source, Source::GitHub);
+    assert_eq!(e.subject, QUERY);
+    assert_eq!(e.url.as_deref(), Some("https://github.com/o/r/issues/2"));
+    assert_eq!(e.meta[META_ACTION], "opened");
+    assert_eq!(e.meta[META_REPO], "o/r");
+    assert_eq!(e.meta[META_NUMBER], "2");
+    assert_eq!(e.meta[META_ITEM_KIND], "pull_request");
+    assert_eq!(e.meta[META_LABELS], "bug, ui");
+    assert_eq!(items[0].meta[META_ITEM_KIND], "issue");
+}
+
+#[derive(Clone, Default)]
+struct RecordingNotifier {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    async fn notify(&self, event: &Event) -> AppResult<()> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn notifies_on_new_closed_merged_and_labeled_items() {
+    let (base, issues) = spawn_stand_in(vec![
+        item(1, false, &["bug"], "2024-01-01T00:00:00Z"),
+        item(2, true, &["bug"], "2024-01-02T00:00:00Z"),
+        item(3, false, &["bug"], "2024-01-03T00:00:00Z"),
+    ])
+    .await;
+    let target_repo = InMemoryTargetRepository::new(vec![target()]);
+    let store = Arc::new(InMemoryEventStore::new());
+    let provider =
+        GitHubSearchProvider::new(GitHubClient::new(None).with_base_url(base), store.clone());
+    let notifier = RecordingNotifier::default();
+    let run_once = RunOnceUseCase {
+        targets: &target_repo,
+        provider: &provider,
+        handle_event: HandleEventUseCase {
+            store: &*store,
+            notifier: &notifier,
+            publisher: None,
+            cooldown_seconds: 0,
+        },
+        concurrency: ConcurrencyLimits::default(),
+    };
+
+    // baseline: 已有条目不通知; 没变化再跑一次也不通知
+    run_once.execute().await.unwrap();
+    run_once.execute().await.unwrap();
+    assert!(notifier.events.lock().unwrap().is_empty());
+
+    {
+        let mut issues = issues.lock().unwrap();
+        // #1 加了标签, #2 合并, #3 关闭, #4 新出现
+        issues[0]["labels"] = json!([{ "name": "bug" }, { "name": "confirmed" }]);
+        issues[0]["updated_at"] = json!("2024-02-01T00:00:00Z");
+        issues[1]["state"] = json!("closed");
+        issues[1]["pull_request"]["merged_at"] = json!("2024-02-02T00:00:00Z");
+        issues[1]["updated_at"] = json!("2024-02-02T00:00:00Z");
+        issues[2]["state"] = json!("closed");
+        issues[2]["updated_at"] = json!("2024-02-03T00:00:00Z");
+        issues.push(item(4, false, &["bug"], "2024-02-04T00:00:00Z"));
+    }
+    run_once.execute().await.unwrap();
+    // 同样的状态不再重复通知
+    run_once.execute().await.unwrap();
+
+    let events = notifier.events.lock().unwrap().clone();
+    let seen: Vec<(&str, &str)> = events
+        .iter()
+        .map(|e| (e.meta[META_NUMBER].as_str(), e.meta[META_ACTION].as_str()))
+        .collect();
+    assert_eq!(
+        seen,
+        [
+            ("1", "labeled"),
+            ("2", "merged"),
+            ("3", "closed"),
+            ("4", "opened")
+        ]
+    );
+    assert_eq!(events[0].meta[META_LABEL], "confirmed");
+}
+
+#[tokio::test]
+async fn failed_lookups_and_unhandled_results_are_retried() {
+    let (base, issues) = spawn_stand_in(vec![
+        item(1, false, &["bug"], "2024-01-01T00:00:00Z"),
+        item(2, false, &["bug"], "2024-01-02T00:00:00Z"),
+    ])
+    .await;
+    let p = GitHubSearchProvider::new(
+        GitHubClient::new(None).with_base_url(base),
+        Arc::new(InMemoryEventStore::new()),
+    );
+    let t = target();
+    let closed = |events: &[Event]| -> Vec<String> {
+        events
+            .iter()
+            .filter(|e| e.meta[META_ACTION] == "closed")
+            .map(|e| e.meta[META_NUMBER].clone())
+            .collect()
+    };
+
+    p.check_items(&t).await.unwrap();
+    p.commit(&t).await.unwrap();
+
+    // #1 关闭, 但单独查询失败: 本轮不报, 也不影响其它条目 (#2 加了标签)
+    {
+        let mut issues = issues.lock().unwrap();
+        issues[0]["state"] = json!("closed");
+        issues[0]["fail"] = json!(true);
+        issues[1]["labels"] = json!([{ "name": "bug" }, { "name": "ui" }]);
+    }
+    let events = p.check_items(&t).await.unwrap();
+    assert!(closed(&events).is_empty());
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].meta[META_LABEL], "ui");
+    p.commit(&t).await.unwrap();
+
+    // 查询恢复后补报; 没 commit (事件没处理完) 时下一轮还会再报
+    issues.lock().unwrap()[0]["fail"] = json!(false);
+    assert_eq!(closed(&p.check_items(&t).await.unwrap()), ["1"]);
+    assert_eq!(closed(&p.check_items(&t).await.unwrap()), ["1"]);
+    p.commit(&t).await.unwrap();
+    assert!(closed(&p.check_items(&t).await.unwrap()).is_empty());
+}
+
+#[tokio::test]
+async fn reopen_and_second_close_are_reported() {
+    let (base, issues) =
+        spawn_stand_in(vec![item(1, false, &["bug"], "2024-01-01T00:00:00Z")]).await;
+    let target_repo = InMemoryTargetRepository::new(vec![target()]);
+    let store = Arc::new(InMemoryEventStore::new());
+    let provider =
+        GitHubSearchProvider::new(GitHubClient::new(None).with_base_url(base), store.clone());
+    let notifier = RecordingNotifier::default();
+    let run_once = RunOnceUseCase {
+        targets: &target_repo,
+        provider: &provider,
+        handle_event: HandleEventUseCase {
+            store: &*store,
+            notifier: &notifier,
+            publisher: None,
+            cooldown_seconds: 0,
+        },
+        concurrency: ConcurrencyLimits::default(),
+    };
+
+    // baseline open, 之后: 关闭 -> 重新打开 -> 再关闭; 每一步后多跑一轮 (无变化)
+    run_once.execute().await.unwrap();
+    for (state, updated_at) in [
+        ("closed", "2024-01-02T00:00:00Z"),
+        ("open", "2024-01-03T00:00:00Z"),
+        ("closed", "2024-01-04T00:00:00Z"),
+    ] {
+        {
+            let mut issues = issues.lock().unwrap();
+            issues[0]["state"] = json!(state);
+            issues[0]["updated_at"] = json!(updated_at);
+        }
+        run_once.execute().await.unwrap();
+        run_once.execute().await.unwrap();
+    }
+
+    let events = notifier.events.lock().unwrap().clone();
+    let actions: Vec<&str> = events
+        .iter()
+        .map(|e| e.meta[META_ACTION].as_str())
+        .collect();
+    assert_eq!(actions, ["closed", "opened", "closed"]);
+    assert_ne!(events[0].event_id, events[2].event_id);
+}
+
+#[test]
+fn config_requires_a_query() {
+    let cfg: Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 60
+targets:
+  - type: github_search
+    query: "repo:o/r is:open"
+"#,
+    )
+    .unwrap();
+    let targets = cfg.to_watch_targets().unwrap();
+    assert_eq!(targets[0].id, "github:search:repo:o/r is:open");
+
+    let bad: Config = serde_yaml::from_str(
+        "poll_interval_seconds: 60\ntargets:\n  - type: github_search\n    query: \"  \"\n",
+    )
+    .unwrap();
+    assert!(bad.to_watch_targets().is_err());
+}
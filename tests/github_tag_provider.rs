@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query},
+    response::IntoResponse,
+    routing::get,
+};
+use serde_json::json;
+
+use repopulse::application::WatchProvider;
+use repopulse::domain::{EventType, NameFilter, RepoId, TagOrder, WatchKind, WatchTarget};
+use repopulse::infrastructure::github_client::GitHubClient;
+use repopulse::infrastructure::github_tag_provider::GitHubTagProvider;
+
+/// tags 分两页 (Link 翻页), 按名字排序 (和 GitHub 一样): 版本最高 / 最新的都在第 2 页;
+/// 提交时间故意和版本顺序不一致
+async fn tags(Query(q): Query<HashMap<String, String>>) -> impl IntoResponse {
+    if q.get("page").map(String::as_str) == Some("2") {
+        return Json(json!([
+            { "name": "v2.0.0-rc.1", "commit": { "sha": "c4" } },
+            { "name": "nightly", "commit": { "sha": "c6" } },
+        ]))
+        .into_response();
+    }
+    (
+        [(
+            "link",
+            r#"</repos/o/r/tags?per_page=100&page=2>; rel="next", </repos/o/r/tags?per_page=100&page=2>; rel="last""#,
+        )],
+        Json(json!([
+            { "name": "v1.9.1", "commit": { "sha": "c5" } },
+            { "name": "v1.9.0", "commit": { "sha": "c2" } },
+            { "name": "v1.10.0", "commit": { "sha": "c3" } },
+        ])),
+    )
+        .into_response()
+}
+
+async fn spawn_stand_in() -> String {
+    let app = Router::new().route("/repos/o/r/tags", get(tags)).route(
+        "/repos/o/r/git/commits/{sha}",
+        get(|Path(sha): Path<String>| async move {
+            let day = &sha[1..];
+            Json(json!({
+                "sha": sha,
+                "committer": { "date": format!("2024-01-0{day}T00:00:00Z") },
+                "html_url": format!("https://example.test/o/r/commit/{sha}"),
+            }))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+fn target(filter: Option<NameFilter>, order: TagOrder) -> WatchTarget {
+    WatchTarget {
+        id: "github:o/r:tag".to_string(),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::GitHubTag {
+            repo: RepoId::parse("o/r").unwrap(),
+            filter,
+            order,
+        },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+async fn provider() -> GitHubTagProvider {
+    let base = spawn_stand_in().await;
+    GitHubTagProvider::new(GitHubClient::new(None).with_base_url(base))
+}
+
+#[tokio::test]
+async fn picks_highest_semver_and_links_commit() {
+    let p = provider().await;
+
+    let event = p
+        .check(&target(None, TagOrder::Semver))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(event.event_type, EventType::GitHubTag);
+    assert_eq!(event.subject, "o/r");
+    assert_eq!(event.new_value, "v2.0.0-rc.1");
+    assert!(event.url.unwrap().ends_with("/o/r/commit/c4"));
+}
+
+#[tokio::test]
+async fn glob_and_regex_filters_narrow_candidates() {
+    let p = provider().await;
+
+    let glob = NameFilter::Glob {
+        pattern: "v1.*".into(),
+    };
+    let event = p
+        .check(&target(Some(glob), TagOrder::Semver))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.new_value, "v1.10.0");
+
+    let regex = NameFilter::Regex {
+        pattern: r"^v1\.9\.\d+$".into(),
+    };
+    let event = p
+        .check(&target(Some(regex), TagOrder::Semver))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.new_value, "v1.9.1");
+
+    let none = NameFilter::Glob {
+        pattern: "v3.*".into(),
+    };
+    assert!(
+        p.check(&target(Some(none), TagOrder::Semver))
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn created_order_uses_commit_date() {
+    let p = provider().await;
+
+    let event = p
+        .check(&target(None, TagOrder::Created))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(event.new_value, "nightly");
+    assert_eq!(event.occurred_at.as_deref(), Some("2024-01-06T00:00:00Z"));
+    assert_eq!(
+        event.url.as_deref(),
+        Some("https://example.test/o/r/commit/c6")
+    );
+}
@@ -0,0 +1,186 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    routing::get,
+};
+use serde_json::{Value, json};
+
+use repopulse::application::usecases::{ConcurrencyLimits, HandleEventUseCase, RunOnceUseCase};
+use repopulse::application::{AppResult, Notifier, WatchProvider};
+use repopulse::domain::{Event, EventType, RepoId, Source, WatchKind, WatchTarget};
+use repopulse::infrastructure::github_client::GitHubClient;
+use repopulse::infrastructure::github_workflow_provider::{
+    GitHubWorkflowProvider, META_HEAD_SHA, META_RUN_NUMBER,
+};
+use repopulse::infrastructure::memory_store::{InMemoryEventStore, InMemoryTargetRepository};
+use repopulse::interfaces::config::Config;
+
+/// (id, conclusion), 最新在前
+type Runs = Arc<Mutex<Vec<(u64, &'static str)>>>;
+
+async fn runs(
+    State(runs): State<Runs>,
+    Query(q): Query<std::collections::HashMap<String, String>>,
+) -> Json<Value> {
+    assert_eq!(q.get("branch").map(String::as_str), Some("release/1.x"));
+    assert_eq!(q.get("status").map(String::as_str), Some("completed"));
+    let runs: Vec<Value> = runs
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(id, conclusion)| {
+            json!({
+                "id": id,
+                "run_number": id + 100,
+                "status": "completed",
+                "conclusion": conclusion,
+                "head_sha": format!("sha{id}"),
+                "html_url": format!("https://github.com/o/r/actions/runs/{id}"),
+                "updated_at": "2024-01-01T00:00:00Z",
+            })
+        })
+        .collect();
+    Json(json!({ "total_count": runs.len(), "workflow_runs": runs }))
+}
+
+async fn spawn_stand_in(initial: Vec<(u64, &'static str)>) -> (String, Runs) {
+    let runs_state: Runs = Arc::new(Mutex::new(initial));
+    let app = Router::new()
+        .route("/repos/o/r/actions/workflows/ci.yml/runs", get(runs))
+        .with_state(runs_state.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{}", addr), runs_state)
+}
+
+fn target() -> WatchTarget {
+    WatchTarget {
+        id: "github:o/r:workflow:ci.yml:release/1.x".to_string(),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::GitHubWorkflow {
+            repo: RepoId::parse("o/r").unwrap(),
+            workflow: "ci.yml".into(),
+            branch: "release/1.x".into(),
+        },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+#[tokio::test]
+async fn reports_latest_meaningful_conclusion_with_run_link() {
+    // 最新的 run 被取消了, 看上一个
+    let (base, _) = spawn_stand_in(vec![(3, "cancelled"), (2, "failure"), (1, "success")]).await;
+    let p = GitHubWorkflowProvider::new(GitHubClient::new(None).with_base_url(base));
+
+    let event = p.check(&target()).await.unwrap().unwrap();
+    assert_eq!(event.event_type, EventType::GitHubWorkflow);
+    assert_eq!(event.source, Source::GitHub);
+    assert_eq!(event.subject, "o/r:ci.yml#release/1.x");
+    assert_eq!(event.new_value, "failure");
+    assert_eq!(
+        event.url.as_deref(),
+        Some("https://github.com/o/r/actions/runs/2")
+    );
+    assert_eq!(event.meta[META_RUN_NUMBER], "102");
+    assert_eq!(event.meta[META_HEAD_SHA], "sha2");
+}
+
+#[derive(Clone, Default)]
+struct RecordingNotifier {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    async fn notify(&self, event: &Event) -> AppResult<()> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn notifies_on_transitions_only() {
+    let (base, runs) = spawn_stand_in(vec![(1, "success")]).await;
+    let target_repo = InMemoryTargetRepository::new(vec![target()]);
+    let provider = GitHubWorkflowProvider::new(GitHubClient::new(None).with_base_url(base));
+    let store = InMemoryEventStore::new();
+    let notifier = RecordingNotifier::default();
+    let run_once = RunOnceUseCase {
+        targets: &target_repo,
+        provider: &provider,
+        handle_event: HandleEventUseCase {
+            store: &store,
+            notifier: &notifier,
+            publisher: None,
+            cooldown_seconds: 0,
+        },
+        concurrency: ConcurrencyLimits::default(),
+    };
+
+    // baseline: success; 再来一个 success 不算变化
+    run_once.execute().await.unwrap();
+    runs.lock().unwrap().insert(0, (2, "success"));
+    run_once.execute().await.unwrap();
+    assert!(notifier.events.lock().unwrap().is_empty());
+
+    // success -> failure -> success -> failure: 每次转变一个事件
+    for (id, conclusion) in [
+        (3, "failure"),
+        (4, "failure"),
+        (5, "success"),
+        (6, "failure"),
+    ] {
+        runs.lock().unwrap().insert(0, (id, conclusion));
+        run_once.execute().await.unwrap();
+    }
+
+    let events = notifier.events.lock().unwrap().clone();
+    let transitions: Vec<(Option<&str>, &str)> = events
+        .iter()
+        .map(|e| (e.old_value.as_deref(), e.new_value.as_str()))
+        .collect();
+    assert_eq!(
+        transitions,
+        [
+            (Some("success"), "failure"),
+            (Some("failure"), "success"),
+            (Some("success"), "failure"),
+        ]
+    );
+    assert_eq!(
+        events[2].url.as_deref(),
+        Some("https://github.com/o/r/actions/runs/6")
+    );
+}
+
+#[test]
+fn config_requires_workflow_and_branch() {
+    let cfg: Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 60
+targets:
+  - type: github_workflow
+    repo: o/r
+    workflow: ci.yml
+    branch: main
+"#,
+    )
+    .unwrap();
+    let targets = cfg.to_watch_targets().unwrap();
+    assert_eq!(targets[0].id, "github:o/r:workflow:ci.yml:main");
+
+    let bad: Config = serde_yaml::from_str(
+        "poll_interval_seconds: 60\ntargets:\n  - type: github_workflow\n    repo: o/r\n    workflow: \"\"\n    branch: main\n",
+    )
+    .unwrap();
+    assert!(bad.to_watch_targets().is_err());
+}
@@ -0,0 +1,111 @@
+use axum::{Router, http::StatusCode, http::Uri, response::IntoResponse};
+
+use repopulse::application::WatchProvider;
+use repopulse::domain::{EventType, Source, WatchKind, WatchTarget};
+use repopulse::infrastructure::api_endpoint::ApiEndpoint;
+use repopulse::infrastructure::go_module_provider::{GoModuleProvider, META_NEWER_MAJOR};
+
+/// 本地 GOPROXY: github.com/Acme/lib 有 v1 / v2+incompatible, 另有 /v2 模块; example.com/pseudo 只有伪版本
+async fn proxy(uri: Uri) -> impl IntoResponse {
+    let info = |v: &str, t: &str| format!(r#"{{"Version":"{v}","Time":"{t}"}}"#);
+    match uri.path() {
+        "/github.com/!acme/lib/@v/list" => {
+            "v1.0.0\nv1.2.0\nv1.3.0-rc.1\nv2.0.0+incompatible\n".into_response()
+        }
+        "/github.com/!acme/lib/@v/v1.2.0.info" => {
+            info("v1.2.0", "2024-01-02T00:00:00Z").into_response()
+        }
+        "/github.com/!acme/lib/@v/v1.3.0-rc.1.info" => {
+            info("v1.3.0-rc.1", "2024-02-01T00:00:00Z").into_response()
+        }
+        "/github.com/!acme/lib/v2/@v/list" => "v2.0.0\nv2.1.0\n".into_response(),
+        "/github.com/!acme/lib/v2/@v/v2.1.0.info" => {
+            info("v2.1.0", "2024-03-01T00:00:00Z").into_response()
+        }
+        "/example.com/pseudo/@v/list" => "".into_response(),
+        "/example.com/pseudo/@latest" => {
+            info("v0.0.0-20240101000000-abcdef123456", "2024-01-01T00:00:00Z").into_response()
+        }
+        _ => (StatusCode::NOT_FOUND, "not found").into_response(),
+    }
+}
+
+async fn spawn_stand_in() -> String {
+    let app = Router::new().fallback(proxy);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+fn target(module: &str, include_prereleases: bool) -> WatchTarget {
+    WatchTarget {
+        id: format!("go:{module}"),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::GoModule {
+            module: module.into(),
+            include_prereleases,
+        },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+async fn provider() -> GoModuleProvider {
+    GoModuleProvider::with_proxy(ApiEndpoint::new(spawn_stand_in().await))
+}
+
+#[tokio::test]
+async fn v1_path_ignores_incompatible_and_flags_v2_module() {
+    let p = provider().await;
+
+    let event = p
+        .check(&target("github.com/Acme/lib", false))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(event.event_type, EventType::GoModule);
+    assert_eq!(event.source, Source::Go);
+    assert_eq!(event.new_value, "v1.2.0");
+    assert_eq!(event.occurred_at.as_deref(), Some("2024-01-02T00:00:00Z"));
+    assert_eq!(event.meta[META_NEWER_MAJOR], "github.com/Acme/lib/v2");
+
+    let event = p
+        .check(&target("github.com/Acme/lib", true))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.new_value, "v1.3.0-rc.1");
+}
+
+#[tokio::test]
+async fn major_suffix_path_only_reports_its_major() {
+    let p = provider().await;
+
+    let event = p
+        .check(&target("github.com/Acme/lib/v2", false))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(event.new_value, "v2.1.0");
+    assert!(!event.meta.contains_key(META_NEWER_MAJOR));
+}
+
+#[tokio::test]
+async fn pseudo_version_only_module_uses_latest() {
+    let p = provider().await;
+
+    let event = p
+        .check(&target("example.com/pseudo", false))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(event.new_value, "v0.0.0-20240101000000-abcdef123456");
+}
@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::get,
+};
+use serde_json::json;
+
+use repopulse::application::usecases::{ConcurrencyLimits, HandleEventUseCase, RunOnceUseCase};
+use repopulse::application::{AppResult, Notifier, WatchProvider};
+use repopulse::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use repopulse::infrastructure::http_json_provider::HttpJsonProvider;
+use repopulse::infrastructure::memory_store::{InMemoryEventStore, InMemoryTargetRepository};
+use repopulse::interfaces::config::Config;
+
+async fn version(headers: HeaderMap) -> impl IntoResponse {
+    // 要求自定义 header, 验证 headers 被带上
+    if headers.get("x-api-key").and_then(|v| v.to_str().ok()) != Some("secret") {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    Json(json!({ "data": { "service": "api", "version": " 4.2.0 " } })).into_response()
+}
+
+async fn accepted() -> impl IntoResponse {
+    (
+        StatusCode::ACCEPTED,
+        Json(json!({ "components": [
+            { "name": "api", "status": "operational", "uptime": 99.9 },
+            { "name": "web", "status": "degraded", "uptime": 97.5 }
+        ]})),
+    )
+}
+
+async fn spawn_stand_in() -> String {
+    let app = Router::new()
+        .route("/version", get(version))
+        .route("/status", get(accepted));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+fn target(
+    url: String,
+    selector: &str,
+    headers: &[(&str, &str)],
+    expected_status: u16,
+    link_template: Option<&str>,
+) -> WatchTarget {
+    WatchTarget {
+        id: format!("http:{url}:json"),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::HttpJson {
+            url,
+            headers: headers
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<BTreeMap<_, _>>(),
+            selector: selector.into(),
+            expected_status,
+            link_template: link_template.map(str::to_string),
+        },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+#[tokio::test]
+async fn selects_value_with_headers_and_link_template() {
+    let base = spawn_stand_in().await;
+    let p = HttpJsonProvider::new();
+    let url = format!("{base}/version");
+
+    let event = p
+        .check(&target(
+            url.clone(),
+            "$.data.version",
+            &[("X-Api-Key", "secret")],
+            200,
+            Some("https://example.com/changelog#{value}"),
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(event.event_type, EventType::HttpJson);
+    assert_eq!(event.source, Source::Http);
+    assert_eq!(event.subject, url);
+    assert_eq!(event.new_value, "4.2.0");
+    assert_eq!(
+        event.url.as_deref(),
+        Some("https://example.com/changelog#4.2.0")
+    );
+
+    // 没带 header -> 401, 不是期望的状态码
+    let err = p
+        .check(&target(url, "$.data.version", &[], 200, None))
+        .await;
+    assert!(err.is_err());
+}
+
+#[tokio::test]
+async fn expected_status_and_multiple_matches() {
+    let base = spawn_stand_in().await;
+    let p = HttpJsonProvider::new();
+    let url = format!("{base}/status");
+
+    let event = p
+        .check(&target(
+            url.clone(),
+            "$.components[*].status",
+            &[],
+            202,
+            None,
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.new_value, "operational, degraded");
+    assert_eq!(event.url.as_deref(), Some(url.as_str()));
+
+    // 非字符串按 JSON 输出
+    let event = p
+        .check(&target(
+            url.clone(),
+            "$.components[?@.name == 'web'].uptime",
+            &[],
+            202,
+            None,
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.new_value, "97.5");
+
+    assert!(
+        p.check(&target(
+            url.clone(),
+            "$.components[0].status",
+            &[],
+            200,
+            None
+        ))
+        .await
+        .is_err()
+    );
+    assert!(
+        p.check(&target(url, "$.missing", &[], 202, None))
+            .await
+            .is_err()
+    );
+}
+
+#[derive(Clone, Default)]
+struct RecordingNotifier {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    async fn notify(&self, event: &Event) -> AppResult<()> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+type Indicator = Arc<Mutex<&'static str>>;
+
+async fn indicator(State(indicator): State<Indicator>) -> impl IntoResponse {
+    Json(json!({ "status": { "indicator": *indicator.lock().unwrap() } }))
+}
+
+#[tokio::test]
+async fn flapping_status_reports_every_change() {
+    let state: Indicator = Arc::new(Mutex::new("none"));
+    let app = Router::new()
+        .route("/status.json", get(indicator))
+        .with_state(state.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let t = target(
+        format!("http://{addr}/status.json"),
+        "$.status.indicator",
+        &[],
+        200,
+        None,
+    );
+    let target_repo = InMemoryTargetRepository::new(vec![t]);
+    let store = InMemoryEventStore::new();
+    let notifier = RecordingNotifier::default();
+    let run_once = RunOnceUseCase {
+        targets: &target_repo,
+        provider: &HttpJsonProvider::new(),
+        handle_event: HandleEventUseCase {
+            store: &store,
+            notifier: &notifier,
+            publisher: None,
+            cooldown_seconds: 0,
+        },
+        concurrency: ConcurrencyLimits::default(),
+    };
+
+    // baseline none, 之后: 故障 -> 恢复 -> 又一次故障
+    for status in ["none", "minor", "none", "minor"] {
+        *state.lock().unwrap() = status;
+        run_once.execute().await.unwrap();
+    }
+
+    let events = notifier.events.lock().unwrap().clone();
+    let changes: Vec<(Option<&str>, &str)> = events
+        .iter()
+        .map(|e| (e.old_value.as_deref(), e.new_value.as_str()))
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            (Some("none"), "minor"),
+            (Some("minor"), "none"),
+            (Some("none"), "minor")
+        ]
+    );
+}
+
+#[test]
+fn config_validates_selector_and_defaults_status() {
+    let cfg: Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 60
+targets:
+  - type: http_json
+    url: "https://status.example.com/api/v2/status.json"
+    selector: "$.status.indicator"
+    headers:
+      Accept: application/json
+"#,
+    )
+    .unwrap();
+    let targets = cfg.to_watch_targets().unwrap();
+    assert_eq!(
+        targets[0].id,
+        "http:https://status.example.com/api/v2/status.json:json"
+    );
+    let WatchKind::HttpJson {
+        expected_status,
+        headers,
+        ..
+    } = &targets[0].kind
+    else {
+        panic!("expected http_json kind");
+    };
+    assert_eq!(*expected_status, 200);
+    assert_eq!(headers["Accept"], "application/json");
+
+    let cfg: Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 60
+targets:
+  - type: http_json
+    url: "https://status.example.com/api/v2/status.json"
+    selector: "status.indicator["
+"#,
+    )
+    .unwrap();
+    assert!(cfg.to_watch_targets().is_err());
+}
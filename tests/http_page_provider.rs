@@ -0,0 +1,174 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{Router, extract::State, response::Html, routing::get};
+
+use repopulse::application::WatchProvider;
+use repopulse::domain::{EventType, Source, WatchKind, WatchTarget};
+use repopulse::infrastructure::http_page_provider::{HttpPageProvider, META_DIFF};
+use repopulse::infrastructure::memory_store::InMemoryEventStore;
+use repopulse::interfaces::config::Config;
+
+type Page = Arc<Mutex<String>>;
+
+async fn page(State(page): State<Page>) -> Html<String> {
+    Html(page.lock().unwrap().clone())
+}
+
+async fn spawn_stand_in(initial: &str) -> (String, Page) {
+    let content: Page = Arc::new(Mutex::new(initial.to_string()));
+    let app = Router::new()
+        .route("/changelog", get(page))
+        .with_state(content.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{}/changelog", addr), content)
+}
+
+fn changelog(nav: &str, entries: &str) -> String {
+    format!(
+        r#"<html><head><script>var t = "{nav}";</script></head><body>
+        <nav>{nav}</nav>
+        <main><ul>{entries}</ul></main>
+        <a href="/dl/tool-1.1.0.tar.gz">download</a>
+        </body></html>"#
+    )
+}
+
+fn target(url: &str, selector: Option<&str>, regex: Option<&str>) -> WatchTarget {
+    WatchTarget {
+        id: format!("http:{url}:page"),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::HttpPage {
+            url: url.into(),
+            headers: Default::default(),
+            selector: selector.map(str::to_string),
+            regex: regex.map(str::to_string),
+        },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+/// 条件请求缓存和文本快照用同一个内存 store
+fn provider() -> HttpPageProvider {
+    let store = Arc::new(InMemoryEventStore::new());
+    HttpPageProvider::new(store.clone(), store)
+}
+
+#[tokio::test]
+async fn selector_ignores_noise_and_reports_a_diff() {
+    let (url, content) =
+        spawn_stand_in(&changelog("visit 1", "<li>v1.0   fixed\n  bugs</li>")).await;
+    let p = provider();
+    let t = target(&url, Some("main li"), None);
+
+    let first = p.check(&t).await.unwrap().unwrap();
+    assert_eq!(first.event_type, EventType::HttpPage);
+    assert_eq!(first.source, Source::Http);
+    assert_eq!(first.new_value.len(), 16);
+    assert!(first.meta.is_empty());
+    // 快照在事件处理完后才保存
+    p.commit(&t).await.unwrap();
+
+    // 选择器外的变化 / 空白差异不影响 hash
+    *content.lock().unwrap() = changelog("visit 2", "<li>v1.0 fixed bugs</li>");
+    let same = p.check(&t).await.unwrap().unwrap();
+    assert_eq!(same.new_value, first.new_value);
+    assert!(same.meta.is_empty());
+
+    *content.lock().unwrap() = changelog(
+        "visit 3",
+        "<li>v1.1 new feature</li><li>v1.0 fixed bugs</li>",
+    );
+    let changed = p.check(&t).await.unwrap().unwrap();
+    assert_ne!(changed.new_value, first.new_value);
+    assert_eq!(changed.meta[META_DIFF], "+ v1.1 new feature");
+}
+
+#[tokio::test]
+async fn regex_capture_and_whole_page_text() {
+    let (url, _content) = spawn_stand_in(&changelog("home", "<li>v1.1</li>")).await;
+    let p = provider();
+
+    // regex 作用在 HTML 上, 取第 1 个捕获组
+    let by_regex = p
+        .check(&target(&url, None, Some(r#"href="[^"]*/(tool-[^"]+)""#)))
+        .await
+        .unwrap()
+        .unwrap();
+    let by_regex_again = p
+        .check(&target(&url, Some("a"), Some(r"tool-[\d.]+\.tar\.gz")))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(by_regex.new_value, by_regex_again.new_value);
+
+    assert!(
+        p.check(&target(&url, Some("main"), Some(r"\.zip")))
+            .await
+            .is_err()
+    );
+    assert!(p.check(&target(&url, Some("table"), None)).await.is_err());
+
+    // 整页文本不包含 <script>
+    let whole = p.check(&target(&url, None, None)).await.unwrap().unwrap();
+    let only_text = p
+        .check(&target(&url, Some("body"), None))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(whole.new_value, only_text.new_value);
+}
+
+#[tokio::test]
+async fn snapshot_only_moves_on_commit() {
+    let (url, content) = spawn_stand_in(&changelog("nav", "<li>v1.0</li>")).await;
+    let p = provider();
+    let t = target(&url, Some("main li"), None);
+    p.check(&t).await.unwrap();
+    p.commit(&t).await.unwrap();
+
+    // 事件没处理成功 (没 commit): 下一轮的 diff 仍然相对上次提交的页面
+    *content.lock().unwrap() = changelog("nav", "<li>v1.1</li><li>v1.0</li>");
+    p.check(&t).await.unwrap();
+    *content.lock().unwrap() = changelog("nav", "<li>v1.2</li><li>v1.1</li><li>v1.0</li>");
+    let retried = p.check(&t).await.unwrap().unwrap();
+    assert_eq!(retried.meta[META_DIFF], "+ v1.2\n+ v1.1");
+    p.commit(&t).await.unwrap();
+
+    let same = p.check(&t).await.unwrap().unwrap();
+    assert!(same.meta.is_empty());
+}
+
+#[test]
+fn config_rejects_invalid_css_selector() {
+    let cfg: Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 60
+targets:
+  - type: http_page
+    url: "https://example.com/changelog"
+    selector: "main >> li"
+"#,
+    )
+    .unwrap();
+    assert!(cfg.to_watch_targets().is_err());
+
+    let cfg: Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 60
+targets:
+  - type: http_page
+    url: "https://example.com/changelog"
+    selector: "main li"
+"#,
+    )
+    .unwrap();
+    let targets = cfg.to_watch_targets().unwrap();
+    assert_eq!(targets[0].id, "http:https://example.com/changelog:page");
+}
@@ -0,0 +1,197 @@
+use repopulse::application::usecases::{ConcurrencyLimits, HandleEventUseCase, RunOnceUseCase};
+use repopulse::application::{AppResult, EventStore, Notifier, WatchProvider};
+use repopulse::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use repopulse::infrastructure::memory_store::{InMemoryEventStore, InMemoryTargetRepository};
+
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+/// 每次 check 返回下一个版本号
+struct SequenceProvider {
+    values: Mutex<Vec<&'static str>>,
+}
+
+#[async_trait]
+impl WatchProvider for SequenceProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let value = self.values.lock().unwrap().remove(0).to_string();
+        let subject = target.kind.subject();
+        Ok(Some(Event {
+            event_id: Event::make_event_id(&EventType::NpmLatest, &subject, &value),
+            event_type: EventType::NpmLatest,
+            source: Source::Npm,
+            subject,
+            old_value: None,
+            new_value: value,
+            occurred_at: None,
+            detected_at: "0s_since_epoch".to_string(),
+            url: None,
+            meta: Default::default(),
+        }))
+    }
+}
+
+#[derive(Clone, Default)]
+struct RecordingNotifier {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    async fn notify(&self, event: &Event) -> AppResult<()> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn first_observation_is_baseline_and_later_events_carry_old_value() {
+    let target_repo = InMemoryTargetRepository::new(vec![WatchTarget {
+        id: "npm:whatsapp-web.js:latest".to_string(),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::NpmLatest {
+            package: "whatsapp-web.js".to_string(),
+        },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }]);
+    let provider = SequenceProvider {
+        values: Mutex::new(vec!["1.0.0", "1.0.0", "1.1.0"]),
+    };
+    let store = InMemoryEventStore::new();
+    let notifier = RecordingNotifier::default();
+
+    let run_once = RunOnceUseCase {
+        targets: &target_repo,
+        provider: &provider,
+        handle_event: HandleEventUseCase {
+            store: &store,
+            notifier: &notifier,
+            publisher: None,
+            cooldown_seconds: 0,
+        },
+        concurrency: ConcurrencyLimits::default(),
+    };
+
+    // baseline: 不通知
+    run_once.execute().await.unwrap();
+    assert!(notifier.events.lock().unwrap().is_empty());
+    assert_eq!(
+        store
+            .get_last_value("npm:whatsapp-web.js:latest")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("1.0.0")
+    );
+
+    // 无变化
+    run_once.execute().await.unwrap();
+    assert!(notifier.events.lock().unwrap().is_empty());
+
+    // 变化: 1.0.0 -> 1.1.0
+    run_once.execute().await.unwrap();
+    let events = notifier.events.lock().unwrap().clone();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].old_value.as_deref(), Some("1.0.0"));
+    assert_eq!(events[0].new_value, "1.1.0");
+    assert_eq!(
+        store
+            .get_last_value("npm:whatsapp-web.js:latest")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("1.1.0")
+    );
+}
+
+#[tokio::test]
+async fn value_returning_to_an_earlier_state_is_still_reported() {
+    let target_repo = InMemoryTargetRepository::new(vec![WatchTarget {
+        id: "npm:left-pad:latest".to_string(),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::NpmLatest {
+            package: "left-pad".to_string(),
+        },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }]);
+    // 发布 -> 回滚 -> 再发布: provider 给的 id 只看新值, 后两次会撞上前面的 id
+    let provider = SequenceProvider {
+        values: Mutex::new(vec!["1.0.0", "1.1.0", "1.0.0", "1.1.0"]),
+    };
+    let store = InMemoryEventStore::new();
+    let notifier = RecordingNotifier::default();
+    let run_once = RunOnceUseCase {
+        targets: &target_repo,
+        provider: &provider,
+        handle_event: HandleEventUseCase {
+            store: &store,
+            notifier: &notifier,
+            publisher: None,
+            cooldown_seconds: 0,
+        },
+        concurrency: ConcurrencyLimits::default(),
+    };
+
+    for _ in 0..4 {
+        run_once.execute().await.unwrap();
+    }
+
+    let events = notifier.events.lock().unwrap().clone();
+    let changes: Vec<(Option<&str>, &str)> = events
+        .iter()
+        .map(|e| (e.old_value.as_deref(), e.new_value.as_str()))
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            (Some("1.0.0"), "1.1.0"),
+            (Some("1.1.0"), "1.0.0"),
+            (Some("1.0.0"), "1.1.0")
+        ]
+    );
+    assert_ne!(events[0].event_id, events[2].event_id);
+}
+
+#[test]
+fn branch_targets_on_one_repo_get_distinct_ids() {
+    let cfg: repopulse::interfaces::config::Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 60
+targets:
+  - type: github_branch
+    repo: o/r
+    branch: main
+  - type: github_branch
+    repo: o/r
+    branch: release
+"#,
+    )
+    .unwrap();
+    let targets = cfg.to_watch_targets().unwrap();
+    let ids: Vec<&str> = targets.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(ids, ["github:o/r:branch:main", "github:o/r:branch:release"]);
+
+    // last value 按 id 存, 重复的 id 直接报错
+    let dup: repopulse::interfaces::config::Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 60
+targets:
+  - type: npm_latest
+    package: left-pad
+  - type: npm_latest
+    package: left-pad
+"#,
+    )
+    .unwrap();
+    let err = dup.to_watch_targets().unwrap_err().to_string();
+    assert!(
+        err.contains("duplicate target id: npm:left-pad:latest"),
+        "{err}"
+    );
+}
@@ -0,0 +1,297 @@
+use std::path::{Path, PathBuf};
+
+use repopulse::application::TargetRepository;
+use repopulse::domain::{WatchKind, WatchTarget};
+use repopulse::infrastructure::manifest_discovery::{ManifestSource, ManifestTargetRepository};
+use repopulse::interfaces::config::Config;
+
+/// 每个测试一个独立的临时目录
+fn temp_project(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "repopulse-discovery-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+fn ids(targets: &[WatchTarget]) -> Vec<&str> {
+    targets.iter().map(|t| t.id.as_str()).collect()
+}
+
+const CARGO_TOML: &str = r#"
+[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+serde = { version = "1", features = ["derive"] }
+tokio = "1" # runtime
+local-util = { path = "../util" }
+from-git = { git = "https://example.com/x.git" }
+json = { package = "serde_json", version = "1" }
+
+[dependencies.reqwest]
+version = "0.12"
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+
+[dev-dependencies]
+insta = "1"
+"#;
+
+const CARGO_LOCK: &str = r#"
+version = 4
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = [
+ "local-util",
+ "serde 1.0.200",
+ "tokio",
+]
+
+[[package]]
+name = "local-util"
+version = "0.1.0"
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "tokio"
+version = "1.40.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = [
+ "pin-project-lite",
+]
+"#;
+
+#[test]
+fn discovers_registry_dependencies_from_each_manifest_type() {
+    let dir = temp_project("types");
+    write(
+        &dir,
+        "web/package.json",
+        r#"{
+          "dependencies": { "react": "^18", "@scope/ui": "1.x", "local": "file:../local", "old": "npm:legacy-pkg@^2" },
+          "devDependencies": { "vitest": "^1" }
+        }"#,
+    );
+    write(
+        &dir,
+        "web/package-lock.json",
+        r#"{ "lockfileVersion": 3, "packages": { "": { "dependencies": { "react": "^18" } }, "node_modules/react": {} } }"#,
+    );
+    write(
+        &dir,
+        "web/node_modules/react/package.json",
+        r#"{ "dependencies": { "loose-envify": "^1" } }"#,
+    );
+    write(&dir, "Cargo.toml", CARGO_TOML);
+    write(&dir, "Cargo.lock", CARGO_LOCK);
+    write(
+        &dir,
+        "ml/requirements.txt",
+        "# pinned\n-r base.txt\nDjango==5.0 ; python_version >= '3.10'\nrequests[socks]>=2\nzope.interface\nmypkg @ https://example.com/mypkg.whl\n-e ./local\n",
+    );
+
+    let source = ManifestSource::new(&dir).with_labels(vec!["app".into()]);
+    let targets = source.discover();
+    assert_eq!(
+        ids(&targets),
+        [
+            "crates-io:libc:latest",
+            "crates-io:reqwest:latest",
+            "crates-io:serde:latest",
+            "crates-io:serde_json:latest",
+            "crates-io:tokio:latest",
+            "npm:@scope/ui:latest",
+            "npm:legacy-pkg:latest",
+            "npm:react:latest",
+            "pypi:django:latest",
+            "pypi:requests:latest",
+            "pypi:zope-interface:latest",
+        ]
+    );
+
+    let react = targets.iter().find(|t| t.id == "npm:react:latest").unwrap();
+    assert_eq!(
+        react.kind,
+        WatchKind::NpmLatest {
+            package: "react".into()
+        }
+    );
+    let web = dir.join("web").to_string_lossy().to_string();
+    assert_eq!(
+        react.labels,
+        [
+            "app".to_string(),
+            format!("manifest:{web}/package-lock.json"),
+            format!("manifest:{web}/package.json"),
+        ]
+    );
+
+    // dev 依赖只在打开 dev_dependencies 时才算
+    let with_dev = ManifestSource::new(&dir)
+        .with_dev_dependencies(true)
+        .discover();
+    assert!(ids(&with_dev).contains(&"npm:vitest:latest"));
+    assert!(ids(&with_dev).contains(&"crates-io:insta:latest"));
+}
+
+#[tokio::test]
+async fn rescans_when_a_manifest_changes_and_keeps_configured_targets() {
+    let dir = temp_project("refresh");
+    write(&dir, "requirements.txt", "flask\nruamel.yaml\n");
+    write(&dir, "Cargo.toml", "[dependencies]\nserde-json = \"1\"\n");
+    let configured = |id: &str, kind: WatchKind| WatchTarget {
+        id: id.into(),
+        enabled: true,
+        labels: vec!["configured".into()],
+        kind,
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    };
+    let repo = ManifestTargetRepository::new(
+        vec![
+            configured(
+                "pypi:Flask:latest",
+                WatchKind::PyPiLatest {
+                    project: "Flask".into(),
+                    include_prereleases: true,
+                },
+            ),
+            // 自定义 id 也算
+            configured(
+                "yaml",
+                WatchKind::PyPiLatest {
+                    project: "ruamel_yaml".into(),
+                    include_prereleases: false,
+                },
+            ),
+            configured(
+                "serde-json",
+                WatchKind::CratesIoLatest {
+                    name: "serde_json".into(),
+                    include_prereleases: false,
+                },
+            ),
+        ],
+        vec![ManifestSource::new(&dir)],
+    );
+
+    // 配置里已有的包优先 (按 registry 的规则比较包名, 不看 id)
+    let targets = repo.list_enabled_targets().await.unwrap();
+    assert_eq!(ids(&targets), ["pypi:Flask:latest", "yaml", "serde-json"]);
+    assert!(targets.iter().all(|t| t.labels == ["configured"]));
+
+    write(&dir, "requirements.txt", "flask\nhttpx>=0.27\n");
+    write(
+        &dir,
+        "tools/package.json",
+        r#"{ "dependencies": { "prettier": "^3" } }"#,
+    );
+    let targets = repo.list_enabled_targets().await.unwrap();
+    assert_eq!(
+        ids(&targets),
+        [
+            "pypi:Flask:latest",
+            "yaml",
+            "serde-json",
+            "npm:prettier:latest",
+            "pypi:httpx:latest"
+        ]
+    );
+
+    std::fs::remove_file(dir.join("tools/package.json")).unwrap();
+    let targets = repo.list_enabled_targets().await.unwrap();
+    assert_eq!(
+        ids(&targets),
+        [
+            "pypi:Flask:latest",
+            "yaml",
+            "serde-json",
+            "pypi:httpx:latest"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn same_package_spelled_differently_is_discovered_once() {
+    let dir = temp_project("spelling");
+    write(&dir, "api/Cargo.toml", "[dependencies]\nfoo_bar = \"1\"\n");
+    write(
+        &dir,
+        "api/worker/Cargo.toml",
+        "[dependencies]\nFoo-Bar = \"1\"\n",
+    );
+    write(&dir, "cli/Cargo.toml", "[dependencies]\nfoo-bar = \"1\"\n");
+    write(&dir, "api/requirements.txt", "Ruamel.Yaml\n");
+    write(&dir, "cli/requirements.txt", "ruamel-yaml\n");
+    let repo = ManifestTargetRepository::new(
+        vec![],
+        vec![
+            ManifestSource::new(dir.join("api")).with_labels(vec!["api".into()]),
+            ManifestSource::new(dir.join("cli")).with_labels(vec!["cli".into()]),
+        ],
+    );
+
+    let targets = repo.list_enabled_targets().await.unwrap();
+    assert_eq!(targets.len(), 2, "{:?}", ids(&targets));
+    for t in &targets {
+        assert!(t.labels.contains(&"api".to_string()), "{:?}", t.labels);
+        assert!(t.labels.contains(&"cli".to_string()), "{:?}", t.labels);
+    }
+    // 每个写法的 manifest 都记在标签里
+    let crate_labels = &targets
+        .iter()
+        .find(|t| t.id.starts_with("crates-io:"))
+        .unwrap()
+        .labels;
+    assert_eq!(
+        crate_labels
+            .iter()
+            .filter(|l| l.ends_with("Cargo.toml"))
+            .count(),
+        3
+    );
+}
+
+#[test]
+fn config_reads_discovery_sources() {
+    let cfg: Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 60
+targets: []
+discovery:
+  - path: "../my-app"
+    labels: ["my-app"]
+    dev_dependencies: true
+  - path: "services/api/Cargo.toml"
+"#,
+    )
+    .unwrap();
+    let discovery = cfg.discovery.unwrap();
+    assert_eq!(discovery.len(), 2);
+    assert_eq!(discovery[0].path, "../my-app");
+    assert_eq!(
+        discovery[0].labels.as_deref(),
+        Some(&["my-app".to_string()][..])
+    );
+    assert_eq!(discovery[0].dev_dependencies, Some(true));
+    assert_eq!(discovery[1].labels, None);
+}
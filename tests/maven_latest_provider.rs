@@ -0,0 +1,109 @@
+use axum::{Router, routing::get};
+
+use repopulse::application::WatchProvider;
+use repopulse::domain::{EventType, Source, WatchKind, WatchTarget};
+use repopulse::infrastructure::api_endpoint::ApiEndpoint;
+use repopulse::infrastructure::maven_latest_provider::MavenLatestProvider;
+
+const WITH_RELEASE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<metadata>
+  <groupId>com.example</groupId>
+  <artifactId>lib</artifactId>
+  <versioning>
+    <latest>2.1.0-SNAPSHOT</latest>
+    <release>2.0.1</release>
+    <versions>
+      <version>1.0.0</version>
+      <version>2.0.0</version>
+      <version>2.0.1</version>
+      <version>2.1.0-SNAPSHOT</version>
+    </versions>
+    <lastUpdated>20240102030405</lastUpdated>
+  </versioning>
+</metadata>"#;
+
+/// 没有 <release>: 从 <versions> 末尾取
+const WITHOUT_RELEASE: &str = r#"<metadata>
+  <versioning>
+    <versions>
+      <version>0.9</version>
+      <version>1.0</version>
+      <version>1.1-SNAPSHOT</version>
+    </versions>
+  </versioning>
+</metadata>"#;
+
+async fn spawn_stand_in() -> String {
+    let app = Router::new()
+        .route(
+            "/com/example/lib/maven-metadata.xml",
+            get(|| async { WITH_RELEASE }),
+        )
+        .route(
+            "/org/acme/tool/maven-metadata.xml",
+            get(|| async { WITHOUT_RELEASE }),
+        );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+fn target(group_id: &str, artifact_id: &str, include_snapshots: bool) -> WatchTarget {
+    WatchTarget {
+        id: format!("maven:{group_id}:{artifact_id}"),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::MavenLatest {
+            group_id: group_id.into(),
+            artifact_id: artifact_id.into(),
+            include_snapshots,
+        },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+#[tokio::test]
+async fn reports_release_and_ignores_snapshots_by_default() {
+    let base = spawn_stand_in().await;
+    let p = MavenLatestProvider::with_repository(ApiEndpoint::new(base.clone()));
+
+    let event = p
+        .check(&target("com.example", "lib", false))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(event.event_type, EventType::MavenLatest);
+    assert_eq!(event.source, Source::Maven);
+    assert_eq!(event.subject, "com.example:lib");
+    assert_eq!(event.new_value, "2.0.1");
+    assert_eq!(event.occurred_at.as_deref(), Some("2024-01-02T03:04:05Z"));
+    assert_eq!(event.url, Some(format!("{base}/com/example/lib/2.0.1/")));
+
+    let event = p
+        .check(&target("com.example", "lib", true))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.new_value, "2.1.0-SNAPSHOT");
+}
+
+#[tokio::test]
+async fn falls_back_to_versions_list_without_release() {
+    let base = spawn_stand_in().await;
+    let p = MavenLatestProvider::with_repository(ApiEndpoint::new(base));
+
+    let event = p
+        .check(&target("org.acme", "tool", false))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(event.new_value, "1.0");
+    assert_eq!(event.occurred_at, None);
+}
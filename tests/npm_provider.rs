@@ -0,0 +1,333 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use axum::{Json, Router, extract::State, routing::get};
+use serde_json::{Value, json};
+
+use repopulse::application::usecases::{ConcurrencyLimits, HandleEventUseCase, RunOnceUseCase};
+use repopulse::application::{AppResult, Notifier, WatchProvider};
+use repopulse::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use repopulse::infrastructure::api_endpoint::ApiEndpoint;
+use repopulse::infrastructure::memory_store::{InMemoryEventStore, InMemoryTargetRepository};
+use repopulse::infrastructure::npm_latest_provider::{
+    META_DEPRECATED, META_UNDEPRECATED, META_UNPUBLISHED, NpmDeprecatedProvider,
+    NpmDistTagProvider, NpmModifiedProvider, NpmRegistryClient, NpmUnpublishedProvider,
+};
+use repopulse::infrastructure::provider_registry::ProviderRegistry;
+use repopulse::interfaces::config::Config;
+
+/// 1.1.0 被 deprecate, 1.0.1 被 unpublish (time 里还在, versions 里没了)
+async fn spawn_stand_in() -> String {
+    let app = Router::new().route(
+        "/{pkg}",
+        get(|| async {
+            Json(json!({
+                "name": "demo",
+                "dist-tags": { "latest": "1.2.0", "next": "2.0.0-rc.1" },
+                "versions": {
+                    "1.0.0": {},
+                    "1.1.0": { "deprecated": "use 1.2.0" },
+                    "1.2.0": { "deprecated": "" },
+                    "2.0.0-rc.1": {},
+                },
+                "time": {
+                    "created": "2024-01-01T00:00:00.000Z",
+                    "modified": "2024-05-01T00:00:00.000Z",
+                    "1.0.0": "2024-01-01T00:00:00.000Z",
+                    "1.0.1": "2024-01-15T00:00:00.000Z",
+                    "1.1.0": "2024-02-01T00:00:00.000Z",
+                    "1.2.0": "2024-03-01T00:00:00.000Z",
+                    "2.0.0-rc.1": "2024-04-01T00:00:00.000Z",
+                },
+            }))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+fn target(kind: WatchKind) -> WatchTarget {
+    WatchTarget {
+        id: "npm:demo".to_string(),
+        enabled: true,
+        labels: vec![],
+        kind,
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+#[tokio::test]
+async fn reports_dist_tags_and_modified_time() {
+    let base = spawn_stand_in().await;
+    let registry = ApiEndpoint::new(base);
+
+    let p = NpmDistTagProvider::with_registry(registry.clone());
+    let event = p
+        .check(&target(WatchKind::NpmDistTag {
+            package: "demo".into(),
+            tag: "next".into(),
+        }))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.event_type, EventType::NpmDistTag);
+    assert_eq!(event.source, Source::Npm);
+    assert_eq!(event.subject, "demo@next");
+    assert_eq!(event.new_value, "2.0.0-rc.1");
+    assert_eq!(
+        event.occurred_at.as_deref(),
+        Some("2024-04-01T00:00:00.000Z")
+    );
+
+    // 不存在的 tag: 没有值
+    let missing = p
+        .check(&target(WatchKind::NpmDistTag {
+            package: "demo".into(),
+            tag: "beta".into(),
+        }))
+        .await
+        .unwrap();
+    assert!(missing.is_none());
+
+    let event = NpmModifiedProvider::with_registry(registry)
+        .check(&target(WatchKind::NpmModified {
+            package: "demo".into(),
+        }))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.event_type, EventType::NpmModified);
+    assert_eq!(event.new_value, "2024-05-01T00:00:00.000Z");
+}
+
+#[tokio::test]
+async fn deprecation_and_unpublish_events_say_which_versions_changed() {
+    let base = spawn_stand_in().await;
+    let registry = ApiEndpoint::new(base);
+
+    let p = NpmDeprecatedProvider::with_registry(registry.clone());
+    let t = target(WatchKind::NpmDeprecated {
+        package: "demo".into(),
+    });
+    let mut event = p.check(&t).await.unwrap().unwrap();
+    assert_eq!(event.event_type, EventType::NpmDeprecated);
+    assert_eq!(event.new_value, "1.1.0");
+
+    event.old_value = Some("1.0.0".into());
+    p.enrich(&t, &mut event).await.unwrap();
+    assert_eq!(event.meta[META_DEPRECATED], "1.1.0");
+    assert_eq!(event.meta[META_UNDEPRECATED], "1.0.0");
+
+    let p = NpmUnpublishedProvider::with_registry(registry);
+    let t = target(WatchKind::NpmUnpublished {
+        package: "demo".into(),
+    });
+    let mut event = p.check(&t).await.unwrap().unwrap();
+    assert_eq!(event.event_type, EventType::NpmUnpublished);
+    assert_eq!(event.new_value, "1.0.1");
+
+    event.old_value = Some(String::new());
+    p.enrich(&t, &mut event).await.unwrap();
+    assert_eq!(event.meta[META_UNPUBLISHED], "1.0.1");
+}
+
+/// 可变的 packument, 同时数请求次数
+#[derive(Clone)]
+struct Registry {
+    packument: Arc<Mutex<Value>>,
+    hits: Arc<AtomicUsize>,
+}
+
+impl Registry {
+    async fn spawn(packument: Value) -> (Self, String) {
+        let registry = Registry {
+            packument: Arc::new(Mutex::new(packument)),
+            hits: Arc::default(),
+        };
+        let app = Router::new()
+            .route(
+                "/{pkg}",
+                get(|State(r): State<Registry>| async move {
+                    r.hits.fetch_add(1, Ordering::SeqCst);
+                    Json(r.packument.lock().unwrap().clone())
+                }),
+            )
+            .with_state(registry.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        (registry, format!("http://{}", addr))
+    }
+}
+
+/// next 指向 `next`, `deprecated` 里的版本被 deprecate
+fn packument(next: &str, deprecated: &[&str]) -> Value {
+    let mut versions = serde_json::Map::new();
+    for v in ["1.0.0", "1.1.0", "2.0.0", "2.1.0"] {
+        let doc = match deprecated.contains(&v) {
+            true => json!({ "deprecated": "do not use" }),
+            false => json!({}),
+        };
+        versions.insert(v.into(), doc);
+    }
+    json!({
+        "name": "demo",
+        "dist-tags": { "latest": "1.1.0", "next": next },
+        "versions": versions,
+        "time": { "modified": "2024-05-01T00:00:00.000Z" },
+    })
+}
+
+#[tokio::test]
+async fn derived_targets_share_one_packument_fetch() {
+    let (registry, base) = Registry::spawn(packument("2.0.0", &["1.0.0"])).await;
+    let npm = NpmRegistryClient::new(ApiEndpoint::new(base));
+
+    let dist_tag = NpmDistTagProvider::with_client(npm.clone());
+    let deprecated = NpmDeprecatedProvider::with_client(npm.clone());
+    let modified = NpmModifiedProvider::with_client(npm);
+    let targets = [
+        target(WatchKind::NpmDistTag {
+            package: "demo".into(),
+            tag: "next".into(),
+        }),
+        target(WatchKind::NpmDeprecated {
+            package: "demo".into(),
+        }),
+        target(WatchKind::NpmModified {
+            package: "demo".into(),
+        }),
+    ];
+    let (a, b, c) = tokio::join!(
+        dist_tag.check(&targets[0]),
+        deprecated.check(&targets[1]),
+        modified.check(&targets[2]),
+    );
+    assert_eq!(a.unwrap().unwrap().new_value, "2.0.0");
+    assert_eq!(b.unwrap().unwrap().new_value, "1.0.0");
+    assert!(c.unwrap().is_some());
+    assert_eq!(registry.hits.load(Ordering::SeqCst), 1);
+}
+
+#[derive(Clone, Default)]
+struct RecordingNotifier {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    async fn notify(&self, event: &Event) -> AppResult<()> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn re_deprecation_and_dist_tag_move_back_are_reported() {
+    let (registry, base) = Registry::spawn(packument("2.0.0", &[])).await;
+    // 每轮都重新拉
+    let npm = NpmRegistryClient::new(ApiEndpoint::new(base)).with_ttl(Duration::ZERO);
+    let mut providers = ProviderRegistry::new();
+    providers.register_provider(NpmDistTagProvider::with_client(npm.clone()));
+    providers.register_provider(NpmDeprecatedProvider::with_client(npm));
+
+    let mut next = target(WatchKind::NpmDistTag {
+        package: "demo".into(),
+        tag: "next".into(),
+    });
+    next.id = "npm:demo:next".into();
+    let mut deprecated = target(WatchKind::NpmDeprecated {
+        package: "demo".into(),
+    });
+    deprecated.id = "npm:demo:deprecated".into();
+    let target_repo = InMemoryTargetRepository::new(vec![next, deprecated]);
+    let store = InMemoryEventStore::new();
+    let notifier = RecordingNotifier::default();
+    let run_once = RunOnceUseCase {
+        targets: &target_repo,
+        provider: &providers,
+        handle_event: HandleEventUseCase {
+            store: &store,
+            notifier: &notifier,
+            publisher: None,
+            cooldown_seconds: 0,
+        },
+        concurrency: ConcurrencyLimits::default(),
+    };
+
+    // baseline, 之后 next 来回移动, 1.1.0 deprecate -> 撤销 -> 再 deprecate
+    for (tag, deprecated) in [
+        ("2.0.0", &[][..]),
+        ("2.1.0", &["1.1.0"][..]),
+        ("2.0.0", &[][..]),
+        ("2.1.0", &["1.1.0"][..]),
+    ] {
+        *registry.packument.lock().unwrap() = packument(tag, deprecated);
+        run_once.execute().await.unwrap();
+    }
+
+    let events = notifier.events.lock().unwrap().clone();
+    let changes = |t: EventType| -> Vec<String> {
+        events
+            .iter()
+            .filter(|e| e.event_type == t)
+            .map(|e| e.new_value.clone())
+            .collect()
+    };
+    assert_eq!(changes(EventType::NpmDistTag), ["2.1.0", "2.0.0", "2.1.0"]);
+    assert_eq!(changes(EventType::NpmDeprecated), ["1.1.0", "", "1.1.0"]);
+}
+
+#[test]
+fn npm_options_add_derived_targets() {
+    let cfg: Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 60
+targets:
+  - type: npm_latest
+    package: "@scope/pkg"
+    dist_tags: ["next", "canary"]
+    deprecations: true
+    modified: true
+    labels: ["js"]
+"#,
+    )
+    .unwrap();
+
+    let targets = cfg.to_watch_targets().unwrap();
+    let ids: Vec<&str> = targets.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        [
+            "npm:@scope/pkg:latest",
+            "npm:@scope/pkg:next",
+            "npm:@scope/pkg:canary",
+            "npm:@scope/pkg:deprecated",
+            "npm:@scope/pkg:modified",
+        ]
+    );
+    assert_eq!(
+        targets[1].kind,
+        WatchKind::NpmDistTag {
+            package: "@scope/pkg".into(),
+            tag: "next".into(),
+        }
+    );
+    assert!(targets.iter().all(|t| t.labels == ["js"]));
+
+    let bad: Config = serde_yaml::from_str(
+        "poll_interval_seconds: 60\ntargets:\n  - type: npm_latest\n    package: p\n    dist_tags: [latest]\n",
+    )
+    .unwrap();
+    assert!(bad.to_watch_targets().is_err());
+}
@@ -0,0 +1,285 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde_json::json;
+
+use repopulse::application::usecases::{ConcurrencyLimits, HandleEventUseCase, RunOnceUseCase};
+use repopulse::application::{AppResult, Notifier, ProviderStateStore, WatchProvider};
+use repopulse::domain::{
+    Endpoint, Event, EventType, ImageRef, NameFilter, Source, WatchKind, WatchTarget,
+};
+use repopulse::infrastructure::memory_store::{InMemoryEventStore, InMemoryTargetRepository};
+use repopulse::infrastructure::oci_registry_provider::{
+    META_ADDED, META_REMOVED, OciDigestProvider, OciRegistryClient, OciTagsProvider,
+};
+
+#[derive(Clone)]
+struct Stand {
+    base: String,
+    token_requests: Arc<AtomicUsize>,
+    /// 20-alpine 当前指向的 digest
+    digest: Arc<Mutex<&'static str>>,
+}
+
+/// 没带 token 时返回 401 + Bearer challenge
+fn unauthorized(stand: &Stand, headers: &HeaderMap) -> Option<Response> {
+    if headers.get("authorization").and_then(|v| v.to_str().ok()) == Some("Bearer t1") {
+        return None;
+    }
+    let challenge = format!(
+        r#"Bearer realm="{}/token",service="stand-in",scope="repository:library/node:pull""#,
+        stand.base
+    );
+    Some((StatusCode::UNAUTHORIZED, [("www-authenticate", challenge)]).into_response())
+}
+
+async fn spawn_stand_in() -> Stand {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let stand = Stand {
+        base: format!("http://{}", listener.local_addr().unwrap()),
+        token_requests: Arc::new(AtomicUsize::new(0)),
+        digest: Arc::new(Mutex::new("sha256:abc")),
+    };
+    let app = Router::new()
+        .route(
+            "/token",
+            get(
+                |State(s): State<Stand>, Query(q): Query<Vec<(String, String)>>| async move {
+                    s.token_requests.fetch_add(1, Ordering::SeqCst);
+                    assert!(q.contains(&("scope".into(), "repository:library/node:pull".into())));
+                    assert!(q.contains(&("service".into(), "stand-in".into())));
+                    Json(json!({ "token": "t1", "expires_in": 300 }))
+                },
+            ),
+        )
+        .route(
+            "/v2/library/node/manifests/20-alpine",
+            get(|State(s): State<Stand>, headers: HeaderMap| async move {
+                if let Some(r) = unauthorized(&s, &headers) {
+                    return r;
+                }
+                let digest = *s.digest.lock().unwrap();
+                ([("docker-content-digest", digest)], "{}").into_response()
+            }),
+        )
+        .route(
+            "/v2/library/node/tags/list",
+            get(
+                |State(s): State<Stand>,
+                 headers: HeaderMap,
+                 Query(q): Query<Vec<(String, String)>>| async move {
+                    if let Some(r) = unauthorized(&s, &headers) {
+                        return r;
+                    }
+                    // 两页
+                    if q.iter().any(|(k, _)| k == "last") {
+                        return Json(
+                            json!({ "name": "library/node", "tags": ["22-alpine", "latest"] }),
+                        )
+                        .into_response();
+                    }
+                    (
+                        [(
+                            "link",
+                            r#"</v2/library/node/tags/list?last=20-alpine&n=1000>; rel="next""#,
+                        )],
+                        Json(json!({ "name": "library/node", "tags": ["18-alpine", "20-alpine"] })),
+                    )
+                        .into_response()
+                },
+            ),
+        )
+        .with_state(stand.clone());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    stand
+}
+
+fn target(stand: &Stand, kind: WatchKind) -> WatchTarget {
+    WatchTarget {
+        id: "oci:node".to_string(),
+        enabled: true,
+        labels: vec![],
+        kind,
+        schedule: None,
+        endpoint: Some(Endpoint {
+            base_url: Some(stand.base.clone()),
+            ..Default::default()
+        }),
+        version_policy: None,
+    }
+}
+
+#[test]
+fn parses_docker_hub_and_registry_references() {
+    let (image, tag) = ImageRef::parse("node:20-alpine").unwrap();
+    assert_eq!(image.as_str(), "docker.io/library/node");
+    assert_eq!(tag.as_deref(), Some("20-alpine"));
+
+    let (image, tag) = ImageRef::parse("ghcr.io/owner/app").unwrap();
+    assert_eq!(image.registry, "ghcr.io");
+    assert_eq!(image.repository, "owner/app");
+    assert_eq!(tag, None);
+
+    let (image, tag) = ImageRef::parse("localhost:5000/app:1.0").unwrap();
+    assert_eq!(image.registry, "localhost:5000");
+    assert_eq!(tag.as_deref(), Some("1.0"));
+}
+
+#[tokio::test]
+async fn reports_tag_digest_after_token_auth() {
+    let stand = spawn_stand_in().await;
+    let client = OciRegistryClient::new();
+    let p = OciDigestProvider::new(client.clone());
+    let (image, _) = ImageRef::parse("node").unwrap();
+    let t = target(
+        &stand,
+        WatchKind::OciDigest {
+            image,
+            tag: "20-alpine".into(),
+        },
+    );
+
+    let event = p.check(&t).await.unwrap().unwrap();
+    assert_eq!(event.event_type, EventType::OciDigest);
+    assert_eq!(event.source, Source::Oci);
+    assert_eq!(event.subject, "docker.io/library/node:20-alpine");
+    assert_eq!(event.new_value, "sha256:abc");
+    assert_eq!(event.url.as_deref(), Some("https://hub.docker.com/_/node"));
+
+    // token 被缓存, 第二次不再换
+    p.check(&t).await.unwrap().unwrap();
+    assert_eq!(stand.token_requests.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn reports_filtered_tag_list_across_pages() {
+    let stand = spawn_stand_in().await;
+    let store = Arc::new(InMemoryEventStore::new());
+    let p = OciTagsProvider::new(OciRegistryClient::new(), store.clone());
+    let (image, _) = ImageRef::parse("node").unwrap();
+    let t = target(
+        &stand,
+        WatchKind::OciTags {
+            image,
+            filter: Some(NameFilter::Glob {
+                pattern: "*-alpine".into(),
+            }),
+        },
+    );
+
+    // 上次处理过的列表
+    store
+        .set_provider_state(&t.id, "16-alpine, 18-alpine, 20-alpine")
+        .await
+        .unwrap();
+
+    // value 只有数量 + hash, 完整列表不进 value / event id
+    let event = p.check(&t).await.unwrap().unwrap();
+    assert_eq!(event.event_type, EventType::OciTags);
+    assert!(
+        event.new_value.starts_with("3 tags @ "),
+        "{}",
+        event.new_value
+    );
+    assert!(!event.event_id.contains("18-alpine"));
+    assert_eq!(event.meta[META_ADDED], "22-alpine");
+    assert_eq!(event.meta[META_REMOVED], "16-alpine");
+
+    // 没 commit 时仍和旧列表比较; commit 之后没有变化
+    let again = p.check(&t).await.unwrap().unwrap();
+    assert_eq!(again.meta[META_ADDED], "22-alpine");
+    p.commit(&t).await.unwrap();
+    let unchanged = p.check(&t).await.unwrap().unwrap();
+    assert_eq!(unchanged.new_value, event.new_value);
+    assert!(unchanged.meta.is_empty());
+}
+
+#[tokio::test]
+async fn tokens_are_not_shared_between_credentials() {
+    let stand = spawn_stand_in().await;
+    let p = OciDigestProvider::new(OciRegistryClient::new());
+    let (image, _) = ImageRef::parse("node").unwrap();
+    let with_user = |user: &str, token: &str| {
+        let mut t = target(
+            &stand,
+            WatchKind::OciDigest {
+                image: image.clone(),
+                tag: "20-alpine".into(),
+            },
+        );
+        let endpoint = t.endpoint.as_mut().unwrap();
+        endpoint.username = Some(user.into());
+        endpoint.token = Some(token.into());
+        t
+    };
+
+    // 同一个 registry + scope, 凭据不同: 各换各的 token
+    p.check(&with_user("alice", "pw-a")).await.unwrap();
+    p.check(&with_user("bob", "pw-b")).await.unwrap();
+    p.check(&with_user("alice", "pw-other")).await.unwrap();
+    assert_eq!(stand.token_requests.load(Ordering::SeqCst), 3);
+
+    p.check(&with_user("alice", "pw-a")).await.unwrap();
+    assert_eq!(stand.token_requests.load(Ordering::SeqCst), 3);
+}
+
+#[derive(Clone, Default)]
+struct RecordingNotifier {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    async fn notify(&self, event: &Event) -> AppResult<()> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn digest_rollback_is_reported() {
+    let stand = spawn_stand_in().await;
+    let (image, _) = ImageRef::parse("node").unwrap();
+    let t = target(
+        &stand,
+        WatchKind::OciDigest {
+            image,
+            tag: "20-alpine".into(),
+        },
+    );
+    let target_repo = InMemoryTargetRepository::new(vec![t]);
+    let store = InMemoryEventStore::new();
+    let notifier = RecordingNotifier::default();
+    let provider = OciDigestProvider::new(OciRegistryClient::new());
+    let run_once = RunOnceUseCase {
+        targets: &target_repo,
+        provider: &provider,
+        handle_event: HandleEventUseCase {
+            store: &store,
+            notifier: &notifier,
+            publisher: None,
+            cooldown_seconds: 0,
+        },
+        concurrency: ConcurrencyLimits::default(),
+    };
+
+    // baseline abc, 之后: 新镜像 -> 回滚 -> 再推新镜像
+    for digest in ["sha256:abc", "sha256:def", "sha256:abc", "sha256:def"] {
+        *stand.digest.lock().unwrap() = digest;
+        run_once.execute().await.unwrap();
+    }
+
+    let events = notifier.events.lock().unwrap().clone();
+    let digests: Vec<&str> = events.iter().map(|e| e.new_value.as_str()).collect();
+    assert_eq!(digests, ["sha256:def", "sha256:abc", "sha256:def"]);
+}
@@ -0,0 +1,211 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::{Json, Router, extract::State, routing::post};
+use serde_json::{Value, json};
+
+use repopulse::application::usecases::{ConcurrencyLimits, HandleEventUseCase, RunOnceUseCase};
+use repopulse::application::{AppResult, Notifier, WatchProvider};
+use repopulse::domain::{Event, EventType, Source, WatchKind, WatchTarget};
+use repopulse::infrastructure::api_endpoint::ApiEndpoint;
+use repopulse::infrastructure::memory_store::{InMemoryEventStore, InMemoryTargetRepository};
+use repopulse::infrastructure::osv_provider::{
+    META_ADVISORY_ID, META_AFFECTED, META_ALIASES, META_FIXED, META_SEVERITY, OsvAdvisoryProvider,
+};
+use repopulse::interfaces::config::Config;
+
+type Vulns = Arc<Mutex<Vec<Value>>>;
+
+/// 每页一条, 用 next_page_token 翻页
+async fn query(State(vulns): State<Vulns>, Json(body): Json<Value>) -> Json<Value> {
+    assert_eq!(body["package"]["ecosystem"], "npm");
+    assert_eq!(body["package"]["name"], "demo");
+    let vulns = vulns.lock().unwrap().clone();
+    let page: usize = body["page_token"]
+        .as_str()
+        .map(|t| t.parse().unwrap())
+        .unwrap_or(0);
+    let mut resp = json!({ "vulns": vulns.get(page).into_iter().collect::<Vec<_>>() });
+    if page + 1 < vulns.len() {
+        resp["next_page_token"] = json!((page + 1).to_string());
+    }
+    Json(resp)
+}
+
+async fn spawn_stand_in(vulns: Vec<Value>) -> (String, Vulns) {
+    let vulns: Vulns = Arc::new(Mutex::new(vulns));
+    let app = Router::new()
+        .route("/v1/query", post(query))
+        .with_state(vulns.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{}", addr), vulns)
+}
+
+fn ghsa(id: &str, published: &str) -> Value {
+    json!({
+        "id": id,
+        "summary": format!("{id} in demo"),
+        "aliases": ["CVE-2024-0001"],
+        "published": published,
+        "modified": published,
+        "database_specific": { "severity": "HIGH" },
+        "affected": [
+            {
+                "package": { "ecosystem": "npm", "name": "demo" },
+                "ranges": [{
+                    "type": "SEMVER",
+                    "events": [
+                        { "introduced": "0" }, { "fixed": "1.2.3" },
+                        { "introduced": "2.0.0" }, { "fixed": "2.0.1" },
+                    ],
+                }],
+            },
+            {
+                "package": { "ecosystem": "npm", "name": "other" },
+                "ranges": [{ "type": "SEMVER", "events": [{ "introduced": "0" }, { "fixed": "9.9.9" }] }],
+            },
+        ],
+    })
+}
+
+fn target() -> WatchTarget {
+    WatchTarget {
+        id: "osv:npm:demo".to_string(),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::Advisories {
+            ecosystem: "npm".into(),
+            package: "demo".into(),
+        },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+#[tokio::test]
+async fn reports_every_advisory_oldest_first_with_details() {
+    let (base, _) = spawn_stand_in(vec![
+        ghsa("GHSA-new", "2024-03-01T00:00:00Z"),
+        json!({
+            "id": "OSV-old",
+            "published": "2024-01-01T00:00:00Z",
+            "severity": [{ "type": "CVSS_V3", "score": "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H" }],
+            "affected": [{ "package": { "ecosystem": "npm", "name": "demo" },
+                           "ranges": [{ "type": "SEMVER", "events": [{ "introduced": "1.0.0" }] }] }],
+        }),
+    ])
+    .await;
+    let p = OsvAdvisoryProvider::with_endpoint(ApiEndpoint::new(base.clone()));
+
+    let items = p.check_items(&target()).await.unwrap();
+    let ids: Vec<&str> = items.iter().map(|e| e.new_value.as_str()).collect();
+    assert_eq!(ids, ["OSV-old", "GHSA-new"]);
+    assert_eq!(items[0].meta[META_AFFECTED], ">=1.0.0");
+    assert!(items[0].meta[META_SEVERITY].starts_with("CVSS_V3 CVSS:3.1/"));
+
+    let e = &items[1];
+    assert_eq!(e.event_type, EventType::Advisory);
+    assert_eq!(e.source, Source::Osv);
+    assert_eq!(e.subject, "demo");
+    assert!(e.is_high_priority());
+    assert_eq!(e.meta[META_ADVISORY_ID], "GHSA-new");
+    assert_eq!(e.meta[META_SEVERITY], "HIGH");
+    assert_eq!(e.meta[META_ALIASES], "CVE-2024-0001");
+    // 其它包的范围不算进来
+    assert_eq!(e.meta[META_AFFECTED], "<1.2.3; >=2.0.0 <2.0.1");
+    assert_eq!(e.meta[META_FIXED], "1.2.3, 2.0.1");
+    assert_eq!(e.url, Some(format!("{base}/v1/vulns/GHSA-new")));
+}
+
+#[derive(Clone, Default)]
+struct RecordingNotifier {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    async fn notify(&self, event: &Event) -> AppResult<()> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn new_advisories_skip_the_notification_cooldown() {
+    let (base, vulns) = spawn_stand_in(vec![ghsa("GHSA-1", "2024-01-01T00:00:00Z")]).await;
+    let target_repo = InMemoryTargetRepository::new(vec![target()]);
+    let provider = OsvAdvisoryProvider::with_endpoint(ApiEndpoint::new(base));
+    let store = InMemoryEventStore::new();
+    let notifier = RecordingNotifier::default();
+    let run_once = RunOnceUseCase {
+        targets: &target_repo,
+        provider: &provider,
+        handle_event: HandleEventUseCase {
+            store: &store,
+            notifier: &notifier,
+            publisher: None,
+            cooldown_seconds: 3600,
+        },
+        concurrency: ConcurrencyLimits::default(),
+    };
+
+    // baseline: 已有公告不通知
+    run_once.execute().await.unwrap();
+    assert!(notifier.events.lock().unwrap().is_empty());
+
+    vulns.lock().unwrap().extend([
+        ghsa("GHSA-2", "2024-02-01T00:00:00Z"),
+        ghsa("GHSA-3", "2024-03-01T00:00:00Z"),
+    ]);
+    run_once.execute().await.unwrap();
+
+    let events = notifier.events.lock().unwrap().clone();
+    let ids: Vec<&str> = events.iter().map(|e| e.new_value.as_str()).collect();
+    assert_eq!(ids, ["GHSA-2", "GHSA-3"]);
+}
+
+#[test]
+fn advisories_option_adds_an_osv_target() {
+    let cfg: Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 60
+targets:
+  - type: maven_latest
+    artifact: "org.apache.logging.log4j:log4j-core"
+    advisories: true
+  - type: advisories
+    ecosystem: PyPI
+    package: django
+"#,
+    )
+    .unwrap();
+
+    let targets = cfg.to_watch_targets().unwrap();
+    let ids: Vec<&str> = targets.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        [
+            "maven:org.apache.logging.log4j:log4j-core:latest",
+            "osv:Maven:org.apache.logging.log4j:log4j-core",
+            "osv:PyPI:django",
+        ]
+    );
+    assert_eq!(
+        targets[1].kind,
+        WatchKind::Advisories {
+            ecosystem: "Maven".into(),
+            package: "org.apache.logging.log4j:log4j-core".into(),
+        }
+    );
+
+    let bad: Config = serde_yaml::from_str(
+        "poll_interval_seconds: 60\ntargets:\n  - type: github_release\n    repo: o/r\n    advisories: true\n",
+    )
+    .unwrap();
+    assert!(bad.to_watch_targets().is_err());
+}
@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use repopulse::application::{AppResult, WatchProvider};
+use repopulse::domain::{Event, EventType, WatchKind, WatchTarget};
+use repopulse::infrastructure::provider_registry::ProviderRegistry;
+
+/// 模拟库使用方自己实现的 provider
+struct ChangelogProvider;
+
+#[async_trait]
+impl WatchProvider for ChangelogProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let WatchKind::Custom {
+            subject, params, ..
+        } = &target.kind
+        else {
+            return Ok(None);
+        };
+        let new_value = params.get("version").cloned().unwrap_or_default();
+        Ok(Some(Event {
+            event_id: Event::make_event_id(&EventType::Custom, subject, &new_value),
+            event_type: EventType::Custom,
+            source: target.kind.source(),
+            subject: subject.clone(),
+            old_value: None,
+            new_value,
+            occurred_at: None,
+            detected_at: "0s_since_epoch".to_string(),
+            url: None,
+            meta: Default::default(),
+        }))
+    }
+}
+
+fn custom_target(provider: &str) -> WatchTarget {
+    WatchTarget {
+        id: format!("{provider}:internal-service"),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::Custom {
+            provider: provider.to_string(),
+            subject: "internal-service".to_string(),
+            params: BTreeMap::from([("version".to_string(), "3.1.0".to_string())]),
+        },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+#[tokio::test]
+async fn dispatches_custom_target_to_registered_provider() {
+    let registry = ProviderRegistry::new().with("changelog", Arc::new(ChangelogProvider));
+
+    let event = registry
+        .check(&custom_target("changelog"))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(event.subject, "internal-service");
+    assert_eq!(event.new_value, "3.1.0");
+}
+
+#[tokio::test]
+async fn unregistered_kind_is_an_error() {
+    let registry = ProviderRegistry::new().with("changelog", Arc::new(ChangelogProvider));
+
+    assert!(registry.check(&custom_target("unknown")).await.is_err());
+}
@@ -0,0 +1,179 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::{Json, Router, routing::get};
+use serde_json::json;
+
+use repopulse::application::usecases::{ConcurrencyLimits, HandleEventUseCase, RunOnceUseCase};
+use repopulse::application::{AppResult, EventStore, Notifier, WatchProvider};
+use repopulse::domain::{Event, EventType, Pep440Version, Source, WatchKind, WatchTarget};
+use repopulse::infrastructure::api_endpoint::ApiEndpoint;
+use repopulse::infrastructure::memory_store::{InMemoryEventStore, InMemoryTargetRepository};
+use repopulse::infrastructure::pypi_provider::{
+    META_YANKED, PyPiLatestProvider, PyPiYankedProvider,
+};
+
+/// 1.10.0 整个被 yank; 2.0.0rc1 是 pre-release; 3.0 还没有文件
+async fn spawn_stand_in() -> String {
+    let file = |yanked: bool, at: &str| json!({ "yanked": yanked, "upload_time_iso_8601": at });
+    let body = json!({
+        "info": { "name": "demo", "version": "1.9.post1" },
+        "releases": {
+            "1.2": [file(false, "2023-01-01T00:00:00Z")],
+            "1.9": [file(false, "2024-01-01T00:00:00Z")],
+            "1.9.post1": [file(false, "2024-01-03T00:00:00Z"), file(false, "2024-01-02T00:00:00Z")],
+            "1.10.0": [file(true, "2024-02-01T00:00:00Z"), file(true, "2024-02-01T00:00:00Z")],
+            "2.0.0rc1": [file(false, "2024-03-01T00:00:00Z")],
+            "3.0": [],
+        },
+    });
+    let app = Router::new().route(
+        "/pypi/demo/json",
+        get(move || {
+            let body = body.clone();
+            async move { Json(body) }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+fn target(kind: WatchKind) -> WatchTarget {
+    WatchTarget {
+        id: "pypi:demo".to_string(),
+        enabled: true,
+        labels: vec![],
+        kind,
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+#[test]
+fn pep440_ordering() {
+    let ordered = [
+        "1.0.dev1",
+        "1.0a1",
+        "1.0b2",
+        "1.0rc1",
+        "1.0",
+        "1.0.post1",
+        "1.1",
+        "1!0.1",
+    ];
+    for w in ordered.windows(2) {
+        let (a, b) = (
+            Pep440Version::parse(w[0]).unwrap(),
+            Pep440Version::parse(w[1]).unwrap(),
+        );
+        assert!(a < b, "{} < {}", w[0], w[1]);
+    }
+    assert_eq!(
+        Pep440Version::parse("1.0.0")
+            .unwrap()
+            .cmp(&Pep440Version::parse("1.0").unwrap()),
+        std::cmp::Ordering::Equal
+    );
+    assert!(Pep440Version::parse("1.0-alpha.1").unwrap().is_prerelease());
+    assert!(Pep440Version::parse("not-a-version").is_none());
+}
+
+#[tokio::test]
+async fn reports_highest_stable_release_by_pep440() {
+    let base = spawn_stand_in().await;
+    let p = PyPiLatestProvider::with_index(ApiEndpoint::new(base.clone()));
+
+    let event = p
+        .check(&target(WatchKind::PyPiLatest {
+            project: "demo".into(),
+            include_prereleases: false,
+        }))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(event.event_type, EventType::PyPiLatest);
+    assert_eq!(event.source, Source::PyPi);
+    assert_eq!(event.new_value, "1.9.post1");
+    assert_eq!(event.occurred_at.as_deref(), Some("2024-01-02T00:00:00Z"));
+    assert_eq!(event.url, Some(format!("{base}/project/demo/1.9.post1/")));
+
+    let event = p
+        .check(&target(WatchKind::PyPiLatest {
+            project: "demo".into(),
+            include_prereleases: true,
+        }))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.new_value, "2.0.0rc1");
+}
+
+#[tokio::test]
+async fn reports_yanked_releases() {
+    let base = spawn_stand_in().await;
+    let p = PyPiYankedProvider::with_index(ApiEndpoint::new(base));
+    let t = target(WatchKind::PyPiYanked {
+        project: "demo".into(),
+    });
+
+    let mut event = p.check(&t).await.unwrap().unwrap();
+    assert_eq!(event.event_type, EventType::PyPiYanked);
+    assert_eq!(event.new_value, "1.10.0");
+
+    event.old_value = Some(String::new());
+    p.enrich(&t, &mut event).await.unwrap();
+    assert_eq!(event.meta[META_YANKED], "1.10.0");
+}
+
+#[derive(Clone, Default)]
+struct RecordingNotifier {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    async fn notify(&self, event: &Event) -> AppResult<()> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn re_yank_after_unyank_is_reported_again() {
+    let base = spawn_stand_in().await;
+    let t = target(WatchKind::PyPiYanked {
+        project: "demo".into(),
+    });
+    let target_repo = InMemoryTargetRepository::new(vec![t.clone()]);
+    let store = InMemoryEventStore::new();
+    let provider = PyPiYankedProvider::with_index(ApiEndpoint::new(base));
+    let notifier = RecordingNotifier::default();
+    let run_once = RunOnceUseCase {
+        targets: &target_repo,
+        provider: &provider,
+        handle_event: HandleEventUseCase {
+            store: &store,
+            notifier: &notifier,
+            publisher: None,
+            cooldown_seconds: 0,
+        },
+        concurrency: ConcurrencyLimits::default(),
+    };
+
+    // "" -> "1.10.0" 两次 (中间被 unyank 过)
+    for _ in 0..2 {
+        store.set_last_value(&t.id, "").await.unwrap();
+        run_once.execute().await.unwrap();
+    }
+
+    let events = notifier.events.lock().unwrap().clone();
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|e| e.meta[META_YANKED] == "1.10.0"));
+    assert_ne!(events[0].event_id, events[1].event_id);
+}
@@ -0,0 +1,109 @@
+use repopulse::application::Scheduler;
+use repopulse::domain::{Schedule, WatchKind, WatchTarget};
+use repopulse::interfaces::config::{Config, DurationCfg};
+
+fn npm_target(package: &str, schedule: Option<Schedule>) -> WatchTarget {
+    WatchTarget {
+        id: format!("npm:{package}:latest"),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::NpmLatest {
+            package: package.to_string(),
+        },
+        schedule,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+#[test]
+fn targets_follow_their_own_interval_or_the_default() {
+    let targets = vec![
+        npm_target("busy", Some(Schedule::interval(60))),
+        npm_target("default", None),
+    ];
+    let mut scheduler = Scheduler::new(600);
+
+    // 从未检查过: 都到期
+    assert_eq!(scheduler.due(&targets, 1_000).len(), 2);
+    scheduler.mark_checked(&targets, 1_000);
+
+    assert!(scheduler.due(&targets, 1_059).is_empty());
+    let due = scheduler.due(&targets, 1_060);
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].id, "npm:busy:latest");
+    assert_eq!(scheduler.next_due_of("npm:default:latest"), Some(1_600));
+    assert_eq!(scheduler.next_wake(&targets, 1_000), 1_060);
+}
+
+#[test]
+fn cron_schedule_is_due_at_next_match_plus_jitter() {
+    // 每天 09:00 UTC; 1_700_000_000 = 2023-11-14T22:13:20Z
+    let schedule = Schedule::cron("0 9 * * *").unwrap();
+    assert_eq!(schedule.next_due(1_700_000_000), 1_700_038_800); // 2023-11-15T09:00:00Z
+
+    let targets = vec![npm_target("daily", Some(schedule.with_jitter(30)))];
+    let mut scheduler = Scheduler::new(600);
+    scheduler.mark_checked(&targets, 1_700_000_000);
+
+    let due = scheduler.next_due_of("npm:daily:latest").unwrap();
+    assert!((1_700_038_800..=1_700_038_830).contains(&due));
+}
+
+#[test]
+fn config_accepts_interval_cron_and_jitter() {
+    let cfg: Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 600
+targets:
+  - type: github_branch
+    repo: "pedroslopez/whatsapp-web.js"
+    branch: "main"
+    interval: 1m
+  - type: npm_latest
+    package: "left-pad"
+    cron: "0 9 * * 1-5"
+    jitter: 5m
+  - type: npm_latest
+    package: "whatsapp-web.js"
+"#,
+    )
+    .unwrap();
+
+    let targets = cfg.to_watch_targets().unwrap();
+    assert_eq!(targets[0].schedule, Some(Schedule::interval(60)));
+    assert_eq!(
+        targets[1].schedule,
+        Some(Schedule::cron("0 9 * * 1-5").unwrap().with_jitter(300))
+    );
+    assert_eq!(targets[2].schedule, None);
+
+    let bad: Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 600
+targets:
+  - type: npm_latest
+    package: "left-pad"
+    cron: "not a cron"
+"#,
+    )
+    .unwrap();
+    assert!(bad.to_watch_targets().is_err());
+}
+
+#[test]
+fn malformed_durations_are_rejected_instead_of_panicking() {
+    let seconds = |s: &str| DurationCfg::Text(s.into()).as_seconds().ok();
+    assert_eq!(seconds("90"), Some(90));
+    assert_eq!(seconds("30s"), Some(30));
+    assert_eq!(seconds(" 2h "), Some(7200));
+    assert_eq!(seconds("1d"), Some(86_400));
+
+    // 多字节结尾 / 没有数字 / 未知单位
+    assert_eq!(seconds("5分"), None);
+    assert_eq!(seconds("分"), None);
+    assert_eq!(seconds("m"), None);
+    assert_eq!(seconds("5w"), None);
+    // 乘上单位后溢出
+    assert_eq!(seconds(&format!("{}d", u64::MAX / 60)), None);
+}
@@ -0,0 +1,227 @@
+use repopulse::application::usecases::{ConcurrencyLimits, HandleEventUseCase, RunOnceUseCase};
+use repopulse::application::{AppResult, EventQuery, EventStore, Notifier, WatchProvider};
+use repopulse::domain::{
+    Event, EventType, Source, VersionBump, VersionPolicy, VersionRange, WatchKind, WatchTarget,
+};
+use repopulse::infrastructure::memory_store::{InMemoryEventStore, InMemoryTargetRepository};
+use repopulse::infrastructure::sqlite_store::SqliteEventStore;
+use repopulse::interfaces::config::Config;
+
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+/// 每次 check 返回下一个版本号
+struct SequenceProvider {
+    values: Mutex<Vec<&'static str>>,
+}
+
+#[async_trait]
+impl WatchProvider for SequenceProvider {
+    async fn check(&self, target: &WatchTarget) -> AppResult<Option<Event>> {
+        let value = self.values.lock().unwrap().remove(0).to_string();
+        let subject = target.kind.subject();
+        Ok(Some(Event {
+            event_id: Event::make_event_id(&EventType::NpmLatest, &subject, &value),
+            event_type: EventType::NpmLatest,
+            source: Source::Npm,
+            subject,
+            old_value: None,
+            new_value: value,
+            occurred_at: None,
+            detected_at: "0s_since_epoch".to_string(),
+            url: None,
+            meta: Default::default(),
+        }))
+    }
+}
+
+#[derive(Clone, Default)]
+struct RecordingNotifier {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    async fn notify(&self, event: &Event) -> AppResult<()> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+fn npm_target(policy: VersionPolicy) -> WatchTarget {
+    WatchTarget {
+        id: "npm:pkg:latest".to_string(),
+        enabled: true,
+        labels: vec![],
+        kind: WatchKind::NpmLatest {
+            package: "pkg".to_string(),
+        },
+        schedule: None,
+        endpoint: None,
+        version_policy: Some(policy),
+    }
+}
+
+async fn run(target: WatchTarget, values: Vec<&'static str>, store: &dyn EventStore) -> Vec<Event> {
+    let runs = values.len();
+    let target_repo = InMemoryTargetRepository::new(vec![target]);
+    let provider = SequenceProvider {
+        values: Mutex::new(values),
+    };
+    let notifier = RecordingNotifier::default();
+    let run_once = RunOnceUseCase {
+        targets: &target_repo,
+        provider: &provider,
+        handle_event: HandleEventUseCase {
+            store,
+            notifier: &notifier,
+            publisher: None,
+            cooldown_seconds: 0,
+        },
+        concurrency: ConcurrencyLimits::default(),
+    };
+    for _ in 0..runs {
+        run_once.execute().await.unwrap();
+    }
+    notifier.events.lock().unwrap().clone()
+}
+
+#[test]
+fn classifies_bumps_and_matches_npm_style_ranges() {
+    assert_eq!(
+        VersionBump::classify("1.2.3", "2.0.0"),
+        Some(VersionBump::Major)
+    );
+    assert_eq!(
+        VersionBump::classify("v1.2", "v1.3"),
+        Some(VersionBump::Minor)
+    );
+    assert_eq!(
+        VersionBump::classify("1.2.3", "1.2.4"),
+        Some(VersionBump::Patch)
+    );
+    assert_eq!(
+        VersionBump::classify("2.0.0-rc.1", "2.0.0"),
+        Some(VersionBump::Prerelease)
+    );
+    assert_eq!(VersionBump::classify("abc", "1.0.0"), None);
+
+    let range = VersionRange::parse(">= 2 < 3").unwrap();
+    assert!(range.matches("v2.4.1"));
+    assert!(!range.matches("3.0.0"));
+    assert!(!range.matches("2.5.0-beta.1"));
+    let range = VersionRange::parse("^1.4 || ^3").unwrap();
+    assert!(range.matches("1.9.0") && range.matches("3.1.0"));
+    assert!(!range.matches("2.0.0"));
+    assert!(VersionRange::parse(">= foo").is_err());
+}
+
+#[tokio::test]
+async fn rejected_versions_are_neither_reported_nor_baselined() {
+    let store = InMemoryEventStore::new();
+    let target = npm_target(VersionPolicy {
+        ignore_prereleases: true,
+        range: Some(">=1 <3".to_string()),
+        notify_on: None,
+    });
+
+    // 2.0.0-rc.1 / 3.0.0 被忽略, 事件从 1.0.0 直接到 2.0.0
+    let events = run(
+        target,
+        vec!["1.0.0", "2.0.0-rc.1", "3.0.0", "2.0.0"],
+        &store,
+    )
+    .await;
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].old_value.as_deref(), Some("1.0.0"));
+    assert_eq!(events[0].new_value, "2.0.0");
+    assert_eq!(
+        events[0].meta.get("bump").map(String::as_str),
+        Some("major")
+    );
+    assert_eq!(
+        store
+            .get_last_value("npm:pkg:latest")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("2.0.0")
+    );
+}
+
+#[tokio::test]
+async fn notify_on_major_still_records_smaller_bumps() {
+    let path = std::env::temp_dir().join(format!("repopulse-bump-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let store = SqliteEventStore::new(&format!("sqlite:{}?mode=rwc", path.display()))
+        .await
+        .unwrap();
+    let target = npm_target(VersionPolicy {
+        notify_on: Some(VersionBump::Major),
+        ..Default::default()
+    });
+
+    let events = run(target, vec!["1.0.0", "1.0.1", "1.1.0", "2.0.0"], &store).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].new_value, "2.0.0");
+
+    // 没通知的变化也有记录, 并能按 bump 过滤
+    let all = store
+        .list_events_filtered(EventQuery {
+            limit: 10,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 3);
+    let minor = store
+        .list_events_filtered(EventQuery {
+            limit: 10,
+            bump: Some(VersionBump::Minor),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(minor.len(), 1);
+    assert_eq!(minor[0].new_value, "1.1.0");
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn config_validates_version_policy() {
+    let cfg: Config = serde_yaml::from_str(
+        r#"
+poll_interval_seconds: 60
+targets:
+  - type: npm_latest
+    package: "pkg"
+    version_policy:
+      ignore_prereleases: true
+      range: ">=2 <3"
+      notify_on: major
+"#,
+    )
+    .unwrap();
+    let targets = cfg.to_watch_targets().unwrap();
+    assert_eq!(
+        targets[0].version_policy,
+        Some(VersionPolicy {
+            ignore_prereleases: true,
+            range: Some(">=2 <3".to_string()),
+            notify_on: Some(VersionBump::Major),
+        })
+    );
+
+    for bad in [
+        // 分支不是版本号
+        "  - type: github_branch\n    repo: o/r\n    branch: main\n    version_policy: { notify_on: major }",
+        "  - type: npm_latest\n    package: p\n    version_policy: { range: \">= nope\" }",
+        "  - type: npm_latest\n    package: p\n    version_policy: { notify_on: huge }",
+    ] {
+        let cfg: Config =
+            serde_yaml::from_str(&format!("poll_interval_seconds: 60\ntargets:\n{bad}\n")).unwrap();
+        assert!(cfg.to_watch_targets().is_err(), "{bad}");
+    }
+}
@@ -0,0 +1,63 @@
+use axum::{Json, Router, routing::get};
+use serde_json::json;
+
+use repopulse::application::WatchProvider;
+use repopulse::domain::{EventType, Source, WatchKind, WatchTarget};
+use repopulse::infrastructure::whatsapp_web_version_provider::WhatsAppWebVersionProvider;
+
+/// 本地起一个假的 check-update 接口，返回固定版本
+async fn spawn_stand_in(version: &'static str) -> String {
+    let app = Router::new().route(
+        "/check-update",
+        get(move || async move {
+            Json(json!({
+                "isBroken": false,
+                "isBelowSoft": false,
+                "isBelowHard": false,
+                "currentVersion": version,
+            }))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}/check-update", addr)
+}
+
+fn target(url: Option<String>) -> WatchTarget {
+    WatchTarget {
+        id: "whatsapp-web:version".to_string(),
+        enabled: true,
+        labels: vec!["whatsapp".to_string()],
+        kind: WatchKind::WhatsAppWebVersion { url },
+        schedule: None,
+        endpoint: None,
+        version_policy: None,
+    }
+}
+
+#[tokio::test]
+async fn should_emit_current_version_from_endpoint() {
+    let url = spawn_stand_in("2.3000.1023204200").await;
+    let provider = WhatsAppWebVersionProvider::with_url(url.clone());
+
+    let event = provider.check(&target(None)).await.unwrap().unwrap();
+
+    assert_eq!(event.event_type, EventType::WhatsAppWebVersion);
+    assert_eq!(event.source, Source::WhatsAppWeb);
+    assert_eq!(event.subject, "whatsapp-web");
+    assert_eq!(event.new_value, "2.3000.1023204200");
+    assert_eq!(event.url.as_deref(), Some(url.as_str()));
+}
+
+#[tokio::test]
+async fn target_url_overrides_provider_default() {
+    let url = spawn_stand_in("2.3000.1").await;
+    let provider = WhatsAppWebVersionProvider::with_url("http://127.0.0.1:9/unreachable");
+
+    let event = provider.check(&target(Some(url))).await.unwrap().unwrap();
+
+    assert_eq!(event.new_value, "2.3000.1");
+}